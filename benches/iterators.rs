@@ -0,0 +1,101 @@
+//! Loops against iterator chains, `unique` against sort-and-dedup and
+//! against `unique_by_key_owned`, and three ways to sum chunks, on the
+//! functions in `iterators::bench`.
+//!
+//! Run with:
+//!
+//! ```text
+//! cargo bench
+//! ```
+
+use criterion::{black_box, BatchSize, BenchmarkId, Criterion};
+use iterators::bench::*;
+
+const LEN: usize = 100_000;
+
+fn loops_vs_chains(c: &mut Criterion) {
+    let numbers = random_numbers(LEN, 1);
+    let strings = number_strings(LEN, 2);
+
+    let mut group = c.benchmark_group("map");
+    group.bench_function("loop", |b| b.iter(|| double_loop(black_box(&numbers))));
+    group.bench_function("iter", |b| b.iter(|| double_iter(black_box(&numbers))));
+    group.finish();
+
+    let mut group = c.benchmark_group("filter_map");
+    group.bench_function("loop", |b| b.iter(|| parse_loop(black_box(&strings))));
+    group.bench_function("iter", |b| b.iter(|| parse_iter(black_box(&strings))));
+    group.finish();
+
+    let mut group = c.benchmark_group("sum");
+    group.bench_function("loop", |b| b.iter(|| sum_loop(black_box(&numbers))));
+    group.bench_function("fold", |b| b.iter(|| sum_fold(black_box(&numbers))));
+    group.bench_function("sum", |b| b.iter(|| sum_iter(black_box(&numbers))));
+    group.finish();
+
+    let mut group = c.benchmark_group("sum_of_even_squares");
+    group.bench_function("loop", |b| {
+        b.iter(|| sum_of_even_squares_loop(black_box(&numbers)))
+    });
+    group.bench_function("iter", |b| {
+        b.iter(|| sum_of_even_squares_iter(black_box(&numbers)))
+    });
+    group.finish();
+}
+
+fn distinct(c: &mut Criterion) {
+    let mut group = c.benchmark_group("distinct");
+    // Few distinct values favour `unique`'s set; many favour sorting.
+    for distinct in [10, 1_000, 100_000] {
+        let repeats = with_duplicates(LEN, distinct, 3);
+        group.bench_with_input(BenchmarkId::new("unique", distinct), &repeats, |b, v| {
+            b.iter(|| distinct_unique(black_box(v)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sort_dedup", distinct),
+            &repeats,
+            |b, v| b.iter(|| distinct_sort_dedup(black_box(v))),
+        );
+    }
+    group.finish();
+
+    // Big records: `unique` clones each new one into its set and hashes
+    // all of it, where `unique_by_key_owned` only keeps the id.
+    let mut group = c.benchmark_group("distinct_records");
+    let records = records(10_000, 1_000, 5);
+    group.bench_function("unique", |b| {
+        b.iter_batched(
+            || records.clone(),
+            distinct_records_unique,
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("unique_by_key_owned", |b| {
+        b.iter_batched(
+            || records.clone(),
+            distinct_records_by_key,
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn chunking(c: &mut Criterion) {
+    let numbers = random_numbers(LEN, 4);
+    let mut group = c.benchmark_group("chunk_sums");
+    for size in [4, 64, 1024] {
+        group.bench_with_input(BenchmarkId::new("loop", size), &size, |b, &n| {
+            b.iter(|| chunk_sums_loop(black_box(&numbers), n))
+        });
+        group.bench_with_input(BenchmarkId::new("slice", size), &size, |b, &n| {
+            b.iter(|| chunk_sums_slice(black_box(&numbers), n))
+        });
+        group.bench_with_input(BenchmarkId::new("fold", size), &size, |b, &n| {
+            b.iter(|| chunk_sums_fold(black_box(&numbers), n))
+        });
+    }
+    group.finish();
+}
+
+criterion::criterion_group!(benches, loops_vs_chains, distinct, chunking);
+criterion::criterion_main!(benches);
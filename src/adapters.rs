@@ -0,0 +1,67 @@
+//! Reusable iterator adapters built with the recipe from `i6_iterator_adapters`:
+//! a struct for the adapter, an `Iterator` impl, an extension trait, and a
+//! blanket implementation of that trait for every iterator.
+
+pub mod cached;
+pub mod context;
+pub mod dedup;
+pub mod describe;
+pub mod downsample;
+pub mod enumerate_from;
+#[cfg(feature = "std")]
+pub mod instrument;
+pub mod interrupt;
+pub mod merge_join;
+#[cfg(feature = "std")]
+pub mod par_map;
+pub mod peek_n;
+#[cfg(feature = "std")]
+pub mod prefetch;
+pub mod probe;
+#[cfg(feature = "std")]
+pub mod progress;
+pub mod retry;
+pub mod rle;
+pub mod split_when;
+pub mod strict;
+pub mod tap;
+#[cfg(feature = "std")]
+pub mod throttle;
+#[cfg(feature = "std")]
+pub mod timeout;
+pub mod trace;
+pub mod unique;
+
+pub use cached::{CachedExt, Replay};
+pub use context::{Context, ContextExt, WithContext};
+pub use dedup::{Dedup, DedupExt, DedupWithCount};
+pub use describe::{DescribeExt, Described, StageInfo};
+pub use downsample::{Downsample, DownsampleExt, PartialGroup};
+pub use enumerate_from::{EnumerateFromExt, EnumerateStep, IndexStep};
+#[cfg(feature = "std")]
+pub use instrument::{InstrumentExt, Instrumented, IterMetrics};
+pub use interrupt::{InterruptExt, UntilInterrupted};
+pub use merge_join::{MergeJoinBy, MergeJoinExt};
+#[cfg(feature = "std")]
+pub use par_map::{ParMap, ParMapExt};
+pub use peek_n::{PeekN, PeekNExt};
+#[cfg(feature = "std")]
+pub use prefetch::{Prefetch, PrefetchExt};
+pub use probe::{Event, Probe, ProbeExt, ProbeLog};
+#[cfg(feature = "std")]
+pub use progress::{Progress, ProgressExt, ProgressReporter, ProgressUpdate, StderrBar};
+pub use retry::{RetryExt, RetryMap};
+pub use rle::{Rld, Rle, RleExt};
+pub use split_when::{Delimiter, SplitBetween, SplitWhen, SplitWhenExt};
+pub use strict::{ConsumeOnce, Strict, StrictExt};
+pub use tap::{Dbg, Tap, TapExt};
+#[cfg(feature = "std")]
+pub use throttle::{Clock, SystemClock, Throttle, ThrottleExt};
+#[cfg(feature = "std")]
+pub use timeout::{Timeout, TimeoutExt, WithTimeout};
+#[cfg(feature = "std")]
+pub use trace::Print;
+pub use trace::{Trace, TraceExt, TraceSink};
+#[cfg(feature = "std")]
+pub use unique::UniqueByKey;
+pub use unique::{Unique, UniqueExt, UniqueSorted};
@@ -0,0 +1,204 @@
+//! `cached()` remembers every item it pulls from an iterator, so that the
+//! items can be gone over again. It yields a `Replay` handle, and each
+//! handle's `replay()` makes another that starts again from the first
+//! item: it goes through the items cached so far, and then pulls new ones
+//! from the source, caching them for the other handles as it goes.
+//!
+//! Each item is pulled from the source once, however many handles there
+//! are and in whatever order they are used, which makes this `tee` for any
+//! number of copies. Nothing is ever evicted from the cache, though, so it
+//! grows to everything the furthest handle has seen. The handles share the
+//! cache through an `Rc`, so they all stay on one thread.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+struct Shared<I: Iterator> {
+    orig: I,
+    cache: Vec<I::Item>,
+    // Whether `orig` has ended, so that it isn't asked again.
+    done: bool,
+}
+
+// Step 1: Define a struct for the adapter.
+pub struct Replay<I: Iterator> {
+    shared: Rc<RefCell<Shared<I>>>,
+    // The index in the cache of the next item this handle yields.
+    index: usize,
+}
+
+impl<I: Iterator> Replay<I> {
+    /// A handle that starts again from the first item.
+    pub fn replay(&self) -> Self {
+        Replay {
+            shared: Rc::clone(&self.shared),
+            index: 0,
+        }
+    }
+
+    /// How many items have been pulled from the source so far.
+    pub fn cached_len(&self) -> usize {
+        self.shared.borrow().cache.len()
+    }
+}
+
+/// A handle at the same place as this one.
+impl<I: Iterator> Clone for Replay<I> {
+    fn clone(&self) -> Self {
+        Replay {
+            shared: Rc::clone(&self.shared),
+            index: self.index,
+        }
+    }
+}
+
+impl<I> fmt::Debug for Replay<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shared = self.shared.borrow();
+        f.debug_struct("Replay")
+            .field("orig", &shared.orig)
+            .field("cache", &shared.cache)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I> Iterator for Replay<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut shared = self.shared.borrow_mut();
+        if self.index == shared.cache.len() {
+            if shared.done {
+                return None;
+            }
+            match shared.orig.next() {
+                Some(item) => shared.cache.push(item),
+                None => {
+                    shared.done = true;
+                    return None;
+                }
+            }
+        }
+        let item = shared.cache[self.index].clone();
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let shared = self.shared.borrow();
+        let cached = shared.cache.len() - self.index;
+        if shared.done {
+            return (cached, Some(cached));
+        }
+        let (lower, upper) = shared.orig.size_hint();
+        (
+            lower.saturating_add(cached),
+            upper.and_then(|n| n.checked_add(cached)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for Replay<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Clone,
+{
+}
+
+impl<I> FusedIterator for Replay<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait CachedExt: Iterator {
+    /// Yields the items, keeping them for the handles that `replay()`
+    /// makes.
+    fn cached(self) -> Replay<Self>
+    where
+        Self: Sized,
+    {
+        Replay {
+            shared: Rc::new(RefCell::new(Shared {
+                orig: self,
+                cache: Vec::new(),
+                done: false,
+            })),
+            index: 0,
+        }
+    }
+}
+
+impl<I: Iterator> CachedExt for I {}
+
+#[test]
+fn cached_replays_from_the_start() {
+    let mut first = (0..5).cached();
+    assert_eq!(first.next(), Some(0));
+    assert_eq!(first.next(), Some(1));
+    assert_eq!(first.cached_len(), 2);
+
+    let second = first.replay();
+    assert_eq!(second.len(), 5);
+    assert_iter_eq!(second, 0..5);
+    assert_iter_eq!(first, 2..5);
+    assert_iter_eq!(
+        core::iter::empty::<u8>().cached().replay(),
+        core::iter::empty()
+    );
+}
+
+#[test]
+fn cached_pulls_each_item_once() {
+    let pulled = core::cell::Cell::new(0);
+    let a = (0..4).inspect(|_| pulled.set(pulled.get() + 1)).cached();
+    let (b, c) = (a.replay(), a.replay());
+    assert_iter_eq!(a, 0..4);
+    assert_iter_eq!(b, 0..4);
+    assert_iter_eq!(c, 0..4);
+    assert_eq!(pulled.get(), 4);
+}
+
+#[test]
+fn cached_with_three_interleaved_handles() {
+    let pulled = core::cell::Cell::new(0);
+    let a = "abcdef"
+        .chars()
+        .inspect(|_| pulled.set(pulled.get() + 1))
+        .cached();
+    let (b, c) = (a.replay(), a.replay());
+    let mut handles = [a, b, c];
+
+    // Whichever handle is furthest ahead pulls from the source; the others
+    // catch up from the cache.
+    let mut seen = alloc::string::String::new();
+    for i in [1, 1, 0, 2, 1, 2, 0] {
+        seen.extend(handles[i].next());
+    }
+    assert_eq!(seen, "abaacbb");
+    assert_eq!(pulled.get(), 3);
+
+    // A clone carries on from where its original is.
+    let [a, b, c] = handles;
+    let d = b.clone();
+    assert_iter_eq!(d, "def".chars());
+    assert_iter_eq!(c, "cdef".chars());
+    assert_iter_eq!(a, "cdef".chars());
+    assert_iter_eq!(b, "def".chars());
+    assert_eq!(pulled.get(), 6);
+}
@@ -0,0 +1,181 @@
+//! `with_context()` yields each item together with its neighbours, as a
+//! `Context { prev, current, next }`: `prev` is `None` for the first item
+//! and `next` for the last. That is what smoothing (averaging each value
+//! with the ones on either side), diffing and tokenizing want to look at.
+//!
+//! An iterator can't hand out references into itself, so the neighbours
+//! are clones: each item is cloned once as the `next` of the item before
+//! it and once as the `prev` of the one after. To know `next`, the
+//! adapter reads one item ahead of what it yields.
+
+use core::iter::FusedIterator;
+
+/// An item with the ones before and after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Context<T> {
+    pub prev: Option<T>,
+    pub current: T,
+    pub next: Option<T>,
+}
+
+impl<T> Context<T> {
+    /// `(prev, current, next)`, borrowed.
+    pub fn as_refs(&self) -> (Option<&T>, &T, Option<&T>) {
+        (self.prev.as_ref(), &self.current, self.next.as_ref())
+    }
+}
+
+impl<T> From<Context<T>> for (Option<T>, T, Option<T>) {
+    fn from(context: Context<T>) -> Self {
+        (context.prev, context.current, context.next)
+    }
+}
+
+// Step 1: Define a struct for the adapter.
+#[derive(Debug, Clone)]
+pub struct WithContext<I: Iterator> {
+    orig: I,
+    // The item last yielded, to be the next one's `prev`.
+    prev: Option<I::Item>,
+    // The item after the one last yielded, read ahead to be its `next`.
+    ahead: Option<I::Item>,
+    // Whether anything has been read yet; after that, `ahead` being `None`
+    // means the end.
+    started: bool,
+}
+
+// Step 2: Implement `Iterator`.
+impl<I> Iterator for WithContext<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Context<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = if self.started {
+            self.ahead.take()?
+        } else {
+            self.started = true;
+            self.orig.next()?
+        };
+        self.ahead = self.orig.next();
+        Some(Context {
+            prev: self.prev.replace(current.clone()),
+            next: self.ahead.clone(),
+            current,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.started && self.ahead.is_none() {
+            return (0, Some(0));
+        }
+        let ahead = usize::from(self.ahead.is_some());
+        let (lower, upper) = self.orig.size_hint();
+        (
+            lower.saturating_add(ahead),
+            upper.and_then(|n| n.checked_add(ahead)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for WithContext<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Clone,
+{
+}
+
+impl<I> FusedIterator for WithContext<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait ContextExt: Iterator {
+    /// Yields each item with the items before and after it.
+    fn with_context(self) -> WithContext<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        WithContext {
+            orig: self,
+            prev: None,
+            ahead: None,
+            started: false,
+        }
+    }
+}
+
+impl<I: Iterator> ContextExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn with_context_at_the_ends() {
+    let triples: Vec<_> = "abc"
+        .chars()
+        .with_context()
+        .map(<(_, _, _)>::from)
+        .collect();
+    assert_eq!(
+        triples,
+        [
+            (None, 'a', Some('b')),
+            (Some('a'), 'b', Some('c')),
+            (Some('b'), 'c', None),
+        ]
+    );
+
+    // A lone item has no neighbours at all.
+    let only = core::iter::once(7).with_context().next().unwrap();
+    assert_eq!(only.as_refs(), (None, &7, None));
+    assert_eq!(core::iter::empty::<u8>().with_context().next(), None);
+}
+
+#[test]
+fn with_context_reads_one_ahead() {
+    let pulled = core::cell::Cell::new(0);
+    let mut it = (0..5)
+        .inspect(|_| pulled.set(pulled.get() + 1))
+        .with_context();
+    assert_eq!(it.len(), 5);
+    it.next();
+    assert_eq!(pulled.get(), 2);
+    assert_eq!(it.len(), 4);
+    it.by_ref().for_each(drop);
+    assert_eq!(it.len(), 0);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn with_context_for_smoothing_and_diffing() {
+    // A three-point moving average, with the ends averaged over what there is.
+    let readings = [3.0, 9.0, 6.0, 0.0];
+    let smoothed: Vec<f64> = readings
+        .into_iter()
+        .with_context()
+        .map(|c| {
+            let window = [c.prev, Some(c.current), c.next];
+            let present = window.iter().flatten();
+            present.clone().sum::<f64>() / present.count() as f64
+        })
+        .collect();
+    assert_eq!(smoothed, [6.0, 6.0, 5.0, 3.0]);
+
+    // The lines that differ from the line before them.
+    let lines = ["a", "a", "b", "b", "a"];
+    let changes: Vec<usize> = lines
+        .iter()
+        .with_context()
+        .enumerate()
+        .filter(|(_, c)| c.prev != Some(c.current))
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(changes, [0, 2, 4]);
+}
@@ -0,0 +1,169 @@
+//! `dedup()` drops each item equal to the one before it, like `Vec::dedup`
+//! for any iterator. `dedup_with_count()` also says how many there were,
+//! as `(count, item)` pairs, like `uniq -c`. Both group the items with the
+//! same `Runs` as `rle()`, so like it they read an item past each run to
+//! find its end.
+
+use super::rle::Runs;
+use core::fmt;
+use core::iter::FusedIterator;
+
+// Step 1: Define a struct for each adapter.
+pub struct Dedup<I: Iterator> {
+    runs: Runs<I>,
+}
+
+pub struct DedupWithCount<I: Iterator> {
+    runs: Runs<I>,
+}
+
+impl<I> Clone for Dedup<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Dedup {
+            runs: self.runs.clone(),
+        }
+    }
+}
+
+impl<I> Clone for DedupWithCount<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        DedupWithCount {
+            runs: self.runs.clone(),
+        }
+    }
+}
+
+impl<I> fmt::Debug for Dedup<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dedup").field("runs", &self.runs).finish()
+    }
+}
+
+impl<I> fmt::Debug for DedupWithCount<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DedupWithCount")
+            .field("runs", &self.runs)
+            .finish()
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I> Iterator for Dedup<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runs.next_run().map(|(item, _)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.runs.size_hint()
+    }
+}
+
+impl<I> Iterator for DedupWithCount<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runs.next_run().map(|(item, count)| (count, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.runs.size_hint()
+    }
+}
+
+impl<I> FusedIterator for Dedup<I>
+where
+    I: FusedIterator,
+    I::Item: PartialEq,
+{
+}
+
+impl<I> FusedIterator for DedupWithCount<I>
+where
+    I: FusedIterator,
+    I::Item: PartialEq,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait DedupExt: Iterator {
+    /// Yields the first item of each run of equal items.
+    fn dedup(self) -> Dedup<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq,
+    {
+        Dedup {
+            runs: Runs::new(self),
+        }
+    }
+
+    /// Yields how long each run of equal items is, and its first item.
+    fn dedup_with_count(self) -> DedupWithCount<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq,
+    {
+        DedupWithCount {
+            runs: Runs::new(self),
+        }
+    }
+}
+
+impl<I: Iterator> DedupExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn dedup_drops_repeats() {
+    let mut v = alloc::vec![1, 1, 2, 3, 3, 3, 1];
+    assert_iter_eq!(v.iter().dedup(), [&1, &2, &3, &1]);
+    v.dedup();
+    assert_eq!(v, [1, 2, 3, 1]);
+    assert_eq!(core::iter::empty::<u8>().dedup().next(), None);
+}
+
+#[test]
+fn dedup_with_count_on_alternating_input() {
+    // Nothing repeats, so every count is 1.
+    let counts: Vec<_> = "abab".chars().dedup_with_count().collect();
+    assert_eq!(counts, [(1, 'a'), (1, 'b'), (1, 'a'), (1, 'b')]);
+    assert_eq!((0..100).map(|n| n % 2).dedup_with_count().count(), 100);
+}
+
+#[test]
+fn dedup_with_count_on_uniform_input() {
+    assert_iter_eq!(core::iter::repeat_n("x", 4).dedup_with_count(), [(4, "x")]);
+    assert_iter_eq!(core::iter::once(0).dedup_with_count(), [(1, 0)]);
+
+    // Like `uniq -c` on lines.
+    let text = "b\nb\na\nb\nb\nb";
+    let counted: Vec<_> = text.lines().dedup_with_count().collect();
+    assert_eq!(counted, [(2, "b"), (1, "a"), (3, "b")]);
+}
@@ -0,0 +1,322 @@
+//! `described(source)` wraps an iterator so that the adapters put on it
+//! write down what they are as they go: `Described` has a method for most
+//! of the crate's adapters (and for `map`, `filter`, `skip` and `take`),
+//! each of which puts the adapter on as usual and also records its name
+//! and parameters. `to_dot()` then draws the chain as a Graphviz digraph,
+//! one box per stage from the source on the left, ready for a slide:
+//!
+//! ```text
+//! let chain = (1..=20).described("1..=20").dedup().enumerate_from(1).take(5);
+//! std::fs::write("chain.dot", chain.to_dot())?;   // dot -Tsvg chain.dot
+//! ```
+//!
+//! Closures can't be printed, so a stage that takes one shows only its
+//! name. Any other adapter can be recorded with `stage(name, params, f)`.
+//! The wrapper is an iterator itself and yields what the chain yields.
+
+use super::{
+    CachedExt, Dedup, DedupExt, DedupWithCount, EnumerateFromExt, EnumerateStep, IndexStep,
+    InterruptExt, Replay, Rle, RleExt, SplitWhen, SplitWhenExt, Strict, StrictExt, Tap, TapExt,
+    UntilInterrupted,
+};
+#[cfg(feature = "std")]
+use super::{SystemClock, Throttle, ThrottleExt, Unique, UniqueExt};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::{self, Write};
+use core::iter::{Filter, FusedIterator, Map, Skip, Take};
+use core::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::hash::Hash;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+/// One stage of a described chain: `take` with parameters `"5"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageInfo {
+    pub name: &'static str,
+    pub params: String,
+}
+
+impl fmt::Display for StageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.params.is_empty() {
+            f.write_str(self.name)
+        } else {
+            write!(f, "{}({})", self.name, self.params)
+        }
+    }
+}
+
+// Step 1: Define a struct for the adapter.
+#[derive(Debug, Clone)]
+pub struct Described<I> {
+    orig: I,
+    source: String,
+    stages: Vec<StageInfo>,
+}
+
+impl<I> Described<I> {
+    /// What the chain started from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The stages so far, from the source outwards.
+    pub fn stages(&self) -> &[StageInfo] {
+        &self.stages
+    }
+
+    /// The chain, without the description.
+    pub fn into_inner(self) -> I {
+        self.orig
+    }
+
+    /// Puts on `f(iter)`, recorded as the stage `name(params)`; for
+    /// adapters that `Described` has no method for.
+    pub fn stage<J, F>(self, name: &'static str, params: impl Into<String>, f: F) -> Described<J>
+    where
+        F: FnOnce(I) -> J,
+    {
+        let mut stages = self.stages;
+        stages.push(StageInfo {
+            name,
+            params: params.into(),
+        });
+        Described {
+            orig: f(self.orig),
+            source: self.source,
+            stages,
+        }
+    }
+
+    /// The chain as a Graphviz digraph, drawn left to right.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pipeline {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=box];\n");
+        let labels = core::iter::once(self.source.clone())
+            .chain(self.stages.iter().map(|stage| stage.to_string()));
+        for (i, label) in labels.enumerate() {
+            let _ = writeln!(dot, "    n{i} [label=\"{}\"];", escape(&label));
+        }
+        for i in 0..self.stages.len() {
+            let _ = writeln!(dot, "    n{i} -> n{};", i + 1);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Quotes and backslashes would end a DOT string early.
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// The standard adapters the crate's lessons lean on most.
+impl<I: Iterator> Described<I> {
+    pub fn map<B, F>(self, f: F) -> Described<Map<I, F>>
+    where
+        F: FnMut(I::Item) -> B,
+    {
+        self.stage("map", "", |it| it.map(f))
+    }
+
+    pub fn filter<P>(self, pred: P) -> Described<Filter<I, P>>
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        self.stage("filter", "", |it| it.filter(pred))
+    }
+
+    pub fn skip(self, n: usize) -> Described<Skip<I>> {
+        self.stage("skip", format!("{n}"), |it| it.skip(n))
+    }
+
+    pub fn take(self, n: usize) -> Described<Take<I>> {
+        self.stage("take", format!("{n}"), |it| it.take(n))
+    }
+}
+
+// The crate's own adapters.
+impl<I: Iterator> Described<I> {
+    pub fn cached(self) -> Described<Replay<I>> {
+        self.stage("cached", "", CachedExt::cached)
+    }
+
+    pub fn dedup(self) -> Described<Dedup<I>>
+    where
+        I::Item: PartialEq,
+    {
+        self.stage("dedup", "", DedupExt::dedup)
+    }
+
+    pub fn dedup_with_count(self) -> Described<DedupWithCount<I>>
+    where
+        I::Item: PartialEq,
+    {
+        self.stage("dedup_with_count", "", DedupExt::dedup_with_count)
+    }
+
+    pub fn enumerate_from<T>(self, start: T) -> Described<EnumerateStep<I, T>>
+    where
+        T: IndexStep + fmt::Debug,
+    {
+        self.stage("enumerate_from", format!("{start:?}"), |it| {
+            it.enumerate_from(start)
+        })
+    }
+
+    pub fn enumerate_step<T>(self, start: T, step: T) -> Described<EnumerateStep<I, T>>
+    where
+        T: IndexStep + fmt::Debug,
+    {
+        self.stage("enumerate_step", format!("{start:?}, {step:?}"), |it| {
+            it.enumerate_step(start, step)
+        })
+    }
+
+    pub fn rle(self) -> Described<Rle<I>>
+    where
+        I::Item: PartialEq,
+    {
+        self.stage("rle", "", RleExt::rle)
+    }
+
+    pub fn split_when<P>(self, pred: P) -> Described<SplitWhen<I, P>>
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        self.stage("split_when", "", |it| it.split_when(pred))
+    }
+
+    pub fn strict(self) -> Described<Strict<I>> {
+        self.stage("strict", "", StrictExt::strict)
+    }
+
+    pub fn tap<F>(self, f: F) -> Described<Tap<I, F>>
+    where
+        F: FnMut(&I::Item),
+    {
+        self.stage("tap", "", |it| it.tap(f))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn unique(self) -> Described<Unique<I, HashSet<I::Item>>>
+    where
+        I::Item: Clone + Hash + Eq,
+    {
+        self.stage("unique", "", UniqueExt::unique)
+    }
+
+    pub fn until_interrupted<S>(self, flag: S) -> Described<UntilInterrupted<I, S>>
+    where
+        S: Borrow<AtomicBool>,
+    {
+        self.stage("until_interrupted", "", |it| it.until_interrupted(flag))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn throttle(self, min_interval: Duration) -> Described<Throttle<I, SystemClock>> {
+        self.stage("throttle", format!("{min_interval:?}"), |it| {
+            it.throttle(min_interval)
+        })
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I: Iterator> Iterator for Described<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.orig.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Described<I> {}
+
+impl<I: FusedIterator> FusedIterator for Described<I> {}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait DescribeExt: Iterator {
+    /// Starts a described chain, with `source` as the name of this
+    /// iterator.
+    fn described(self, source: impl Into<String>) -> Described<Self>
+    where
+        Self: Sized,
+    {
+        Described {
+            orig: self,
+            source: source.into(),
+            stages: Vec::new(),
+        }
+    }
+}
+
+impl<I: Iterator> DescribeExt for I {}
+
+#[test]
+fn described_records_each_stage() {
+    let chain = "aabccc"
+        .chars()
+        .described("\"aabccc\".chars()")
+        .dedup_with_count()
+        .enumerate_from(1u8)
+        .map(|(i, (n, c))| (i, n, c));
+    assert_eq!(chain.source(), "\"aabccc\".chars()");
+    let names: Vec<String> = chain.stages().iter().map(|s| s.to_string()).collect();
+    assert_eq!(names, ["dedup_with_count", "enumerate_from(1)", "map"]);
+    // It runs as the undescribed chain does.
+    assert_iter_eq!(chain, [(1, 2, 'a'), (2, 1, 'b'), (3, 3, 'c')]);
+}
+
+#[test]
+fn described_three_stage_pipeline_to_dot() {
+    let chain = [3, 3, 1, 4, 4, 1, 5]
+        .into_iter()
+        .described("digits")
+        .dedup()
+        .filter(|n| *n > 1)
+        .take(3);
+    assert_eq!(
+        chain.to_dot(),
+        "digraph pipeline {
+    rankdir=LR;
+    node [shape=box];
+    n0 [label=\"digits\"];
+    n1 [label=\"dedup\"];
+    n2 [label=\"filter\"];
+    n3 [label=\"take(3)\"];
+    n0 -> n1;
+    n1 -> n2;
+    n2 -> n3;
+}
+"
+    );
+    assert_iter_eq!(chain, [3, 4, 5]);
+}
+
+#[test]
+fn described_escapes_labels_and_takes_any_stage() {
+    let chain = (0..4)
+        .described(r#"say "hi" \o/"#)
+        .stage("step_by", "2", |it| it.step_by(2));
+    let dot = chain.to_dot();
+    assert!(dot.contains(r#"n0 [label="say \"hi\" \\o/"];"#), "{dot}");
+    assert!(dot.contains(r#"n1 [label="step_by(2)"];"#), "{dot}");
+    assert_iter_eq!(chain.into_inner(), [0, 2]);
+
+    // A source alone is one node and no edges.
+    assert!(!core::iter::empty::<u8>()
+        .described("nothing")
+        .to_dot()
+        .contains("->"));
+}
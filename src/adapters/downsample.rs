@@ -0,0 +1,233 @@
+//! `downsample(n, agg)` cuts the items into groups of `n` and yields one
+//! value per group, `agg` of the group as a slice. A long stream of
+//! readings becomes one point per `n` of them, few enough to plot.
+//!
+//! `agg` is any closure from a slice, or one of the functions here:
+//! `mean`, `min`, `max`, `first` and `last`. The last group is short when
+//! the length isn't a multiple of `n`; by default it is aggregated like the
+//! others, and `partial(PartialGroup::Drop)` drops it instead, so that
+//! every value stands for exactly `n` items.
+//!
+//! Each group is gathered into one buffer, kept from group to group, so
+//! `agg` never sees an empty slice and, once the buffer has grown to the
+//! size of a group, nothing more is allocated. It grows as items come, so
+//! a huge `n` on a short stream only takes room for the items there are.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// What `downsample` does with a last group that has fewer than `n`
+/// items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartialGroup {
+    /// Aggregates it like any other group.
+    #[default]
+    Keep,
+    /// Leaves it out.
+    Drop,
+}
+
+/// The mean of the group.
+pub fn mean<T: Copy + Into<f64>>(group: &[T]) -> f64 {
+    group.iter().map(|&x| x.into()).sum::<f64>() / group.len() as f64
+}
+
+/// The smallest item of the group; the first of them, if several tie.
+pub fn min<T: Copy + PartialOrd>(group: &[T]) -> T {
+    group
+        .iter()
+        .copied()
+        .reduce(|min, x| if x < min { x } else { min })
+        .expect("downsample never makes an empty group")
+}
+
+/// The largest item of the group; the first of them, if several tie.
+pub fn max<T: Copy + PartialOrd>(group: &[T]) -> T {
+    group
+        .iter()
+        .copied()
+        .reduce(|max, x| if x > max { x } else { max })
+        .expect("downsample never makes an empty group")
+}
+
+pub fn first<T: Clone>(group: &[T]) -> T {
+    group[0].clone()
+}
+
+pub fn last<T: Clone>(group: &[T]) -> T {
+    group[group.len() - 1].clone()
+}
+
+// Step 1: Define a struct for the adapter.
+pub struct Downsample<I: Iterator, F> {
+    orig: I,
+    n: usize,
+    agg: F,
+    partial: PartialGroup,
+    // The group being gathered; empty between calls to `next`.
+    group: Vec<I::Item>,
+}
+
+impl<I: Iterator, F> Downsample<I, F> {
+    /// Sets what happens to a short last group.
+    pub fn partial(mut self, partial: PartialGroup) -> Self {
+        self.partial = partial;
+        self
+    }
+
+    // How many groups `items` more items make.
+    fn groups(&self, items: usize) -> usize {
+        match self.partial {
+            PartialGroup::Keep => items.div_ceil(self.n),
+            PartialGroup::Drop => items / self.n,
+        }
+    }
+}
+
+impl<I, F> Clone for Downsample<I, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Downsample {
+            orig: self.orig.clone(),
+            n: self.n,
+            agg: self.agg.clone(),
+            partial: self.partial,
+            group: self.group.clone(),
+        }
+    }
+}
+
+impl<I: Iterator + fmt::Debug, F> fmt::Debug for Downsample<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Downsample")
+            .field("orig", &self.orig)
+            .field("n", &self.n)
+            .field("partial", &self.partial)
+            .finish_non_exhaustive()
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, F, B> Iterator for Downsample<I, F>
+where
+    I: Iterator,
+    F: FnMut(&[I::Item]) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<B> {
+        self.group.extend(self.orig.by_ref().take(self.n));
+        let short = self.group.len() < self.n;
+        let value = if self.group.is_empty() || (short && self.partial == PartialGroup::Drop) {
+            None
+        } else {
+            Some((self.agg)(&self.group))
+        };
+        self.group.clear();
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.orig.size_hint();
+        (self.groups(lower), upper.map(|n| self.groups(n)))
+    }
+}
+
+impl<I, F, B> ExactSizeIterator for Downsample<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&[I::Item]) -> B,
+{
+}
+
+impl<I, F, B> FusedIterator for Downsample<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&[I::Item]) -> B,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait DownsampleExt: Iterator {
+    /// Yields `agg` of each group of `n` items.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    fn downsample<F, B>(self, n: usize, agg: F) -> Downsample<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&[Self::Item]) -> B,
+    {
+        assert!(n > 0, "downsample: groups of 0 items would never end");
+        Downsample {
+            orig: self,
+            n,
+            agg,
+            partial: PartialGroup::Keep,
+            group: Vec::new(),
+        }
+    }
+}
+
+impl<I: Iterator> DownsampleExt for I {}
+
+#[test]
+fn downsample_with_each_aggregate() {
+    let readings = [3, 9, 6, 0, 2, 2, 7, 1];
+    assert_iter_eq!(readings.into_iter().downsample(4, mean), [4.5, 3.0]);
+    assert_iter_eq!(readings.into_iter().downsample(3, min), [3, 0, 1]);
+    assert_iter_eq!(readings.into_iter().downsample(3, max), [9, 2, 7]);
+    assert_iter_eq!(readings.into_iter().downsample(3, first), [3, 0, 7]);
+    assert_iter_eq!(readings.into_iter().downsample(3, last), [6, 2, 1]);
+
+    // Any closure over the group.
+    let spreads = readings.into_iter().downsample(2, |g: &[i32]| {
+        g.iter().max().unwrap() - g.iter().min().unwrap()
+    });
+    assert_iter_eq!(spreads, [6, 6, 0, 6]);
+}
+
+#[test]
+fn downsample_partial_groups() {
+    let it = (1..=7u8).downsample(3, mean);
+    assert_eq!(it.len(), 3);
+    assert_iter_eq!(it, [2.0, 5.0, 7.0]);
+
+    let it = (1..=7u8).downsample(3, mean).partial(PartialGroup::Drop);
+    assert_eq!(it.len(), 2);
+    assert_iter_eq!(it, [2.0, 5.0]);
+
+    // With no partial group, the policy makes no difference.
+    assert_iter_eq!(
+        (1..=6u8).downsample(3, max).partial(PartialGroup::Drop),
+        (1..=6u8).downsample(3, max)
+    );
+    // A stream shorter than one group.
+    assert_iter_eq!((0..2).downsample(5, first), [0]);
+    assert_iter_eq!((0..2).downsample(5, first).partial(PartialGroup::Drop), []);
+    assert_eq!(core::iter::empty::<u8>().downsample(5, first).next(), None);
+    // One group of everything, however many items that turns out to be.
+    assert_iter_eq!((1..=4u8).downsample(usize::MAX, mean), [2.5]);
+}
+
+#[test]
+fn downsample_a_long_stream_for_plotting() {
+    use crate::sources::Linspace;
+
+    // A thousand points of a ramp, down to ten.
+    let points = Linspace::new(0.0, 999.0, 1000).downsample(100, mean);
+    assert_eq!(points.len(), 10);
+    assert_iter_eq!(points, (0..10).map(|i| i as f64 * 100.0 + 49.5));
+}
+
+#[test]
+#[should_panic(expected = "downsample: groups of 0 items would never end")]
+fn downsample_by_zero() {
+    let _ = (0..4).downsample(0, first);
+}
@@ -0,0 +1,173 @@
+//! `enumerate_from(start)` is `enumerate` counting from `start` instead of
+//! 0, for 1-based line numbers and the like; `enumerate_step(start, step)`
+//! also chooses how far apart the indices are. The index can be any
+//! `IndexStep` type: a different integer type than `usize`, or a newtype
+//! for the kind of index it is.
+//!
+//! An index is only worked out when its item is, so counting up to the
+//! type's maximum is fine; an index past it panics.
+
+use core::iter::FusedIterator;
+
+/// An index type for `enumerate_from` and `enumerate_step`.
+pub trait IndexStep: Copy {
+    /// The step from one index to the next in `enumerate_from`.
+    const ONE: Self;
+
+    /// `self + step`, or `None` if it doesn't fit.
+    fn checked_step(self, step: Self) -> Option<Self>;
+}
+
+macro_rules! impl_index_step {
+    ($($t:ty),*) => {
+        $(
+            impl IndexStep for $t {
+                const ONE: Self = 1;
+
+                fn checked_step(self, step: Self) -> Option<Self> {
+                    self.checked_add(step)
+                }
+            }
+        )*
+    };
+}
+
+impl_index_step!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Step 1: Define a struct for the custom adapter.
+#[derive(Debug, Clone)]
+pub struct EnumerateStep<I, T> {
+    orig: I,
+    // The index of the last item yielded, or of the first one to come if
+    // `started` is false.
+    index: T,
+    step: T,
+    started: bool,
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, T> Iterator for EnumerateStep<I, T>
+where
+    I: Iterator,
+    T: IndexStep,
+{
+    type Item = (T, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        if self.started {
+            self.index = self
+                .index
+                .checked_step(self.step)
+                .expect("enumerate_step: the index overflowed");
+        }
+        self.started = true;
+        Some((self.index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I, T> ExactSizeIterator for EnumerateStep<I, T>
+where
+    I: ExactSizeIterator,
+    T: IndexStep,
+{
+}
+
+impl<I, T> FusedIterator for EnumerateStep<I, T>
+where
+    I: FusedIterator,
+    T: IndexStep,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait EnumerateFromExt: Iterator {
+    /// Pairs each item with its index, counting up from `start`.
+    fn enumerate_from<T: IndexStep>(self, start: T) -> EnumerateStep<Self, T>
+    where
+        Self: Sized,
+    {
+        self.enumerate_step(start, T::ONE)
+    }
+
+    /// Pairs each item with its index, starting at `start` and going up by
+    /// `step`, which may be negative.
+    fn enumerate_step<T: IndexStep>(self, start: T, step: T) -> EnumerateStep<Self, T>
+    where
+        Self: Sized,
+    {
+        EnumerateStep {
+            orig: self,
+            index: start,
+            step,
+            started: false,
+        }
+    }
+}
+
+impl<I: Iterator> EnumerateFromExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn enumerate_from_counts_from_start() {
+    let lines: Vec<_> = "one\ntwo\nthree".lines().enumerate_from(1).collect();
+    assert_eq!(lines, [(1, "one"), (2, "two"), (3, "three")]);
+
+    let mut it = ['a', 'b'].into_iter().enumerate_from(-1i8);
+    assert_eq!(it.len(), 2);
+    assert_eq!(it.next(), Some((-1, 'a')));
+    assert_eq!(it.next(), Some((0, 'b')));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn enumerate_step_spaces_the_indices() {
+    assert_iter_eq!(
+        "abc".chars().enumerate_step(10u32, 5),
+        [(10, 'a'), (15, 'b'), (20, 'c')]
+    );
+    assert_iter_eq!(
+        "abc".chars().enumerate_step(0i64, -2),
+        [(0, 'a'), (-2, 'b'), (-4, 'c')]
+    );
+}
+
+#[test]
+fn enumerate_with_a_typed_index() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct LineNo(u32);
+
+    impl IndexStep for LineNo {
+        const ONE: Self = LineNo(1);
+
+        fn checked_step(self, step: Self) -> Option<Self> {
+            self.0.checked_step(step.0).map(LineNo)
+        }
+    }
+
+    let numbered: Vec<_> = ["x", "y"].into_iter().enumerate_from(LineNo(1)).collect();
+    assert_eq!(numbered, [(LineNo(1), "x"), (LineNo(2), "y")]);
+}
+
+#[test]
+fn enumerate_from_reaches_the_maximum() {
+    // The index after the last item is never worked out.
+    let last = (0..3).enumerate_from(u8::MAX - 2).last();
+    assert_eq!(last, Some((u8::MAX, 2)));
+    assert_eq!(
+        (0..2).enumerate_step(i8::MIN + 1, -1).last(),
+        Some((i8::MIN, 1))
+    );
+}
+
+#[test]
+#[should_panic(expected = "the index overflowed")]
+fn enumerate_from_panics_past_the_maximum() {
+    (0..3).enumerate_from(u8::MAX - 1).for_each(drop);
+}
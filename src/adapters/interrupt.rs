@@ -0,0 +1,166 @@
+//! `until_interrupted(flag)` ends an iterator once `flag` is set, from
+//! anywhere: another thread, or a Ctrl-C handler. An endless source like
+//! `PasswordGenerator` can then be consumed for as long as someone wants,
+//! and the consumer still finishes normally (a `collect` still returns,
+//! and whatever comes after it still runs) instead of the process being
+//! killed halfway through.
+//!
+//! The flag is checked before each item is pulled, so an item that is
+//! being made when the flag is set still comes out. The flag can be an
+//! `&AtomicBool` or an `Arc<AtomicBool>`, or anything else that borrows as
+//! one. With the "ctrlc" feature, `ctrl_c()` makes one that Ctrl-C sets.
+
+use core::borrow::Borrow;
+use core::iter::FusedIterator;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// Step 1: Define a struct for the adapter.
+#[derive(Debug, Clone)]
+pub struct UntilInterrupted<I, S> {
+    orig: I,
+    flag: S,
+    // Set once the flag has been seen, so a flag that is cleared again
+    // doesn't bring the iterator back.
+    stopped: bool,
+}
+
+impl<I, S: Borrow<AtomicBool>> UntilInterrupted<I, S> {
+    /// Whether the iterator stopped because of the flag.
+    pub fn was_interrupted(&self) -> bool {
+        self.stopped
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, S> Iterator for UntilInterrupted<I, S>
+where
+    I: Iterator,
+    S: Borrow<AtomicBool>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped || self.flag.borrow().load(Ordering::Relaxed) {
+            self.stopped = true;
+            return None;
+        }
+        self.orig.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // It could stop at any time.
+        (0, self.orig.size_hint().1)
+    }
+}
+
+impl<I, S> FusedIterator for UntilInterrupted<I, S>
+where
+    I: FusedIterator,
+    S: Borrow<AtomicBool>,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait InterruptExt: Iterator {
+    /// Yields the items until `flag` is set.
+    fn until_interrupted<S>(self, flag: S) -> UntilInterrupted<Self, S>
+    where
+        Self: Sized,
+        S: Borrow<AtomicBool>,
+    {
+        UntilInterrupted {
+            orig: self,
+            flag,
+            stopped: false,
+        }
+    }
+}
+
+impl<I: Iterator> InterruptExt for I {}
+
+/// A flag that Ctrl-C sets, for `until_interrupted`. A process can only
+/// have one Ctrl-C handler, so this fails if there already is one.
+#[cfg(feature = "ctrlc")]
+pub fn ctrl_c() -> Result<std::sync::Arc<AtomicBool>, ctrlc::Error> {
+    let flag = std::sync::Arc::new(AtomicBool::new(false));
+    let handler_flag = std::sync::Arc::clone(&flag);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::Relaxed))?;
+    Ok(flag)
+}
+
+#[cfg(test)]
+use std::{sync::Arc, thread, time::Duration};
+
+#[test]
+fn until_interrupted_stops_when_the_flag_is_set() {
+    let flag = AtomicBool::new(false);
+    let mut it = (0..).until_interrupted(&flag);
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next(), Some(1));
+    assert!(!it.was_interrupted());
+    flag.store(true, Ordering::Relaxed);
+    assert_eq!(it.next(), None);
+    assert!(it.was_interrupted());
+
+    // Clearing the flag doesn't start it again.
+    flag.store(false, Ordering::Relaxed);
+    assert_eq!(it.next(), None);
+
+    // Without the flag, it is the iterator as it was.
+    let never = AtomicBool::new(false);
+    assert_iter_eq!((0..3).until_interrupted(&never), 0..3);
+    assert_eq!((0..3).until_interrupted(&never).size_hint(), (0, Some(3)));
+}
+
+#[test]
+fn until_interrupted_from_another_thread() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let setter = {
+        let flag = Arc::clone(&flag);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            flag.store(true, Ordering::Relaxed);
+        })
+    };
+    // An endless source, slowed down so the flag lands partway through.
+    let ticks: Vec<u32> = (0..)
+        .inspect(|_| thread::sleep(Duration::from_millis(1)))
+        .until_interrupted(Arc::clone(&flag))
+        .collect();
+    setter.join().unwrap();
+    assert!(!ticks.is_empty());
+    assert!(ticks.iter().copied().eq(0..ticks.len() as u32));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn until_interrupted_ends_an_endless_password_source() {
+    use crate::sources::PasswordGenerator;
+
+    let flag = AtomicBool::new(false);
+    let passwords = PasswordGenerator::builder()
+        .seed(1415)
+        .build()
+        .unwrap()
+        .until_interrupted(&flag);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(20));
+            flag.store(true, Ordering::Relaxed);
+        });
+        let mut count = 0;
+        for password in passwords {
+            assert_eq!(password.len(), 16);
+            count += 1;
+        }
+        assert!(count > 0);
+    });
+}
+
+#[cfg(feature = "ctrlc")]
+#[test]
+fn ctrl_c_installs_one_handler() {
+    let flag = ctrl_c().unwrap();
+    assert!(!flag.load(Ordering::Relaxed));
+    assert!(matches!(ctrl_c(), Err(ctrlc::Error::MultipleHandlers)));
+}
@@ -0,0 +1,208 @@
+//! `left.merge_join_by(right, cmp)` walks two iterators sorted by the same
+//! key side by side, the way a database merge join does, and yields an
+//! `EitherOrBoth` for each step: `Both(l, r)` where `cmp(&l, &r)` is
+//! `Equal`, and `Left(l)` or `Right(r)` for an item that has no match on
+//! the other side. Keeping only the `Both`s gives an inner join; also
+//! keeping the `Left`s, a left outer join; keeping everything, a full outer
+//! join. It never holds more than one item from each side.
+//!
+//! A match moves both sides on, so with repeated keys the first repeat on
+//! the left is paired with the first on the right, the second with the
+//! second, and so on. If the inputs aren't sorted by `cmp`, the result is
+//! meaningless but still one entry per item.
+
+use crate::either::EitherOrBoth;
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::{FusedIterator, Peekable};
+
+// Step 1: Define a struct for the custom adapter.
+pub struct MergeJoinBy<L, R, F>
+where
+    L: Iterator,
+    R: Iterator,
+{
+    left: Peekable<L>,
+    right: Peekable<R>,
+    cmp: F,
+}
+
+impl<L, R, F> Clone for MergeJoinBy<L, R, F>
+where
+    L: Iterator + Clone,
+    L::Item: Clone,
+    R: Iterator + Clone,
+    R::Item: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        MergeJoinBy {
+            left: self.left.clone(),
+            right: self.right.clone(),
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<L, R, F> fmt::Debug for MergeJoinBy<L, R, F>
+where
+    L: Iterator + fmt::Debug,
+    L::Item: fmt::Debug,
+    R: Iterator + fmt::Debug,
+    R::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MergeJoinBy")
+            .field("left", &self.left)
+            .field("right", &self.right)
+            .finish_non_exhaustive()
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<L, R, F> Iterator for MergeJoinBy<L, R, F>
+where
+    L: Iterator,
+    R: Iterator,
+    F: FnMut(&L::Item, &R::Item) -> Ordering,
+{
+    type Item = EitherOrBoth<L::Item, R::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ordering = match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) => (self.cmp)(l, r),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        Some(match ordering {
+            Ordering::Less => EitherOrBoth::Left(self.left.next()?),
+            Ordering::Greater => EitherOrBoth::Right(self.right.next()?),
+            Ordering::Equal => EitherOrBoth::Both(self.left.next()?, self.right.next()?),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // At least one entry per item of the longer side; at most one per
+        // item of either.
+        let (l_lower, l_upper) = self.left.size_hint();
+        let (r_lower, r_upper) = self.right.size_hint();
+        let upper = match (l_upper, r_upper) {
+            (Some(l), Some(r)) => l.checked_add(r),
+            _ => None,
+        };
+        (l_lower.max(r_lower), upper)
+    }
+}
+
+impl<L, R, F> FusedIterator for MergeJoinBy<L, R, F>
+where
+    L: FusedIterator,
+    R: FusedIterator,
+    F: FnMut(&L::Item, &R::Item) -> Ordering,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait MergeJoinExt: Iterator {
+    /// Joins this sorted iterator with the sorted `other`, matching up the
+    /// items for which `cmp` is `Equal`.
+    fn merge_join_by<R, F>(self, other: R, cmp: F) -> MergeJoinBy<Self, R::IntoIter, F>
+    where
+        Self: Sized,
+        R: IntoIterator,
+        F: FnMut(&Self::Item, &R::Item) -> Ordering,
+    {
+        MergeJoinBy {
+            left: self.peekable(),
+            right: other.into_iter().peekable(),
+            cmp,
+        }
+    }
+}
+
+impl<I: Iterator> MergeJoinExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[cfg(test)]
+fn by_key<K: Ord, A, B>(l: &(K, A), r: &(K, B)) -> Ordering {
+    l.0.cmp(&r.0)
+}
+
+#[test]
+fn merge_join_matches_keys() {
+    use EitherOrBoth::{Both, Left, Right};
+
+    let names = [(1, "ann"), (2, "bob"), (4, "dee")];
+    let scores = [(2, 70), (3, 55), (4, 90), (5, 60)];
+    let joined: Vec<_> = names.into_iter().merge_join_by(scores, by_key).collect();
+    assert_eq!(
+        joined,
+        [
+            Left((1, "ann")),
+            Both((2, "bob"), (2, 70)),
+            Right((3, 55)),
+            Both((4, "dee"), (4, 90)),
+            Right((5, 60)),
+        ]
+    );
+}
+
+#[test]
+fn merge_join_inner_and_outer_joins() {
+    let names = [(1, "ann"), (2, "bob"), (4, "dee")];
+    let scores = [(2, 70), (3, 55), (4, 90)];
+    let join = || names.into_iter().merge_join_by(scores, by_key);
+
+    let inner: Vec<_> = join()
+        .filter_map(EitherOrBoth::both)
+        .map(|((_, name), (_, score))| (name, score))
+        .collect();
+    assert_eq!(inner, [("bob", 70), ("dee", 90)]);
+
+    let left_outer: Vec<_> = join()
+        .filter_map(|e| match e.into_options() {
+            (Some((_, name)), score) => Some((name, score.map(|(_, s)| s))),
+            (None, _) => None,
+        })
+        .collect();
+    assert_eq!(
+        left_outer,
+        [("ann", None), ("bob", Some(70)), ("dee", Some(90))]
+    );
+
+    let full_outer_keys: Vec<i32> = join()
+        .map(|e| match e {
+            EitherOrBoth::Left((k, _)) | EitherOrBoth::Both((k, _), _) => k,
+            EitherOrBoth::Right((k, _)) => k,
+        })
+        .collect();
+    assert_eq!(full_outer_keys, [1, 2, 3, 4]);
+}
+
+#[test]
+fn merge_join_with_repeated_keys_and_empty_sides() {
+    // Repeats are paired up in order; the extra one has no match.
+    let left = [(1, 'a'), (1, 'b'), (1, 'c')];
+    let right = [(1, 'x'), (1, 'y')];
+    let both = left
+        .into_iter()
+        .merge_join_by(right, by_key)
+        .filter(|e| matches!(e, EitherOrBoth::Both(..)))
+        .count();
+    assert_eq!(both, 2);
+
+    let it = left
+        .into_iter()
+        .merge_join_by(Vec::<(i32, char)>::new(), by_key);
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert!(it.map(EitherOrBoth::right).all(|r| r.is_none()));
+    assert_eq!(
+        core::iter::empty::<u8>()
+            .merge_join_by(0..0u8, |a, b| a.cmp(b))
+            .next(),
+        None
+    );
+}
@@ -0,0 +1,299 @@
+//! `par_map` is `map` with the closure run on a small pool of worker
+//! threads, written from scratch with nothing but `std::thread` and
+//! `std::sync::mpsc`:
+//!
+//! - items go out to the workers over one channel, tagged with their index;
+//! - results come back over another, in whatever order the workers finish;
+//! - results that arrive early wait in a buffer until their turn, so the
+//!   adapter yields them in input order, like `map` would.
+//!
+//! At most `window` items are out with the workers at a time, so a slow
+//! consumer doesn't make the adapter drain the whole input into memory.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Outcome<B> = Result<B, Box<dyn Any + Send>>;
+
+pub struct ParMap<I, B>
+where
+    I: Iterator,
+{
+    orig: I,
+    // `None` once the adapter is being dropped, which tells the workers to
+    // stop.
+    jobs: Option<Sender<(usize, I::Item)>>,
+    results: Receiver<(usize, Outcome<B>)>,
+    workers: Vec<JoinHandle<()>>,
+    // Results that came back ahead of their turn, by index.
+    ready: HashMap<usize, Outcome<B>>,
+    // The index of the next item to send out, and of the next to yield.
+    sent: usize,
+    yielded: usize,
+    window: usize,
+    exhausted: bool,
+}
+
+impl<I, B> ParMap<I, B>
+where
+    I: Iterator,
+    I::Item: Send + 'static,
+    B: Send + 'static,
+{
+    fn new<F>(orig: I, threads: usize, f: F) -> Self
+    where
+        F: Fn(I::Item) -> B + Send + Sync + 'static,
+    {
+        assert!(threads > 0, "par_map needs at least one thread");
+        let (jobs, job_queue) = mpsc::channel::<(usize, I::Item)>();
+        let (done, results) = mpsc::channel();
+        let job_queue = Arc::new(Mutex::new(job_queue));
+        let f = Arc::new(f);
+
+        let workers = (0..threads)
+            .map(|_| {
+                let job_queue = Arc::clone(&job_queue);
+                let done = done.clone();
+                let f = Arc::clone(&f);
+                thread::spawn(move || loop {
+                    // The lock is only held while waiting for a job, not
+                    // while running it.
+                    let job = job_queue.lock().unwrap().recv();
+                    let Ok((index, item)) = job else {
+                        return;
+                    };
+                    // A panic in `f` is sent back to be raised by `next()`,
+                    // rather than leaving `next()` waiting for ever.
+                    let outcome = panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                    if done.send((index, outcome)).is_err() {
+                        return;
+                    }
+                })
+            })
+            .collect();
+
+        ParMap {
+            orig,
+            jobs: Some(jobs),
+            results,
+            workers,
+            ready: HashMap::new(),
+            sent: 0,
+            yielded: 0,
+            window: 2 * threads,
+            exhausted: false,
+        }
+    }
+
+    /// Sends items out until the window is full or the input runs dry.
+    fn fill(&mut self) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        while !self.exhausted && self.sent - self.yielded < self.window {
+            match self.orig.next() {
+                Some(item) => {
+                    jobs.send((self.sent, item))
+                        .expect("the workers outlive the adapter");
+                    self.sent += 1;
+                }
+                None => self.exhausted = true,
+            }
+        }
+    }
+}
+
+impl<I, B> Iterator for ParMap<I, B>
+where
+    I: Iterator,
+    I::Item: Send + 'static,
+    B: Send + 'static,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        if self.yielded == self.sent {
+            return None;
+        }
+        let outcome = loop {
+            if let Some(outcome) = self.ready.remove(&self.yielded) {
+                break outcome;
+            }
+            let (index, outcome) = self
+                .results
+                .recv()
+                .expect("the workers outlive the adapter");
+            self.ready.insert(index, outcome);
+        };
+        self.yielded += 1;
+        match outcome {
+            Ok(value) => Some(value),
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let in_flight = self.sent - self.yielded;
+        if self.exhausted {
+            return (in_flight, Some(in_flight));
+        }
+        let (lo, hi) = self.orig.size_hint();
+        (
+            lo.saturating_add(in_flight),
+            hi.and_then(|hi| hi.checked_add(in_flight)),
+        )
+    }
+}
+
+impl<I, B> FusedIterator for ParMap<I, B>
+where
+    I: Iterator,
+    I::Item: Send + 'static,
+    B: Send + 'static,
+{
+}
+
+impl<I, B> Drop for ParMap<I, B>
+where
+    I: Iterator,
+{
+    fn drop(&mut self) {
+        // Closing the job channel lets every worker finish what it is doing
+        // and return.
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<I, B> fmt::Debug for ParMap<I, B>
+where
+    I: Iterator + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParMap")
+            .field("orig", &self.orig)
+            .field("threads", &self.workers.len())
+            .field("in_flight", &(self.sent - self.yielded))
+            .finish()
+    }
+}
+
+pub trait ParMapExt: Iterator {
+    /// Like `map`, but runs `f` on `threads` worker threads. Results come
+    /// out in input order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is 0. A panic in `f` is raised again by the
+    /// `next()` call that would have returned its result.
+    fn par_map<B, F>(self, threads: usize, f: F) -> ParMap<Self, B>
+    where
+        Self: Sized,
+        Self::Item: Send + 'static,
+        B: Send + 'static,
+        F: Fn(Self::Item) -> B + Send + Sync + 'static;
+}
+
+impl<I: Iterator> ParMapExt for I {
+    fn par_map<B, F>(self, threads: usize, f: F) -> ParMap<Self, B>
+    where
+        Self::Item: Send + 'static,
+        B: Send + 'static,
+        F: Fn(Self::Item) -> B + Send + Sync + 'static,
+    {
+        ParMap::new(self, threads, f)
+    }
+}
+
+#[cfg(test)]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(test)]
+use std::sync::{Barrier, Condvar};
+
+#[test]
+fn par_map_keeps_input_order() {
+    // In each run of four items, every item waits for the next one to
+    // finish, so the workers finish them back to front.
+    let finished = Arc::new((Mutex::new(Vec::new()), Condvar::new()));
+    let log = Arc::clone(&finished);
+    let out: Vec<u64> = (0..12u64)
+        .par_map(4, move |x| {
+            let (order, done) = &*log;
+            let mut order = order.lock().unwrap();
+            if x % 4 != 3 {
+                order = done.wait_while(order, |o| !o.contains(&(x + 1))).unwrap();
+            }
+            order.push(x);
+            done.notify_all();
+            x * x
+        })
+        .collect();
+
+    let (order, _) = &*finished;
+    assert_eq!(
+        *order.lock().unwrap(),
+        [3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8]
+    );
+    assert_eq!(out, (0..12u64).map(|x| x * x).collect::<Vec<_>>());
+
+    assert_eq!((0..0).par_map(3, |x: i32| x).count(), 0);
+    assert_eq!((0..100).par_map(1, |x| x + 1).sum::<i32>(), 5050);
+}
+
+#[test]
+fn par_map_runs_on_several_threads_at_once() {
+    // No item gets past the barrier until all four are running, which
+    // takes four threads at once.
+    let barrier = Arc::new(Barrier::new(4));
+    let threads: Vec<thread::ThreadId> = (0..4)
+        .par_map(4, move |_| {
+            barrier.wait();
+            thread::current().id()
+        })
+        .collect();
+
+    let distinct: std::collections::HashSet<_> = threads.iter().collect();
+    assert_eq!(distinct.len(), 4);
+    assert!(!threads.contains(&thread::current().id()));
+}
+
+#[test]
+fn par_map_bounds_the_items_in_flight() {
+    let pulled = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&pulled);
+    let mut squares = (0..1000)
+        .inspect(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .par_map(2, |x| x * x);
+
+    assert_eq!(squares.next(), Some(0));
+    // A window of twice the threads, and nothing more.
+    assert_eq!(pulled.load(Ordering::SeqCst), 4);
+    assert_eq!(squares.size_hint(), (999, Some(999)));
+    assert_eq!(squares.nth(9), Some(100));
+    assert!(pulled.load(Ordering::SeqCst) <= 11 + 4);
+    // Dropping it early stops the workers.
+    drop(squares);
+    assert!(pulled.load(Ordering::SeqCst) < 1000);
+}
+
+#[test]
+#[should_panic(expected = "no threes")]
+fn par_map_passes_on_panics() {
+    let _: Vec<i32> = (0..6)
+        .par_map(2, |x| {
+            assert_ne!(x, 3, "no threes");
+            x
+        })
+        .collect();
+}
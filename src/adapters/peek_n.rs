@@ -0,0 +1,205 @@
+//! `peek_n(k)` can look up to `k` items ahead: `peek(0)` is the item `next`
+//! will yield, `peek(1)` the one after it, and so on up to `peek(k - 1)`.
+//! That sits between `Peekable`, which only sees one item ahead, and a
+//! multi-peek with no limit: the items looked at but not yet yielded wait in
+//! a buffer that never holds more than `k` of them, and is only filled as
+//! far as the furthest `peek` so far has needed.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+use core::iter::FusedIterator;
+
+// Step 1: Define a struct for the adapter.
+pub struct PeekN<I: Iterator> {
+    orig: I,
+    // The items peeked at and not yet yielded, the next one first.
+    ahead: VecDeque<I::Item>,
+    lookahead: usize,
+    // Whether `orig` has ended, so that it isn't asked again.
+    done: bool,
+}
+
+impl<I: Iterator> PeekN<I> {
+    /// How far ahead `peek` can look.
+    pub fn lookahead(&self) -> usize {
+        self.lookahead
+    }
+
+    /// The item `i` places ahead, where 0 is the one `next` will yield, or
+    /// `None` if the iterator ends before it.
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not less than the lookahead.
+    #[track_caller]
+    pub fn peek(&mut self, i: usize) -> Option<&I::Item> {
+        self.fill(i);
+        self.ahead.get(i)
+    }
+
+    /// Like `peek`, but the item can be changed before it is yielded.
+    #[track_caller]
+    pub fn peek_mut(&mut self, i: usize) -> Option<&mut I::Item> {
+        self.fill(i);
+        self.ahead.get_mut(i)
+    }
+
+    // Pulls items until the buffer reaches index `i`, or `orig` ends.
+    #[track_caller]
+    fn fill(&mut self, i: usize) {
+        assert!(
+            i < self.lookahead,
+            "peek: {i} is past the lookahead of {}",
+            self.lookahead
+        );
+        while self.ahead.len() <= i && !self.done {
+            match self.orig.next() {
+                Some(item) => self.ahead.push_back(item),
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+impl<I> Clone for PeekN<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        PeekN {
+            orig: self.orig.clone(),
+            ahead: self.ahead.clone(),
+            lookahead: self.lookahead,
+            done: self.done,
+        }
+    }
+}
+
+impl<I> fmt::Debug for PeekN<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeekN")
+            .field("orig", &self.orig)
+            .field("ahead", &self.ahead)
+            .field("lookahead", &self.lookahead)
+            .finish()
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I: Iterator> Iterator for PeekN<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.ahead.pop_front() {
+            Some(item) => Some(item),
+            None if self.done => None,
+            None => self.orig.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let ahead = self.ahead.len();
+        if self.done {
+            return (ahead, Some(ahead));
+        }
+        let (lower, upper) = self.orig.size_hint();
+        (
+            lower.saturating_add(ahead),
+            upper.and_then(|n| n.checked_add(ahead)),
+        )
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for PeekN<I> {}
+
+impl<I: FusedIterator> FusedIterator for PeekN<I> {}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait PeekNExt: Iterator {
+    /// Yields the items, with `peek(i)` to look up to `lookahead` of them
+    /// ahead.
+    ///
+    /// # Panics
+    ///
+    /// If `lookahead` is 0.
+    fn peek_n(self, lookahead: usize) -> PeekN<Self>
+    where
+        Self: Sized,
+    {
+        assert!(lookahead > 0, "peek_n: a lookahead of 0 can't peek at all");
+        PeekN {
+            orig: self,
+            // Only as big as the peeks need, so a huge lookahead costs nothing
+            // until it is used.
+            ahead: VecDeque::new(),
+            lookahead,
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator> PeekNExt for I {}
+
+#[test]
+fn peek_n_looks_ahead_without_consuming() {
+    let mut it = "abcde".chars().peek_n(3);
+    assert_eq!(it.peek(2), Some(&'c'));
+    assert_eq!(it.peek(0), Some(&'a'));
+    assert_eq!(it.next(), Some('a'));
+    assert_eq!(it.peek(2), Some(&'d'));
+    *it.peek_mut(1).unwrap() = 'C';
+    assert_iter_eq!(it, "bCde".chars());
+}
+
+#[test]
+fn peek_n_pulls_only_what_it_needs() {
+    let pulled = core::cell::Cell::new(0);
+    let mut it = (0..10).inspect(|_| pulled.set(pulled.get() + 1)).peek_n(4);
+    assert_eq!(it.peek(1), Some(&1));
+    assert_eq!(pulled.get(), 2);
+    assert_eq!(it.peek(0), Some(&0));
+    assert_eq!(pulled.get(), 2);
+    assert_eq!(it.len(), 10);
+    it.next();
+    it.next();
+    // The buffer is empty again, so `next` goes straight to the source.
+    it.next();
+    assert_eq!(pulled.get(), 3);
+    assert_eq!(it.peek(3), Some(&6));
+    assert_eq!(it.ahead.len(), 4);
+    assert_eq!(it.len(), 7);
+}
+
+#[test]
+fn peek_n_past_the_end() {
+    let mut it = [1, 2].into_iter().peek_n(4);
+    assert_eq!(it.peek(3), None);
+    assert_eq!(it.peek(2), None);
+    assert_eq!(it.peek(1), Some(&2));
+    assert_eq!(it.size_hint(), (2, Some(2)));
+    assert_iter_eq!(it.by_ref(), [1, 2]);
+    assert_eq!(it.peek(0), None);
+    assert_eq!(it.next(), None);
+
+    // No limit to speak of, and no room taken for it.
+    let mut far = (0..5).peek_n(usize::MAX);
+    assert_eq!(far.peek(4), Some(&4));
+    assert_eq!(far.peek(1000), None);
+    assert_iter_eq!(far, 0..5);
+
+    let mut empty = core::iter::empty::<u8>().peek_n(1);
+    assert_eq!(empty.peek(0), None);
+    assert_eq!(empty.lookahead(), 1);
+}
+
+#[test]
+#[should_panic(expected = "peek: 3 is past the lookahead of 3")]
+fn peek_n_beyond_the_lookahead() {
+    let mut it = (0..10).peek_n(3);
+    it.peek(3);
+}
@@ -0,0 +1,197 @@
+//! `prefetch` moves an iterator to a thread of its own, which runs ahead of
+//! the consumer and parks up to `buffer_size` items in a bounded channel.
+//! A slow producer (reading a file, say) then overlaps with whatever the
+//! consumer does with each item, instead of taking turns with it.
+//!
+//! The channel being bounded is what keeps the producer from running off
+//! with the whole input: once the buffer is full, it blocks until the
+//! consumer takes something out (backpressure).
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::panic;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+pub struct Prefetch<T> {
+    // Both are `None` once the producer has finished.
+    items: Option<Receiver<T>>,
+    producer: Option<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Prefetch<T> {
+    fn new<I>(orig: I, buffer_size: usize) -> Self
+    where
+        I: Iterator<Item = T> + Send + 'static,
+    {
+        let (sender, items) = mpsc::sync_channel(buffer_size);
+        let producer = thread::spawn(move || {
+            for item in orig {
+                // The consumer hung up, so nobody wants the rest.
+                if sender.send(item).is_err() {
+                    return;
+                }
+            }
+        });
+        Prefetch {
+            items: Some(items),
+            producer: Some(producer),
+        }
+    }
+}
+
+impl<T> Iterator for Prefetch<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Ok(item) = self.items.as_ref()?.recv() {
+            return Some(item);
+        }
+        // The channel only disconnects when the producer returns, whether
+        // it ran out of items or panicked. A panic is raised again here.
+        self.items = None;
+        if let Some(producer) = self.producer.take() {
+            if let Err(payload) = producer.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+        None
+    }
+}
+
+impl<T> FusedIterator for Prefetch<T> {}
+
+impl<T> Drop for Prefetch<T> {
+    fn drop(&mut self) {
+        // Hanging up first makes a producer blocked on a full buffer give up,
+        // so the join can't wait for ever. A panic nobody asked about is
+        // dropped along with the rest.
+        self.items = None;
+        if let Some(producer) = self.producer.take() {
+            let _ = producer.join();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Prefetch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prefetch")
+            .field("finished", &self.items.is_none())
+            .finish()
+    }
+}
+
+pub trait PrefetchExt: Iterator {
+    /// Runs this iterator on a thread of its own, at most `buffer_size`
+    /// items ahead of the consumer. With a `buffer_size` of 0, each item is
+    /// handed over directly.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator panics on its thread, the panic is raised again by
+    /// `next()` once the items before it have been yielded.
+    fn prefetch(self, buffer_size: usize) -> Prefetch<Self::Item>
+    where
+        Self: Sized + Send + 'static,
+        Self::Item: Send + 'static;
+}
+
+impl<I: Iterator> PrefetchExt for I {
+    fn prefetch(self, buffer_size: usize) -> Prefetch<Self::Item>
+    where
+        Self: Send + 'static,
+        Self::Item: Send + 'static,
+    {
+        Prefetch::new(self, buffer_size)
+    }
+}
+
+#[cfg(test)]
+use std::time::Duration;
+
+// An iterator over `0..n` that reports each item it hands out on `pulls`.
+#[cfg(test)]
+fn reported(
+    n: usize,
+) -> (
+    impl Iterator<Item = usize> + Send + 'static,
+    Receiver<usize>,
+) {
+    let (sender, pulls) = mpsc::channel();
+    let iter = (0..n).inspect(move |&x| {
+        let _ = sender.send(x);
+    });
+    (iter, pulls)
+}
+
+// Waiting a little for a report that shouldn't come can only make these
+// tests pass when they shouldn't, on a very slow machine, never fail.
+#[cfg(test)]
+const QUIET: Duration = Duration::from_millis(50);
+
+#[test]
+fn prefetch_yields_everything_in_order() {
+    let v: Vec<usize> = (0..1000).prefetch(8).collect();
+    assert_iter_eq!(v.iter().copied(), 0..1000);
+    assert_eq!((0..5).prefetch(0).collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+
+    let mut empty = std::iter::empty::<i32>().prefetch(4);
+    assert_eq!(empty.next(), None);
+    assert_eq!(empty.next(), None);
+}
+
+#[test]
+fn prefetch_overlaps_producer_and_consumer() {
+    let (iter, pulls) = reported(10);
+    let mut sum = 0;
+    for item in iter.prefetch(10) {
+        // Holding on to each item until the producer has pulled the next
+        // one would wait for ever if the two took turns.
+        if item < 9 {
+            let ahead = || pulls.recv_timeout(Duration::from_secs(10));
+            while ahead().expect("the producer should run ahead") <= item {}
+        }
+        sum += item;
+    }
+    assert_eq!(sum, 45);
+}
+
+#[test]
+fn prefetch_applies_backpressure() {
+    let (iter, pulls) = reported(100);
+    let mut ahead = iter.prefetch(3);
+    // Three in the buffer and one waiting to go in.
+    assert_eq!(pulls.iter().take(4).collect::<Vec<_>>(), [0, 1, 2, 3]);
+    assert!(pulls.recv_timeout(QUIET).is_err());
+
+    assert_eq!(ahead.next(), Some(0));
+    assert_eq!(pulls.recv(), Ok(4));
+    assert!(pulls.recv_timeout(QUIET).is_err());
+}
+
+#[test]
+fn prefetch_stops_the_producer_on_early_drop() {
+    let (iter, pulls) = reported(1_000_000);
+    let mut ahead = iter.prefetch(2);
+    assert_eq!(ahead.nth(4), Some(4));
+    drop(ahead);
+    // The producer has returned by the time `drop` does, taking the sending
+    // end of `pulls` with it, so this counts every item it ever pulled.
+    let pulled = pulls.iter().count();
+    assert!(pulled <= 5 + 3, "{pulled}");
+}
+
+#[test]
+fn prefetch_raises_producer_panics_on_next() {
+    let mut ahead = (0..5)
+        .inspect(|&x| assert_ne!(x, 2, "two is not allowed"))
+        .prefetch(4);
+    assert_eq!(ahead.next(), Some(0));
+    assert_eq!(ahead.next(), Some(1));
+
+    let err = panic::catch_unwind(panic::AssertUnwindSafe(|| ahead.next())).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("two is not allowed"));
+    // The panic is only raised once.
+    assert_eq!(ahead.next(), None);
+}
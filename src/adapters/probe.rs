@@ -0,0 +1,186 @@
+//! `spy` wraps an iterator and writes down everything that happens to it:
+//! each call to `next()`, and what the call returned. Give several stages
+//! of a pipeline the same `ProbeLog` and the log shows the order in which
+//! they run, which makes laziness something a test can check rather than
+//! take on trust.
+
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Something that happened to a probed iterator, tagged with the probe's
+/// label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// `next()` was called.
+    Next(&'static str),
+    /// `next()` returned an item, written out with `{:?}`. Items are kept
+    /// as text so that probes on stages with different item types can
+    /// share a log.
+    Yield(&'static str, String),
+    /// `next()` returned `None`.
+    Done(&'static str),
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Next(label) => write!(f, "{label}: next"),
+            Event::Yield(label, item) => write!(f, "{label}: yield {item}"),
+            Event::Done(label) => write!(f, "{label}: done"),
+        }
+    }
+}
+
+/// The events of one or more probes, oldest first. Start with
+/// `ProbeLog::default()`.
+pub type ProbeLog = Rc<RefCell<Vec<Event>>>;
+
+// Step 1: Define a struct for the custom adapter.
+#[derive(Debug, Clone)]
+pub struct Probe<I> {
+    orig: I,
+    label: &'static str,
+    log: ProbeLog,
+}
+
+// Step 2: Implement `Iterator`. Only `next` is forwarded one call at a time;
+// the default `fold`, `count` and so on go through it, so every item that
+// passes is logged.
+impl<I> Iterator for Probe<I>
+where
+    I: Iterator,
+    I::Item: fmt::Debug,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.log.borrow_mut().push(Event::Next(self.label));
+        // The log isn't borrowed while the inner iterator runs, as it may
+        // have probes of its own.
+        let item = self.orig.next();
+        let event = match &item {
+            Some(item) => Event::Yield(self.label, format!("{item:?}")),
+            None => Event::Done(self.label),
+        };
+        self.log.borrow_mut().push(event);
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Probe<I>
+where
+    I: ExactSizeIterator,
+    I::Item: fmt::Debug,
+{
+}
+
+impl<I> FusedIterator for Probe<I>
+where
+    I: FusedIterator,
+    I::Item: fmt::Debug,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait ProbeExt: Iterator {
+    /// Logs every `next()` call on this iterator, and its result, to `log`
+    /// under `label`.
+    fn spy(self, label: &'static str, log: &ProbeLog) -> Probe<Self>
+    where
+        Self: Sized,
+        Self::Item: fmt::Debug,
+    {
+        Probe {
+            orig: self,
+            label,
+            log: Rc::clone(log),
+        }
+    }
+}
+
+impl<I: Iterator> ProbeExt for I {}
+
+#[cfg(test)]
+fn lines(log: &ProbeLog) -> Vec<String> {
+    log.borrow().iter().map(|event| event.to_string()).collect()
+}
+
+#[test]
+fn map_does_nothing_until_consumed() {
+    let log = ProbeLog::default();
+    let mut doubled = (1..4).spy("source", &log).map(|x| x * 2);
+    // Building the pipeline didn't touch the source.
+    assert!(log.borrow().is_empty());
+
+    assert_eq!(doubled.next(), Some(2));
+    assert_eq!(lines(&log), ["source: next", "source: yield 1"]);
+
+    // Each item goes all the way through before the next one is asked for.
+    assert_eq!(doubled.collect::<Vec<_>>(), [4, 6]);
+    assert_eq!(log.borrow().len(), 8);
+    assert_eq!(log.borrow().last(), Some(&Event::Done("source")));
+}
+
+#[test]
+fn probes_show_the_evaluation_order() {
+    let log = ProbeLog::default();
+    let first_big_even = (1..10)
+        .spy("source", &log)
+        .filter(|n| n % 2 == 0)
+        .spy("filter", &log)
+        .map(|n| n * 10)
+        .find(|&n| n > 30);
+    assert_eq!(first_big_even, Some(40));
+
+    // `find` asks the filter, the filter asks the source until it gets an
+    // even number, and nothing past 4 is ever produced.
+    assert_eq!(
+        lines(&log),
+        [
+            "filter: next",
+            "source: next",
+            "source: yield 1",
+            "source: next",
+            "source: yield 2",
+            "filter: yield 2",
+            "filter: next",
+            "source: next",
+            "source: yield 3",
+            "source: next",
+            "source: yield 4",
+            "filter: yield 4",
+        ]
+    );
+}
+
+#[test]
+fn probe_records_exhaustion() {
+    let log = ProbeLog::default();
+    let mut empty = core::iter::empty::<u8>().spy("empty", &log);
+    assert_eq!(empty.next(), None);
+    assert_eq!(empty.next(), None);
+    assert_eq!(
+        *log.borrow(),
+        [
+            Event::Next("empty"),
+            Event::Done("empty"),
+            Event::Next("empty"),
+            Event::Done("empty")
+        ]
+    );
+
+    let words = ["a", "b"].into_iter().spy("words", &log);
+    assert_eq!(words.len(), 2);
+    log.borrow_mut().clear();
+    assert_eq!(words.count(), 2);
+    assert_eq!(lines(&log)[1], "words: yield \"a\"");
+}
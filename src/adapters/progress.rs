@@ -0,0 +1,368 @@
+//! `progress(total)` reports how far along an iterator is: every so many
+//! items, or every so often, it hands a `ProgressUpdate` (the count, the
+//! percentage, the rate and an ETA) to a `ProgressReporter`. The default
+//! reporter draws a bar on standard error, redrawn in place:
+//!
+//! ```text
+//! [##########          ]  500/1000  50%  1234.5/s  ETA 405.2ms
+//! ```
+//!
+//! Other reporters can log the updates, or keep them for a test to check.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::iter::FusedIterator;
+use std::time::{Duration, Instant};
+
+/// Where an iterator has got to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressUpdate {
+    /// The items yielded so far.
+    pub count: usize,
+    /// How many items there will be, if known.
+    pub total: Option<usize>,
+    pub elapsed: Duration,
+    /// Whether the iterator has run out. The last update always has this
+    /// set, however recently the one before it came.
+    pub done: bool,
+}
+
+impl ProgressUpdate {
+    /// How much of `total` is done, from 0 to 100.
+    pub fn percent(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(100.0),
+            Some(total) => Some(100.0 * self.count as f64 / total as f64),
+            None => None,
+        }
+    }
+
+    /// Items per second so far.
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.count as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// The time the rest should take at the rate so far, if there is a
+    /// rate yet and the estimate fits in a `Duration`.
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.count);
+        if remaining == 0 {
+            return Some(Duration::ZERO);
+        }
+        let rate = self.rate();
+        if rate > 0.0 {
+            Duration::try_from_secs_f64(remaining as f64 / rate).ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// Receives the updates of a `progress` adapter.
+pub trait ProgressReporter {
+    fn report(&mut self, update: &ProgressUpdate);
+}
+
+/// Keeps every update.
+impl ProgressReporter for Vec<ProgressUpdate> {
+    fn report(&mut self, update: &ProgressUpdate) {
+        self.push(*update);
+    }
+}
+
+impl<R: ProgressReporter + ?Sized> ProgressReporter for &mut R {
+    fn report(&mut self, update: &ProgressUpdate) {
+        (**self).report(update);
+    }
+}
+
+/// Draws a progress bar on one line of standard error, going back to the
+/// start of the line for each update and ending it when the iterator is
+/// done.
+#[derive(Debug, Clone, Copy)]
+pub struct StderrBar {
+    /// How many characters the bar itself takes up.
+    pub width: usize,
+}
+
+impl Default for StderrBar {
+    fn default() -> Self {
+        StderrBar { width: 20 }
+    }
+}
+
+impl StderrBar {
+    /// The line for `update`, without the carriage return.
+    pub fn render(&self, update: &ProgressUpdate) -> String {
+        let mut line = String::new();
+        match (update.percent(), update.total) {
+            (Some(percent), Some(total)) => {
+                let filled = ((percent / 100.0 * self.width as f64) as usize).min(self.width);
+                let digits = total.to_string().len();
+                let _ = write!(
+                    line,
+                    "[{}{}] {:>digits$}/{total} {percent:>3.0}%",
+                    "#".repeat(filled),
+                    " ".repeat(self.width - filled),
+                    update.count,
+                );
+            }
+            _ => {
+                let _ = write!(line, "{} items", update.count);
+            }
+        }
+        let _ = write!(line, "  {:.1}/s", update.rate());
+        if update.done {
+            let _ = write!(line, "  in {:.1?}", update.elapsed);
+        } else if let Some(eta) = update.eta() {
+            let _ = write!(line, "  ETA {:.1?}", eta);
+        }
+        line
+    }
+}
+
+impl ProgressReporter for StderrBar {
+    fn report(&mut self, update: &ProgressUpdate) {
+        let line = self.render(update);
+        let mut stderr = std::io::stderr().lock();
+        // Progress is best effort: a closed stderr shouldn't stop the work.
+        let _ = write!(stderr, "\r{line}");
+        if update.done {
+            let _ = writeln!(stderr);
+        }
+        let _ = stderr.flush();
+    }
+}
+
+// Step 1: Define a struct for the custom adapter.
+#[derive(Debug, Clone)]
+pub struct Progress<I, R = StderrBar> {
+    orig: I,
+    reporter: R,
+    total: Option<usize>,
+    count: usize,
+    start: Instant,
+    // Report once `every_items` items, or `every`, have gone by since the
+    // last report.
+    every_items: usize,
+    every: Duration,
+    since_report: usize,
+    last_report: Instant,
+    done: bool,
+}
+
+impl<I, R> Progress<I, R> {
+    /// Reports after every `n` items. The default is 100.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn every_items(mut self, n: usize) -> Self {
+        assert!(n > 0, "can't report every 0 items");
+        self.every_items = n;
+        self
+    }
+
+    /// Reports when `interval` has passed since the last report, whatever
+    /// the count. The default is 100ms.
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.every = interval;
+        self
+    }
+
+    /// Sends the updates to `reporter` instead.
+    pub fn with_reporter<R2: ProgressReporter>(self, reporter: R2) -> Progress<I, R2> {
+        Progress {
+            orig: self.orig,
+            reporter,
+            total: self.total,
+            count: self.count,
+            start: self.start,
+            every_items: self.every_items,
+            every: self.every,
+            since_report: self.since_report,
+            last_report: self.last_report,
+            done: self.done,
+        }
+    }
+}
+
+impl<I, R: ProgressReporter> Progress<I, R> {
+    fn report(&mut self, now: Instant) {
+        self.since_report = 0;
+        self.last_report = now;
+        self.reporter.report(&ProgressUpdate {
+            count: self.count,
+            total: self.total,
+            elapsed: now - self.start,
+            done: self.done,
+        });
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, R> Iterator for Progress<I, R>
+where
+    I: Iterator,
+    R: ProgressReporter,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.orig.next();
+        let now = Instant::now();
+        match item {
+            Some(_) => {
+                self.count += 1;
+                self.since_report += 1;
+                if self.since_report >= self.every_items || now - self.last_report >= self.every {
+                    self.report(now);
+                }
+            }
+            None => {
+                self.done = true;
+                self.report(now);
+            }
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            self.orig.size_hint()
+        }
+    }
+}
+
+impl<I, R> FusedIterator for Progress<I, R>
+where
+    I: Iterator,
+    R: ProgressReporter,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait ProgressExt: Iterator {
+    /// Reports progress towards `total` items on standard error. With
+    /// `None`, the total is the iterator's exact `size_hint`, if it has
+    /// one.
+    fn progress(self, total: Option<usize>) -> Progress<Self>
+    where
+        Self: Sized,
+    {
+        let total = total.or_else(|| match self.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        });
+        let now = Instant::now();
+        Progress {
+            orig: self,
+            reporter: StderrBar::default(),
+            total,
+            count: 0,
+            start: now,
+            every_items: 100,
+            every: Duration::from_millis(100),
+            since_report: 0,
+            last_report: now,
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator> ProgressExt for I {}
+
+#[test]
+fn progress_reports_every_n_items() {
+    let mut updates = Vec::new();
+    let sum: u32 = (0..10)
+        .progress(None)
+        .every_items(4)
+        .every(Duration::MAX)
+        .with_reporter(&mut updates)
+        .sum();
+    assert_eq!(sum, 45);
+
+    let seen: Vec<(usize, Option<usize>, bool)> =
+        updates.iter().map(|u| (u.count, u.total, u.done)).collect();
+    assert_eq!(
+        seen,
+        [
+            (4, Some(10), false),
+            (8, Some(10), false),
+            (10, Some(10), true)
+        ]
+    );
+    assert_eq!(updates[0].percent(), Some(40.0));
+    assert_eq!(updates[2].eta(), Some(Duration::ZERO));
+}
+
+#[test]
+fn progress_reports_every_so_often() {
+    let mut updates = Vec::new();
+    let slow = (0..6).inspect(|_| std::thread::sleep(Duration::from_millis(20)));
+    // Without an exact size, there's no total unless one is given.
+    let count = slow
+        .filter(|_| true)
+        .progress(None)
+        .every_items(usize::MAX)
+        .every(Duration::from_millis(30))
+        .with_reporter(&mut updates)
+        .count();
+    assert_eq!(count, 6);
+    assert!(updates.len() >= 3, "{updates:?}");
+    assert!(updates
+        .iter()
+        .all(|u| u.total.is_none() && u.eta().is_none()));
+    let last = updates.last().unwrap();
+    assert!(last.done && last.count == 6);
+    assert!(last.rate() > 0.0 && last.rate() < 60.0);
+}
+
+#[test]
+fn progress_bar_rendering() {
+    let bar = StderrBar { width: 10 };
+    let update = ProgressUpdate {
+        count: 25,
+        total: Some(100),
+        elapsed: Duration::from_secs(5),
+        done: false,
+    };
+    assert_eq!(update.rate(), 5.0);
+    assert_eq!(update.eta(), Some(Duration::from_secs(15)));
+    assert_eq!(
+        bar.render(&update),
+        "[##        ]  25/100  25%  5.0/s  ETA 15.0s"
+    );
+
+    let done = ProgressUpdate {
+        count: 7,
+        total: None,
+        elapsed: Duration::from_millis(500),
+        done: true,
+    };
+    assert_eq!(bar.render(&done), "7 items  14.0/s  in 500.0ms");
+}
+
+#[test]
+fn progress_eta_too_far_off_is_unknown() {
+    let bar = StderrBar { width: 10 };
+    let crawling = ProgressUpdate {
+        count: 1,
+        total: Some(usize::MAX),
+        elapsed: Duration::from_secs(1_000_000),
+        done: false,
+    };
+    assert_eq!(crawling.eta(), None);
+    assert!(!bar.render(&crawling).contains("ETA"));
+}
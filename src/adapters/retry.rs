@@ -0,0 +1,185 @@
+//! `retry_map(n, f)` is `map` for an operation that sometimes fails when
+//! it shouldn't, like a read over a flaky connection: when `f` returns an
+//! `Err` for an item, it is called again for the same item, up to `n` more
+//! times, and the item only comes out as an `Err` (the last one) if every
+//! attempt failed.
+//!
+//! `backoff(hook)` adds a call between a failed attempt and the next one,
+//! with the number of the attempt that failed (from 1) and its error. That
+//! is where to sleep, for longer each time, or to log the failure.
+//!
+//! ```text
+//! a --f--> Err --hook(1)--f--> Err --hook(2)--f--> Ok(x)   yields Ok(x)
+//! ```
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+// Step 1: Define a struct for the adapter.
+#[derive(Clone)]
+pub struct RetryMap<I, F, B> {
+    orig: I,
+    f: F,
+    backoff: B,
+    retries: usize,
+}
+
+impl<I: fmt::Debug, F, B> fmt::Debug for RetryMap<I, F, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryMap")
+            .field("orig", &self.orig)
+            .field("retries", &self.retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, F, B> RetryMap<I, F, B> {
+    /// Calls `hook` with the attempt number and the error after each
+    /// failed attempt that is going to be retried.
+    pub fn backoff<H, E>(self, hook: H) -> RetryMap<I, F, H>
+    where
+        H: FnMut(usize, &E),
+    {
+        RetryMap {
+            orig: self.orig,
+            f: self.f,
+            backoff: hook,
+            retries: self.retries,
+        }
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, F, B, T, E> Iterator for RetryMap<I, F, B>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> Result<T, E>,
+    B: FnMut(usize, &E),
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        let mut attempt = 1;
+        loop {
+            match (self.f)(&item) {
+                Err(e) if attempt <= self.retries => {
+                    (self.backoff)(attempt, &e);
+                    attempt += 1;
+                }
+                result => return Some(result),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I, F, B, T, E> ExactSizeIterator for RetryMap<I, F, B>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&I::Item) -> Result<T, E>,
+    B: FnMut(usize, &E),
+{
+}
+
+impl<I, F, B, T, E> FusedIterator for RetryMap<I, F, B>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item) -> Result<T, E>,
+    B: FnMut(usize, &E),
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait RetryExt: Iterator {
+    /// Yields `f` of each item, calling it up to `retries` more times for
+    /// an item while it returns `Err`.
+    fn retry_map<F, T, E>(self, retries: usize, f: F) -> RetryMap<Self, F, fn(usize, &E)>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> Result<T, E>,
+    {
+        RetryMap {
+            orig: self,
+            f,
+            backoff: |_, _| {},
+            retries,
+        }
+    }
+}
+
+impl<I: Iterator> RetryExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+// A job that fails its first `fails` attempts, and counts them all.
+#[cfg(test)]
+struct Flaky {
+    fails: usize,
+    attempts: core::cell::Cell<usize>,
+}
+
+#[cfg(test)]
+fn flaky(fails: usize) -> Flaky {
+    Flaky {
+        fails,
+        attempts: core::cell::Cell::new(0),
+    }
+}
+
+#[cfg(test)]
+fn attempt(job: &Flaky) -> Result<usize, &'static str> {
+    job.attempts.set(job.attempts.get() + 1);
+    if job.attempts.get() <= job.fails {
+        Err("flaked")
+    } else {
+        Ok(job.fails)
+    }
+}
+
+#[test]
+fn retry_map_retries_until_it_works() {
+    let jobs = [flaky(0), flaky(2), flaky(3)];
+    let results: Vec<_> = jobs.iter().retry_map(3, |job| attempt(job)).collect();
+    assert_eq!(results, [Ok(0), Ok(2), Ok(3)]);
+    let attempts: Vec<_> = jobs.iter().map(|job| job.attempts.get()).collect();
+    assert_eq!(attempts, [1, 3, 4]);
+}
+
+#[test]
+fn retry_map_gives_up_after_the_retries() {
+    let jobs = [flaky(1), flaky(5), flaky(0)];
+    let results: Vec<_> = jobs.iter().retry_map(2, |job| attempt(job)).collect();
+    // The failure doesn't stop the items after it.
+    assert_eq!(results, [Ok(1), Err("flaked"), Ok(0)]);
+    assert_eq!(jobs[1].attempts.get(), 3);
+
+    // With no retries it is `map`.
+    let job = flaky(1);
+    let once: Vec<_> = core::iter::once(&job)
+        .retry_map(0, |job| attempt(job))
+        .collect();
+    assert_eq!(once, [Err("flaked")]);
+    assert_eq!(job.attempts.get(), 1);
+}
+
+#[test]
+fn retry_map_calls_the_backoff_hook_between_attempts() {
+    let jobs = [flaky(2), flaky(0), flaky(9)];
+    let mut waits = Vec::new();
+    let results: Vec<_> = jobs
+        .iter()
+        .retry_map(2, |job| attempt(job))
+        .backoff(|n, e: &&str| waits.push((n, *e)))
+        .collect();
+    assert_eq!(results, [Ok(2), Ok(0), Err("flaked")]);
+    // Never after the last attempt, which has nothing to wait for.
+    assert_eq!(
+        waits,
+        [(1, "flaked"), (2, "flaked"), (1, "flaked"), (2, "flaked")]
+    );
+}
@@ -0,0 +1,250 @@
+//! Run-length encoding: `rle()` turns each run of equal items into one
+//! `(item, count)` pair, and `rld()` turns `(item, count)` pairs back into
+//! the runs, so that `it.rle().rld()` yields what `it` did.
+//!
+//! ```text
+//! a a a b c c  --rle-->  (a, 3) (b, 1) (c, 2)  --rld-->  a a a b c c
+//! ```
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Groups the items of an iterator into runs of equal items. Holds on to
+/// the first item of the next run, which was read to find where the
+/// current one ends.
+pub(super) struct Runs<I: Iterator> {
+    orig: I,
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator> Runs<I> {
+    pub(super) fn new(orig: I) -> Self {
+        Runs {
+            orig,
+            pending: None,
+        }
+    }
+
+    /// The next run's first item and its length.
+    pub(super) fn next_run(&mut self) -> Option<(I::Item, usize)>
+    where
+        I::Item: PartialEq,
+    {
+        let first = self.pending.take().or_else(|| self.orig.next())?;
+        let mut count = 1;
+        for item in self.orig.by_ref() {
+            if item != first {
+                self.pending = Some(item);
+                break;
+            }
+            count += 1;
+        }
+        Some((first, count))
+    }
+
+    /// Between one run, if there are any items left at all, and one run
+    /// per item.
+    pub(super) fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.orig.size_hint();
+        let pending = self.pending.is_some() as usize;
+        let lower = lower.saturating_add(pending).min(1);
+        (lower, upper.and_then(|n| n.checked_add(pending)))
+    }
+}
+
+impl<I> Clone for Runs<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Runs {
+            orig: self.orig.clone(),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<I> fmt::Debug for Runs<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runs")
+            .field("orig", &self.orig)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+// Step 1: Define a struct for each adapter.
+pub struct Rle<I: Iterator> {
+    runs: Runs<I>,
+}
+
+impl<I> Clone for Rle<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Rle {
+            runs: self.runs.clone(),
+        }
+    }
+}
+
+impl<I> fmt::Debug for Rle<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rle").field("runs", &self.runs).finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rld<I, T> {
+    orig: I,
+    // The run being expanded, and how many of it are left to yield.
+    current: Option<(T, usize)>,
+}
+
+// Step 2: Implement `Iterator`.
+impl<I> Iterator for Rle<I>
+where
+    I: Iterator,
+    I::Item: PartialEq,
+{
+    type Item = (I::Item, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runs.next_run()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.runs.size_hint()
+    }
+}
+
+impl<I> FusedIterator for Rle<I>
+where
+    I: FusedIterator,
+    I::Item: PartialEq,
+{
+}
+
+impl<I, T> Iterator for Rld<I, T>
+where
+    I: Iterator<Item = (T, usize)>,
+    T: Clone,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            match &mut self.current {
+                Some((item, left)) if *left > 1 => {
+                    *left -= 1;
+                    return Some(item.clone());
+                }
+                // The last of a run can be moved out rather than cloned.
+                Some((_, 1)) => return self.current.take().map(|(item, _)| item),
+                _ => self.current = Some(self.orig.next()?),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.current.as_ref().map_or(0, |&(_, left)| left);
+        match self.orig.size_hint() {
+            // Nothing after the current run.
+            (_, Some(0)) => (left, Some(left)),
+            // The runs to come could have any length, including 0.
+            _ => (left, None),
+        }
+    }
+}
+
+impl<I, T> FusedIterator for Rld<I, T>
+where
+    I: FusedIterator<Item = (T, usize)>,
+    T: Clone,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait RleExt: Iterator {
+    /// Each run of equal items as the item and the length of the run.
+    fn rle(self) -> Rle<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq,
+    {
+        Rle {
+            runs: Runs::new(self),
+        }
+    }
+
+    /// Each `(item, count)` pair as `count` copies of `item`. A count of
+    /// 0 yields nothing.
+    fn rld<T>(self) -> Rld<Self, T>
+    where
+        Self: Sized + Iterator<Item = (T, usize)>,
+        T: Clone,
+    {
+        Rld {
+            orig: self,
+            current: None,
+        }
+    }
+}
+
+impl<I: Iterator> RleExt for I {}
+
+#[cfg(test)]
+use alloc::{string::String, vec::Vec};
+
+#[test]
+fn rle_counts_runs() {
+    assert_iter_eq!("aaabcc".chars().rle(), [('a', 3), ('b', 1), ('c', 2)]);
+    assert_iter_eq!([1, 2, 1].into_iter().rle(), [(1, 1), (2, 1), (1, 1)]);
+    assert_iter_eq!([7; 5].into_iter().rle(), [(7, 5)]);
+    assert_eq!(core::iter::empty::<u8>().rle().next(), None);
+
+    let mut it = [0, 0, 1].into_iter().rle();
+    assert_eq!(it.size_hint(), (1, Some(3)));
+    it.next();
+    // The 1 has been read, and is all that's left.
+    assert_eq!(it.size_hint(), (1, Some(1)));
+}
+
+#[test]
+fn rld_expands_pairs() {
+    let text: String = [('a', 3), ('b', 0), ('c', 2)].into_iter().rld().collect();
+    assert_eq!(text, "aaacc");
+    assert_eq!(core::iter::empty::<(u8, usize)>().rld().next(), None);
+
+    let mut it = [("x", 2)].into_iter().rld();
+    assert_eq!(it.size_hint(), (0, None));
+    it.next();
+    assert_eq!(it.size_hint(), (1, Some(1)));
+}
+
+#[test]
+fn rle_then_rld_round_trips() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(1402);
+    for len in 0..200 {
+        // Few distinct values, so there are runs to find.
+        let items: Vec<u8> = (0..len).map(|_| rng.gen_range(0..3)).collect();
+        let runs: Vec<(u8, usize)> = items.iter().copied().rle().collect();
+        assert!(runs.windows(2).all(|w| w[0].0 != w[1].0));
+        assert_eq!(runs.iter().map(|&(_, n)| n).sum::<usize>(), len);
+        assert_eq!(runs.into_iter().rld().collect::<Vec<_>>(), items);
+    }
+}
@@ -0,0 +1,427 @@
+//! `split_when` cuts an iterator into `Vec<T>` segments wherever a predicate
+//! fires, like `slice::split` but for any iterator. `split_between` does the
+//! same with a predicate over each pair of adjacent elements.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// What happens to an element that matched the predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// The delimiter is discarded (`slice::split`). Every delimiter closes a
+    /// segment, so leading/trailing delimiters produce empty segments.
+    Drop,
+    /// The delimiter closes the segment it ends (`slice::split_inclusive`).
+    KeepTrailing,
+    /// The delimiter opens the next segment.
+    KeepLeading,
+}
+
+// Step 1: Define a struct for the custom adapter.
+pub struct SplitWhen<I, P>
+where
+    I: Iterator,
+{
+    orig: I,
+    pred: P,
+    delimiter: Delimiter,
+    // A `KeepLeading` delimiter that belongs to the next segment.
+    carry: Option<I::Item>,
+    done: bool,
+}
+
+impl<I, P> Clone for SplitWhen<I, P>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        SplitWhen {
+            orig: self.orig.clone(),
+            pred: self.pred.clone(),
+            delimiter: self.delimiter,
+            carry: self.carry.clone(),
+            done: self.done,
+        }
+    }
+}
+
+impl<I, P> fmt::Debug for SplitWhen<I, P>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitWhen")
+            .field("orig", &self.orig)
+            .field("delimiter", &self.delimiter)
+            .field("carry", &self.carry)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+// Step 2: Implement `Iterator` for the custom adapter.
+impl<I, P> Iterator for SplitWhen<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut segment: Vec<I::Item> = self.carry.take().into_iter().collect();
+        for item in self.orig.by_ref() {
+            if !(self.pred)(&item) {
+                segment.push(item);
+                continue;
+            }
+            match self.delimiter {
+                Delimiter::Drop => return Some(segment),
+                Delimiter::KeepTrailing => {
+                    segment.push(item);
+                    return Some(segment);
+                }
+                Delimiter::KeepLeading if segment.is_empty() => segment.push(item),
+                Delimiter::KeepLeading => {
+                    self.carry = Some(item);
+                    return Some(segment);
+                }
+            }
+        }
+
+        self.done = true;
+        // Only `Drop` reports the (possibly empty) segment after the last
+        // delimiter, exactly like `slice::split`.
+        if self.delimiter == Delimiter::Drop || !segment.is_empty() {
+            Some(segment)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let (lower, upper) = self.orig.size_hint();
+        let carried = self.carry.is_some() as usize;
+        match self.delimiter {
+            // n items hold at most n delimiters, so at most n + 1 segments,
+            // and the final segment is always reported.
+            Delimiter::Drop => (1, upper.and_then(|n| n.checked_add(1))),
+            // Each segment holds at least one item.
+            _ => {
+                let lower = lower.saturating_add(carried).min(1);
+                (lower, upper.and_then(|n| n.checked_add(carried)))
+            }
+        }
+    }
+}
+
+// Every path that returns `None` sets `done` first.
+impl<I, P> FusedIterator for SplitWhen<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+}
+
+pub struct SplitBetween<I, F>
+where
+    I: Iterator,
+{
+    orig: I,
+    f: F,
+    // First element of the segment that hasn't been returned yet.
+    head: Option<I::Item>,
+}
+
+impl<I, F> Clone for SplitBetween<I, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        SplitBetween {
+            orig: self.orig.clone(),
+            f: self.f.clone(),
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<I, F> fmt::Debug for SplitBetween<I, F>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SplitBetween")
+            .field("orig", &self.orig)
+            .field("head", &self.head)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, F> Iterator for SplitBetween<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> bool,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut segment = vec![self.head.take().or_else(|| self.orig.next())?];
+        for item in self.orig.by_ref() {
+            if (self.f)(segment.last().unwrap(), &item) {
+                self.head = Some(item);
+                break;
+            }
+            segment.push(item);
+        }
+        Some(segment)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.orig.size_hint();
+        let head = self.head.is_some() as usize;
+        let lower = lower.saturating_add(head).min(1);
+        (lower, upper.and_then(|n| n.checked_add(head)))
+    }
+}
+
+impl<I, F> FusedIterator for SplitBetween<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item, &I::Item) -> bool,
+{
+}
+
+// Step 3: Define a new extension trait with the new operator to be
+//         added, as a sub-trait of `Iterator`.
+pub trait SplitWhenExt: Iterator {
+    /// Splits at every element matching `pred`, dropping the delimiters.
+    fn split_when<P>(self, pred: P) -> SplitWhen<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        self.split_when_with(pred, Delimiter::Drop)
+    }
+
+    /// Splits at every element matching `pred`, with `delimiter` deciding
+    /// where the matching element ends up.
+    fn split_when_with<P>(self, pred: P, delimiter: Delimiter) -> SplitWhen<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool;
+
+    /// Splits between `a` and `b` whenever `f(a, b)` holds. Never yields an
+    /// empty segment.
+    fn split_between<F>(self, f: F) -> SplitBetween<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> bool;
+}
+
+// Step 4: Implement the trait for all types that implement `Iterator`.
+impl<I: Iterator> SplitWhenExt for I {
+    fn split_when_with<P>(self, pred: P, delimiter: Delimiter) -> SplitWhen<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        SplitWhen {
+            orig: self,
+            pred,
+            delimiter,
+            carry: None,
+            done: false,
+        }
+    }
+
+    fn split_between<F>(self, f: F) -> SplitBetween<Self, F>
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> bool,
+    {
+        SplitBetween {
+            orig: self,
+            f,
+            head: None,
+        }
+    }
+}
+
+#[test]
+fn split_when_matches_slice_split() {
+    let inputs: [&[i32]; 5] = [&[1, 0, 2, 3, 0, 4], &[0, 1, 2], &[1, 2, 0], &[0, 0], &[]];
+
+    for input in inputs {
+        let expected: Vec<Vec<i32>> = input.split(|&x| x == 0).map(|s| s.to_vec()).collect();
+        let result: Vec<_> = input.iter().copied().split_when(|&x| x == 0).collect();
+        assert_eq!(result, expected, "input = {input:?}");
+    }
+}
+
+#[test]
+fn split_when_leading_and_trailing_delimiters() {
+    let result: Vec<_> = [0, 1, 0].into_iter().split_when(|&x| x == 0).collect();
+    assert_eq!(result, [vec![], vec![1], vec![]]);
+}
+
+#[test]
+fn split_when_keep_trailing() {
+    let result: Vec<_> = "a,b,,c,"
+        .chars()
+        .split_when_with(|&c| c == ',', Delimiter::KeepTrailing)
+        .collect();
+    assert_eq!(
+        result,
+        [vec!['a', ','], vec!['b', ','], vec![','], vec!['c', ',']]
+    );
+
+    let result: Vec<_> = ",a"
+        .chars()
+        .split_when_with(|&c| c == ',', Delimiter::KeepTrailing)
+        .collect();
+    assert_eq!(result, [vec![','], vec!['a']]);
+}
+
+#[test]
+fn split_when_keep_leading() {
+    let lines = ["# one", "a", "b", "# two", "c", "# three"];
+
+    let sections: Vec<_> = lines
+        .into_iter()
+        .split_when_with(|line| line.starts_with('#'), Delimiter::KeepLeading)
+        .collect();
+    assert_eq!(
+        sections,
+        [vec!["# one", "a", "b"], vec!["# two", "c"], vec!["# three"]]
+    );
+
+    let result: Vec<_> = [1, 2, 0, 3]
+        .into_iter()
+        .split_when_with(|&x| x == 0, Delimiter::KeepLeading)
+        .collect();
+    assert_eq!(result, [vec![1, 2], vec![0, 3]]);
+}
+
+#[test]
+fn split_when_empty_input() {
+    let empty = std::iter::empty::<i32>;
+
+    assert_eq!(empty().split_when(|_| true).collect::<Vec<_>>(), [vec![]]);
+    for delimiter in [Delimiter::KeepLeading, Delimiter::KeepTrailing] {
+        assert_eq!(empty().split_when_with(|_| true, delimiter).count(), 0);
+    }
+}
+
+#[test]
+fn split_between_adjacent_pairs() {
+    // Split into strictly increasing runs.
+    let runs: Vec<_> = [1, 2, 5, 3, 4, 4, 9]
+        .into_iter()
+        .split_between(|a, b| b <= a)
+        .collect();
+    assert_eq!(runs, [vec![1, 2, 5], vec![3, 4], vec![4, 9]]);
+
+    let none: Vec<Vec<i32>> = std::iter::empty().split_between(|_, _| true).collect();
+    assert!(none.is_empty());
+
+    let single: Vec<_> = [7].into_iter().split_between(|_, _| true).collect();
+    assert_eq!(single, [vec![7]]);
+}
+
+#[test]
+fn split_when_size_hint() {
+    let mut it = [1, 0, 2].into_iter().split_when(|&x| x == 0);
+    assert_eq!(it.size_hint(), (1, Some(4)));
+    it.next();
+    assert_eq!(it.size_hint(), (1, Some(2)));
+    it.next();
+    assert_eq!(it.size_hint(), (0, Some(0)));
+
+    let mut it = [0, 1, 0, 2]
+        .into_iter()
+        .split_when_with(|&x| x == 0, Delimiter::KeepLeading);
+    it.next();
+    // The second `0` is carried over into the next segment.
+    assert_eq!(it.size_hint(), (1, Some(2)));
+
+    let it = std::iter::empty::<i32>().split_when_with(|_| true, Delimiter::KeepTrailing);
+    assert_eq!(it.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn split_between_size_hint() {
+    let mut it = [1, 2, 1].into_iter().split_between(|a, b| b < a);
+    assert_eq!(it.size_hint(), (1, Some(3)));
+    it.next();
+    assert_eq!(it.size_hint(), (1, Some(1)));
+    it.next();
+    assert_eq!(it.size_hint(), (0, Some(0)));
+}
+
+#[test]
+fn split_adapters_size_hint_on_endless_input() {
+    let mut it = [0, 1]
+        .into_iter()
+        .cycle()
+        .split_when_with(|&x| x == 0, Delimiter::KeepLeading);
+    assert_eq!(it.next(), Some(vec![0, 1]));
+    assert_eq!(it.size_hint(), (1, None));
+
+    let mut it = [1, 0].into_iter().cycle().split_between(|a, b| b < a);
+    assert_eq!(it.next(), Some(vec![1]));
+    assert_eq!(it.size_hint(), (1, None));
+}
+
+#[test]
+fn split_adapters_stay_exhausted() {
+    // `from_fn` isn't fused: it resumes after its first `None`.
+    let mut calls = 0;
+    let flaky = move || {
+        calls += 1;
+        (calls != 2).then_some(0)
+    };
+
+    let mut it = std::iter::from_fn(flaky).split_when(|&x| x == 0);
+    assert_eq!(it.next(), Some(vec![]));
+    assert_eq!(it.next(), Some(vec![]));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+
+    let mut it = [1].into_iter().split_between(|_, _| true);
+    assert_eq!(it.next(), Some(vec![1]));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn split_adapters_clone_independently() {
+    let mut it = [1, 0, 2, 0, 3].into_iter().split_when(|&x| x == 0);
+    assert_eq!(it.next(), Some(vec![1]));
+
+    let copy = it.clone();
+    assert_eq!(it.next(), Some(vec![2]));
+    assert_eq!(copy.collect::<Vec<_>>(), [vec![2], vec![3]]);
+    assert_eq!(it.collect::<Vec<_>>(), [vec![3]]);
+
+    let mut runs = [1, 2, 1, 2].into_iter().split_between(|a, b| b < a);
+    runs.next();
+    let copy = runs.clone();
+    assert_eq!(runs.collect::<Vec<_>>(), copy.collect::<Vec<_>>());
+    assert_eq!(
+        format!("{:?}", [1].into_iter().split_between(|_, _| true)),
+        "SplitBetween { orig: IntoIter([1]), head: None, .. }"
+    );
+}
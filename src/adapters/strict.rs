@@ -0,0 +1,264 @@
+//! Two guards that turn quiet misuse of an iterator into a panic that
+//! says what went wrong.
+//!
+//! `strict()` panics if `next()` is called again after it returned `None`.
+//! Only a `FusedIterator` promises anything about that call, so code that
+//! makes it works with some iterators and not others; under `strict()` it
+//! fails straight away.
+//!
+//! `consume_once()` is for iterators that get cloned and passed around:
+//! all the clones share a flag, and the second one to start iterating
+//! panics, since going over the same items twice is usually a mistake.
+
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::iter::FusedIterator;
+use core::panic::Location;
+
+// Step 1: Define a struct for each adapter.
+#[derive(Debug, Clone)]
+pub struct Strict<I> {
+    orig: I,
+    // Where `strict()` was called, for the panic message.
+    location: &'static Location<'static>,
+    exhausted: bool,
+}
+
+impl<I> Strict<I> {
+    #[track_caller]
+    fn after_exhaustion(&self) -> ! {
+        panic!(
+            "next() called after the iterator returned None (strict() at {}); \
+             call .fuse() first if that is meant to keep returning None",
+            self.location
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ConsumeOnce<I> {
+    orig: I,
+    location: &'static Location<'static>,
+    // Shared by all the clones: set by the first of them to start.
+    claimed: Rc<Cell<bool>>,
+    started: bool,
+}
+
+impl<I: Clone> Clone for ConsumeOnce<I> {
+    fn clone(&self) -> Self {
+        ConsumeOnce {
+            orig: self.orig.clone(),
+            location: self.location,
+            claimed: Rc::clone(&self.claimed),
+            // A clone of one that has started is a second consumer too.
+            started: false,
+        }
+    }
+}
+
+impl<I> ConsumeOnce<I> {
+    #[track_caller]
+    fn start(&mut self) {
+        if !self.started {
+            if self.claimed.replace(true) {
+                panic!(
+                    "the items of this iterator (consume_once() at {}) are being \
+                     iterated over a second time, through a clone; collect them \
+                     first if they are needed twice",
+                    self.location
+                );
+            }
+            self.started = true;
+        }
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I: Iterator> Iterator for Strict<I> {
+    type Item = I::Item;
+
+    #[track_caller]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            self.after_exhaustion();
+        }
+        let item = self.orig.next();
+        self.exhausted = item.is_none();
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exhausted {
+            (0, Some(0))
+        } else {
+            self.orig.size_hint()
+        }
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for Strict<I> {
+    #[track_caller]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            self.after_exhaustion();
+        }
+        let item = self.orig.next_back();
+        self.exhausted = item.is_none();
+        item
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Strict<I> {}
+
+// No `FusedIterator` for `Strict`: it panics where a fused iterator would
+// return `None`.
+
+impl<I: Iterator> Iterator for ConsumeOnce<I> {
+    type Item = I::Item;
+
+    #[track_caller]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.start();
+        self.orig.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+
+    #[track_caller]
+    fn fold<Acc, G>(mut self, init: Acc, g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        self.start();
+        self.orig.fold(init, g)
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for ConsumeOnce<I> {
+    #[track_caller]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.start();
+        self.orig.next_back()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for ConsumeOnce<I> {}
+
+impl<I: FusedIterator> FusedIterator for ConsumeOnce<I> {}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait StrictExt: Iterator {
+    /// Panics if `next()` or `next_back()` is called after either has
+    /// returned `None`.
+    #[track_caller]
+    fn strict(self) -> Strict<Self>
+    where
+        Self: Sized,
+    {
+        Strict {
+            orig: self,
+            location: Location::caller(),
+            exhausted: false,
+        }
+    }
+
+    /// Panics if this iterator and a clone of it both start yielding
+    /// items: only one of them may be used.
+    #[track_caller]
+    fn consume_once(self) -> ConsumeOnce<Self>
+    where
+        Self: Sized,
+    {
+        ConsumeOnce {
+            orig: self,
+            location: Location::caller(),
+            claimed: Rc::new(Cell::new(false)),
+            started: false,
+        }
+    }
+}
+
+impl<I: Iterator> StrictExt for I {}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+// Returns `None` every other call: the kind of iterator that `strict()` is
+// there to catch code relying on.
+#[cfg(test)]
+struct Flaky(u32);
+
+#[cfg(test)]
+impl Iterator for Flaky {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.0 += 1;
+        self.0.is_multiple_of(2).then_some(self.0)
+    }
+}
+
+#[test]
+fn strict_passes_a_well_behaved_use_through() {
+    let mut it = (1..4).strict();
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.next_back(), Some(3));
+    assert_eq!(it.by_ref().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(it.size_hint(), (0, Some(0)));
+
+    // Once `fuse()`d, calling again after `None` is fine.
+    let mut fused = Flaky(0).strict().fuse();
+    assert_eq!(fused.next(), None);
+    assert_eq!(fused.next(), None);
+}
+
+#[test]
+#[should_panic(expected = "next() called after the iterator returned None")]
+fn strict_panics_on_next_after_none() {
+    let mut it = Flaky(0).strict();
+    assert_eq!(it.next(), None);
+    // Without the guard this would be `Some(2)`.
+    it.next();
+}
+
+#[test]
+#[should_panic(expected = "strict() at src/adapters/strict.rs")]
+fn strict_names_where_it_was_added() {
+    let mut it = core::iter::empty::<()>().strict();
+    it.next();
+    it.next_back();
+}
+
+#[test]
+fn consume_once_allows_one_consumer() {
+    let it = [1, 2, 3].into_iter().consume_once();
+    let spare = it.clone();
+    assert_eq!(it.sum::<i32>(), 6);
+    // A clone that is never iterated is fine.
+    drop(spare);
+
+    let mut it = (0..4).consume_once();
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next_back(), Some(3));
+    assert_eq!(it.len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "iterated over a second time, through a clone")]
+fn consume_once_panics_on_the_second_consumer() {
+    let evens = (0..10).filter(|n| n % 2 == 0).consume_once();
+    let total: i32 = evens.clone().sum();
+    let count = evens.count();
+    assert_eq!((total, count), (20, 5));
+}
+
+#[test]
+#[should_panic(expected = "consume_once() at")]
+fn consume_once_catches_a_clone_taken_midway() {
+    let mut it = (0..3).consume_once();
+    it.next();
+    let mut rest = it.clone();
+    rest.next();
+}
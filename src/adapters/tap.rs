@@ -0,0 +1,197 @@
+//! Two ways to look at items on their way through a pipeline without
+//! touching them. `tap(f)` calls `f` on a reference to each item, like
+//! `Iterator::inspect`. `dbg()` is the `dbg!` macro for every item: it
+//! prints where it was called from, the item's index and the item to
+//! standard error.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::panic::Location;
+
+// Step 1: Define a struct for each adapter.
+#[derive(Clone)]
+pub struct Tap<I, F> {
+    orig: I,
+    f: F,
+}
+
+impl<I: fmt::Debug, F> fmt::Debug for Tap<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tap").field("orig", &self.orig).finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dbg<I> {
+    orig: I,
+    // Where `dbg()` was called, to tell apart the output of several.
+    location: &'static Location<'static>,
+    // The index of the next item from the front.
+    index: usize,
+}
+
+impl<I> Dbg<I> {
+    /// Where in the source `dbg()` was called.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    #[cfg(feature = "std")]
+    fn print(&self, index: usize, item: &impl fmt::Debug) {
+        std::eprintln!("[{}] #{index} = {item:#?}", self.location);
+    }
+
+    // Without `std` there's nowhere to print to.
+    #[cfg(not(feature = "std"))]
+    fn print(&self, _index: usize, _item: &impl fmt::Debug) {}
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, F> Iterator for Tap<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item),
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        (self.f)(&item);
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+
+    fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let mut f = self.f;
+        self.orig.fold(init, move |acc, item| {
+            f(&item);
+            g(acc, item)
+        })
+    }
+}
+
+impl<I, F> DoubleEndedIterator for Tap<I, F>
+where
+    I: DoubleEndedIterator,
+    F: FnMut(&I::Item),
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next_back()?;
+        (self.f)(&item);
+        Some(item)
+    }
+}
+
+impl<I, F> ExactSizeIterator for Tap<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&I::Item),
+{
+}
+
+impl<I, F> FusedIterator for Tap<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item),
+{
+}
+
+impl<I> Iterator for Dbg<I>
+where
+    I: Iterator,
+    I::Item: fmt::Debug,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        self.print(self.index, &item);
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Dbg<I>
+where
+    I: ExactSizeIterator,
+    I::Item: fmt::Debug,
+{
+}
+
+impl<I> FusedIterator for Dbg<I>
+where
+    I: FusedIterator,
+    I::Item: fmt::Debug,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait TapExt: Iterator {
+    /// Calls `f` on each item as it goes past.
+    fn tap<F>(self, f: F) -> Tap<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item),
+    {
+        Tap { orig: self, f }
+    }
+
+    /// Prints each item to standard error, with the file, line and column
+    /// of this call and the item's index: `[src/main.rs:4:10] #0 = 1`.
+    /// Without `std`, it prints nothing.
+    #[track_caller]
+    fn dbg(self) -> Dbg<Self>
+    where
+        Self: Sized,
+        Self::Item: fmt::Debug,
+    {
+        Dbg {
+            orig: self,
+            location: Location::caller(),
+            index: 0,
+        }
+    }
+}
+
+impl<I: Iterator> TapExt for I {}
+
+#[test]
+fn tap_sees_every_item_and_changes_none() {
+    let mut seen = Vec::new();
+    let doubled: Vec<i32> = (1..=3).tap(|&n| seen.push(n)).map(|n| n * 2).collect();
+    assert_eq!(doubled, [2, 4, 6]);
+    assert_eq!(seen, [1, 2, 3]);
+
+    // Only the items that are pulled get tapped.
+    let mut count = 0;
+    let first = (0..).tap(|_| count += 1).find(|n| n % 5 == 4);
+    assert_eq!((first, count), (Some(4), 5));
+
+    let mut seen = Vec::new();
+    let mut it = ["a", "b", "c"].into_iter().tap(|s| seen.push(*s));
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.next_back(), Some("c"));
+    assert_eq!(it.next(), Some("a"));
+    assert_eq!(seen, ["c", "a"]);
+}
+
+#[test]
+fn dbg_passes_items_through() {
+    let it = vec![(1, 'a'), (2, 'b')].into_iter().dbg();
+    // The location is this call, not somewhere inside the adapter.
+    assert_eq!(it.location().file(), file!());
+    assert_eq!(it.location().line(), line!() - 3);
+    assert_eq!(it.len(), 2);
+    assert_eq!(it.collect::<Vec<_>>(), [(1, 'a'), (2, 'b')]);
+    assert_eq!((0..10).dbg().filter(|n| n % 3 == 0).sum::<i32>(), 18);
+}
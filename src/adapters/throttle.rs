@@ -0,0 +1,281 @@
+//! `throttle(min_interval)` spaces the items out in time: each is yielded
+//! at least `min_interval` after the one before, sleeping for whatever is
+//! left of it first. That keeps a fast generator from sending more
+//! requests a second than an API allows.
+//!
+//! `burst(n)` lets up to `n` items through back to back, after a quiet
+//! spell that has saved up for them, while holding the average to the same
+//! rate; the default is 1, which is no burst at all. It works like a token
+//! bucket with room for `n` tokens and one coming in every `min_interval`.
+//!
+//! The time comes from a `Clock`, which is `SystemClock` unless
+//! `with_clock` says otherwise, so that tests can let time pass without
+//! waiting for it.
+
+use std::iter::FusedIterator;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Where `Throttle` gets the time from, and how it waits.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real time, and `thread::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration);
+    }
+}
+
+// Step 1: Define a struct for the adapter.
+#[derive(Debug, Clone)]
+pub struct Throttle<I, C> {
+    orig: I,
+    clock: C,
+    interval: Duration,
+    burst: u32,
+    bucket: Bucket,
+}
+
+// How full the token bucket is.
+#[derive(Debug, Clone, Copy)]
+enum Bucket {
+    // Nothing has gone through yet.
+    Full,
+    // When it would be full again if nothing else went through: each item
+    // moves it on by `interval`.
+    FullAt(Instant),
+    // Full again would be later than an `Instant` can say, so it never
+    // refills: `left` more items go through, and no more after them.
+    Draining { left: u32 },
+}
+
+impl<I, C> Throttle<I, C> {
+    /// Lets up to `n` items through without waiting, when there has been
+    /// time to save up for them.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    pub fn burst(mut self, n: u32) -> Self {
+        assert!(
+            n > 0,
+            "throttle: a burst of 0 would never let anything through"
+        );
+        self.burst = n;
+        self
+    }
+
+    /// Uses `clock` for the time and for waiting.
+    pub fn with_clock<D: Clock>(self, clock: D) -> Throttle<I, D> {
+        Throttle {
+            orig: self.orig,
+            clock,
+            interval: self.interval,
+            burst: self.burst,
+            bucket: self.bucket,
+        }
+    }
+}
+
+// Step 2: Implement `Iterator`.
+impl<I: Iterator, C: Clock> Iterator for Throttle<I, C> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        let mut now = self.clock.now();
+        let from = match self.bucket {
+            Bucket::Full => now,
+            Bucket::FullAt(full_at) => {
+                // The bucket has a token once it is no more than `burst - 1`
+                // intervals from full. Savings too long for a `Duration`, or
+                // reaching back before any `Instant`, cover any wait.
+                let ready_at = self
+                    .interval
+                    .checked_mul(self.burst - 1)
+                    .and_then(|saved| full_at.checked_sub(saved));
+                if let Some(wait) = ready_at.and_then(|at| at.checked_duration_since(now)) {
+                    if !wait.is_zero() {
+                        self.clock.sleep(wait);
+                        now += wait;
+                    }
+                }
+                full_at.max(now)
+            }
+            Bucket::Draining { left: 0 } => loop {
+                self.clock.sleep(Duration::MAX);
+            },
+            Bucket::Draining { left } => {
+                self.bucket = Bucket::Draining { left: left - 1 };
+                return Some(item);
+            }
+        };
+        self.bucket = match from.checked_add(self.interval) {
+            Some(full_at) => Bucket::FullAt(full_at),
+            // `interval` isn't zero here. Each interval the bucket is short
+            // of full is a token it doesn't hold.
+            None => {
+                let short = (from - now).as_nanos().div_ceil(self.interval.as_nanos());
+                let left = u128::from(self.burst - 1).saturating_sub(short) as u32;
+                Bucket::Draining { left }
+            }
+        };
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator, C: Clock> ExactSizeIterator for Throttle<I, C> {}
+
+impl<I: FusedIterator, C: Clock> FusedIterator for Throttle<I, C> {}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait ThrottleExt: Iterator {
+    /// Yields the items no closer together than `min_interval`.
+    fn throttle(self, min_interval: Duration) -> Throttle<Self, SystemClock>
+    where
+        Self: Sized,
+    {
+        Throttle {
+            orig: self,
+            clock: SystemClock,
+            interval: min_interval,
+            burst: 1,
+            bucket: Bucket::Full,
+        }
+    }
+}
+
+impl<I: Iterator> ThrottleExt for I {}
+
+// A clock that only moves when something sleeps on it, and keeps a log of
+// the sleeps.
+#[cfg(test)]
+struct MockClock {
+    start: Instant,
+    elapsed: std::cell::Cell<Duration>,
+    sleeps: std::cell::RefCell<Vec<Duration>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    fn new() -> Self {
+        MockClock {
+            start: Instant::now(),
+            elapsed: Default::default(),
+            sleeps: Default::default(),
+        }
+    }
+
+    fn advance(&self, by: Duration) {
+        self.elapsed.set(self.elapsed.get() + by);
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+        self.sleeps.borrow_mut().push(duration);
+    }
+}
+
+#[cfg(test)]
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn throttle_spaces_items_out() {
+    let clock = MockClock::new();
+    let items: Vec<_> = (0..4).throttle(ms(100)).with_clock(&clock).collect();
+    assert_eq!(items, [0, 1, 2, 3]);
+    // The first goes straight through.
+    assert_eq!(*clock.sleeps.borrow(), [ms(100), ms(100), ms(100)]);
+    assert_eq!(clock.elapsed.get(), ms(300));
+}
+
+#[test]
+fn throttle_counts_time_spent_elsewhere() {
+    let clock = MockClock::new();
+    let mut it = (0..4).throttle(ms(100)).with_clock(&clock);
+    it.next();
+    clock.advance(ms(30));
+    it.next();
+    // Slower than the limit already: no wait at all.
+    clock.advance(ms(250));
+    it.next();
+    it.next();
+    assert_eq!(*clock.sleeps.borrow(), [ms(70), ms(100)]);
+}
+
+#[test]
+fn throttle_with_a_burst() {
+    let clock = MockClock::new();
+    let mut it = (0..10).throttle(ms(100)).burst(3).with_clock(&clock);
+    // Three at once, then one per interval.
+    it.by_ref().take(3).for_each(drop);
+    assert!(clock.sleeps.borrow().is_empty());
+    it.by_ref().take(2).for_each(drop);
+    assert_eq!(*clock.sleeps.borrow(), [ms(100), ms(100)]);
+
+    // A quiet spell saves up for another burst, but no more than three.
+    clock.advance(ms(1000));
+    clock.sleeps.borrow_mut().clear();
+    it.by_ref().take(3).for_each(drop);
+    assert!(clock.sleeps.borrow().is_empty());
+    it.next();
+    assert_eq!(*clock.sleeps.borrow(), [ms(100)]);
+}
+
+#[test]
+fn throttle_with_an_endless_interval() {
+    assert_eq!((0..3).throttle(Duration::MAX).next(), Some(0));
+
+    // The bucket never refills, but what it holds still goes through.
+    let clock = MockClock::new();
+    let mut it = (0..3).throttle(Duration::MAX).burst(2).with_clock(&clock);
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.next(), Some(1));
+    assert!(clock.sleeps.borrow().is_empty());
+}
+
+#[test]
+fn throttle_keeps_the_size() {
+    let it = [1, 2, 3].iter().throttle(ms(1));
+    assert_eq!(it.len(), 3);
+    assert_eq!(
+        std::iter::empty::<u8>()
+            .throttle(ms(1))
+            .with_clock(MockClock::new())
+            .next(),
+        None
+    );
+}
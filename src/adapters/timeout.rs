@@ -0,0 +1,180 @@
+//! `with_timeout(limit)` keeps a slow or stuck iterator from holding up
+//! whoever is waiting on it. The iterator runs on a thread of its own, as
+//! with `prefetch`, and hands each item over a channel; when one takes
+//! longer than `limit` to arrive, `next()` yields `Err(Timeout)` instead
+//! of waiting on.
+//!
+//! A timeout doesn't lose anything: the item is still on its way, and the
+//! next call to `next()` waits for it again. It is up to the consumer to
+//! stop after a timeout, or after a few of them, with `take_while` or
+//! `map_while`. Dropping the adapter leaves the thread to finish on its
+//! own, rather than joining it, since it may be stuck for good.
+
+use std::error::Error;
+use std::fmt;
+use std::iter::FusedIterator;
+use std::panic;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// An item didn't arrive within the time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    pub limit: Duration,
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no item arrived within {:?}", self.limit)
+    }
+}
+
+impl Error for Timeout {}
+
+pub struct WithTimeout<T> {
+    // Both are `None` once the producer has finished.
+    items: Option<Receiver<T>>,
+    producer: Option<JoinHandle<()>>,
+    limit: Duration,
+}
+
+impl<T: Send + 'static> WithTimeout<T> {
+    fn new<I>(orig: I, limit: Duration) -> Self
+    where
+        I: Iterator<Item = T> + Send + 'static,
+    {
+        // With no buffer the producer gets at most one item ahead.
+        let (sender, items) = mpsc::sync_channel(0);
+        let producer = thread::spawn(move || {
+            for item in orig {
+                if sender.send(item).is_err() {
+                    return;
+                }
+            }
+        });
+        WithTimeout {
+            items: Some(items),
+            producer: Some(producer),
+            limit,
+        }
+    }
+}
+
+impl<T> Iterator for WithTimeout<T> {
+    type Item = Result<T, Timeout>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.items.as_ref()?.recv_timeout(self.limit) {
+            Ok(item) => return Some(Ok(item)),
+            Err(RecvTimeoutError::Timeout) => {
+                return Some(Err(Timeout { limit: self.limit }));
+            }
+            Err(RecvTimeoutError::Disconnected) => {}
+        }
+        // The producer has returned, so joining it can't hang. A panic on
+        // its thread is raised again here, as `prefetch` does.
+        self.items = None;
+        if let Some(producer) = self.producer.take() {
+            if let Err(payload) = producer.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+        None
+    }
+}
+
+impl<T> FusedIterator for WithTimeout<T> {}
+
+impl<T> fmt::Debug for WithTimeout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithTimeout")
+            .field("limit", &self.limit)
+            .field("finished", &self.items.is_none())
+            .finish()
+    }
+}
+
+pub trait TimeoutExt: Iterator {
+    /// Runs this iterator on a thread of its own, and yields `Err(Timeout)`
+    /// whenever an item takes longer than `limit` to come.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator panics on its thread, the panic is raised again by
+    /// `next()` once the items before it have been yielded.
+    fn with_timeout(self, limit: Duration) -> WithTimeout<Self::Item>
+    where
+        Self: Sized + Send + 'static,
+        Self::Item: Send + 'static,
+    {
+        WithTimeout::new(self, limit)
+    }
+}
+
+impl<I: Iterator> TimeoutExt for I {}
+
+#[cfg(test)]
+use std::time::Instant;
+
+#[test]
+fn with_timeout_passes_prompt_items_through() {
+    let items: Vec<_> = (0..5).with_timeout(Duration::from_secs(5)).collect();
+    assert_eq!(items, [Ok(0), Ok(1), Ok(2), Ok(3), Ok(4)]);
+
+    let mut empty = std::iter::empty::<u8>().with_timeout(Duration::from_secs(5));
+    assert_eq!(empty.next(), None);
+    assert_eq!(empty.next(), None);
+}
+
+#[test]
+fn with_timeout_reports_a_slow_item_and_still_yields_it() {
+    let limit = Duration::from_millis(20);
+    let mut it = (0..3)
+        .inspect(|&n| {
+            if n == 1 {
+                thread::sleep(Duration::from_millis(100));
+            }
+        })
+        .with_timeout(limit);
+    assert_eq!(it.next(), Some(Ok(0)));
+    assert_eq!(it.next(), Some(Err(Timeout { limit })));
+    // Waiting again gives the late item its chance.
+    let late = std::iter::from_fn(|| it.next()).find(Result::is_ok);
+    assert_eq!(late, Some(Ok(1)));
+    assert_eq!(it.next(), Some(Ok(2)));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn with_timeout_gets_a_pipeline_past_a_quiet_channel() {
+    // The receiver's iterator would wait for as long as the sender lives.
+    let (tx, rx) = mpsc::channel();
+    let sender = thread::spawn(move || {
+        for n in 0..3 {
+            tx.send(n).unwrap();
+        }
+        thread::sleep(Duration::from_millis(500));
+        drop(tx);
+    });
+    let start = Instant::now();
+    let got: Vec<i32> = rx
+        .into_iter()
+        .with_timeout(Duration::from_millis(50))
+        .map_while(Result::ok)
+        .collect();
+    assert_eq!(got, [0, 1, 2]);
+    assert!(start.elapsed() < Duration::from_millis(400));
+    sender.join().unwrap();
+}
+
+#[test]
+fn with_timeout_raises_producer_panics() {
+    let mut it = (0..3)
+        .inspect(|&n| assert_ne!(n, 1, "one is too many"))
+        .with_timeout(Duration::from_secs(5));
+    assert_eq!(it.next(), Some(Ok(0)));
+    let err = panic::catch_unwind(panic::AssertUnwindSafe(|| it.next())).unwrap_err();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains("one is too many"));
+}
@@ -0,0 +1,199 @@
+//! `trace(label)` prints `"{label}: yield {item:?}"` for every item that
+//! passes through, so a pipeline with a `trace` after each stage prints
+//! how each item makes its way down it. `trace_to` sends the lines to any
+//! `TraceSink` instead, such as a buffer a test can look at.
+
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Where trace lines go.
+pub trait TraceSink {
+    fn line(&mut self, line: String);
+}
+
+/// Prints each line to standard output.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Print;
+
+#[cfg(feature = "std")]
+impl TraceSink for Print {
+    fn line(&mut self, line: String) {
+        std::println!("{line}");
+    }
+}
+
+/// Collects the lines.
+impl TraceSink for Vec<String> {
+    fn line(&mut self, line: String) {
+        self.push(line);
+    }
+}
+
+impl<S: TraceSink + ?Sized> TraceSink for &mut S {
+    fn line(&mut self, line: String) {
+        (**self).line(line);
+    }
+}
+
+/// A sink that several stages can share, so their lines end up
+/// interleaved in the order they happened.
+impl<S: TraceSink + ?Sized> TraceSink for Rc<RefCell<S>> {
+    fn line(&mut self, line: String) {
+        self.borrow_mut().line(line);
+    }
+}
+
+// Step 1: Define a struct for the custom adapter.
+#[derive(Debug, Clone)]
+pub struct Trace<I, S> {
+    orig: I,
+    label: &'static str,
+    sink: S,
+}
+
+// Step 2: Implement `Iterator`.
+impl<I, S> Iterator for Trace<I, S>
+where
+    I: Iterator,
+    I::Item: fmt::Debug,
+    S: TraceSink,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next()?;
+        self.sink.line(format!("{}: yield {item:?}", self.label));
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orig.size_hint()
+    }
+
+    fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let (label, mut sink) = (self.label, self.sink);
+        self.orig.fold(init, move |acc, item| {
+            sink.line(format!("{label}: yield {item:?}"));
+            g(acc, item)
+        })
+    }
+}
+
+impl<I, S> DoubleEndedIterator for Trace<I, S>
+where
+    I: DoubleEndedIterator,
+    I::Item: fmt::Debug,
+    S: TraceSink,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let item = self.orig.next_back()?;
+        self.sink.line(format!("{}: yield {item:?}", self.label));
+        Some(item)
+    }
+}
+
+impl<I, S> ExactSizeIterator for Trace<I, S>
+where
+    I: ExactSizeIterator,
+    I::Item: fmt::Debug,
+    S: TraceSink,
+{
+}
+
+impl<I, S> FusedIterator for Trace<I, S>
+where
+    I: FusedIterator,
+    I::Item: fmt::Debug,
+    S: TraceSink,
+{
+}
+
+// Step 3: Define an extension trait and implement it for all iterators.
+pub trait TraceExt: Iterator {
+    /// Prints `"{label}: yield {item:?}"` for each item.
+    #[cfg(feature = "std")]
+    fn trace(self, label: &'static str) -> Trace<Self, Print>
+    where
+        Self: Sized,
+        Self::Item: fmt::Debug,
+    {
+        self.trace_to(label, Print)
+    }
+
+    /// Sends `"{label}: yield {item:?}"` to `sink` for each item.
+    fn trace_to<S: TraceSink>(self, label: &'static str, sink: S) -> Trace<Self, S>
+    where
+        Self: Sized,
+        Self::Item: fmt::Debug,
+    {
+        Trace {
+            orig: self,
+            label,
+            sink,
+        }
+    }
+}
+
+impl<I: Iterator> TraceExt for I {}
+
+#[test]
+fn trace_lines_follow_the_items_down_the_pipeline() {
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let result: Vec<i32> = (1..=4)
+        .trace_to("source", lines.clone())
+        .filter(|n| n % 2 == 0)
+        .trace_to("even", lines.clone())
+        .map(|n| n * n)
+        .trace_to("square", lines.clone())
+        .collect();
+    assert_eq!(result, [4, 16]);
+    assert_eq!(
+        *lines.borrow(),
+        [
+            "source: yield 1",
+            "source: yield 2",
+            "even: yield 2",
+            "square: yield 4",
+            "source: yield 3",
+            "source: yield 4",
+            "even: yield 4",
+            "square: yield 16",
+        ]
+    );
+}
+
+#[test]
+fn trace_passes_items_through_unchanged() {
+    let mut lines = Vec::new();
+    let words: Vec<&str> = ["b", "a"]
+        .into_iter()
+        .rev()
+        .trace_to("words", &mut lines)
+        .collect();
+    assert_eq!(words, ["a", "b"]);
+    assert_eq!(lines, ["words: yield \"a\"", "words: yield \"b\""]);
+
+    // Tracing from the back works too, and only yields are traced.
+    let mut lines = Vec::new();
+    let mut it = (0..3).trace_to("n", &mut lines);
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.next_back(), Some(2));
+    assert_eq!(it.by_ref().count(), 2);
+    assert_eq!(it.next(), None);
+    assert_eq!(lines, ["n: yield 2", "n: yield 0", "n: yield 1"]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn trace_prints_without_getting_in_the_way() {
+    assert_eq!((0..3).trace("printed").sum::<i32>(), 3);
+}
@@ -0,0 +1,530 @@
+//! `unique` yields each item once, the first time it turns up, like the
+//! `Unique` from `i6_iterator_adapters`.
+//!
+//! The items seen so far go in a `HashSet`, so items need `Hash + Eq`,
+//! and `unique()` needs `std`: without it there is no `HashSet` (it needs a
+//! random source for its hasher). `unique_ord()` puts them in a `BTreeSet`
+//! instead, so items need `Ord`; it is the one to use without `std`, and for
+//! items that are `Ord` but not `Hash`. `unique_with_hasher(hasher)` uses a
+//! `HashSet` with some other hasher than the default, such as a faster one,
+//! or a fixed one to make the set's behaviour repeatable.
+//!
+//! All of those put a clone of each new item in the set, and hash or
+//! compare whole items, which is wasteful for big items told apart by a
+//! small part of them. `unique_by_key_owned(key)`, also `std` only, keeps
+//! only `key(item)` in the set: the items are never cloned (they needn't even be `Clone`),
+//! and two items with the same key count as repeats. The `distinct_records`
+//! benchmark compares the two on records with a kilobyte of payload each.
+//!
+//! `unique_sorted()` is for input that is already sorted, where repeats
+//! are next to each other: it only has to hold on to one item, so it needs
+//! no set and no `Hash` or `Clone`, only `Ord`. `unique_sorted_by` takes
+//! the comparison as a closure. Debug builds check the order as they go,
+//! and panic at the first item that is out of it.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use alloc::collections::BTreeSet;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hash};
+
+// The sets `Unique` can keep the items it has seen in. Public in a private
+// module, so that it can bound public impls without being nameable.
+mod seen {
+    use alloc::collections::BTreeSet;
+
+    pub trait SeenSet<T> {
+        /// Adds `item`, and says whether it was new.
+        fn insert(&mut self, item: T) -> bool;
+    }
+
+    impl<T: Ord> SeenSet<T> for BTreeSet<T> {
+        fn insert(&mut self, item: T) -> bool {
+            BTreeSet::insert(self, item)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<T, S> SeenSet<T> for std::collections::HashSet<T, S>
+    where
+        T: core::hash::Hash + Eq,
+        S: core::hash::BuildHasher,
+    {
+        fn insert(&mut self, item: T) -> bool {
+            std::collections::HashSet::insert(self, item)
+        }
+    }
+}
+
+use seen::SeenSet;
+
+/// `S` is the set the items seen so far go in: a `HashSet` for `unique()`,
+/// a `BTreeSet` for `unique_ord()`, and a `HashSet` with the given hasher
+/// for `unique_with_hasher`.
+pub struct Unique<I, S>
+where
+    I: Iterator,
+{
+    orig: I,
+    seen: S,
+}
+
+impl<I, S> Clone for Unique<I, S>
+where
+    I: Iterator + Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Unique {
+            orig: self.orig.clone(),
+            seen: self.seen.clone(),
+        }
+    }
+}
+
+impl<I, S> fmt::Debug for Unique<I, S>
+where
+    I: Iterator + fmt::Debug,
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unique")
+            .field("orig", &self.orig)
+            .field("seen", &self.seen)
+            .finish()
+    }
+}
+
+impl<I, S> Iterator for Unique<I, S>
+where
+    I: Iterator,
+    I::Item: Clone,
+    S: SeenSet<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.orig
+            .by_ref()
+            .find(|item| self.seen.insert(item.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.orig.size_hint().1)
+    }
+
+    fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let mut seen = self.seen;
+        self.orig.fold(init, move |acc, item| {
+            if seen.insert(item.clone()) {
+                g(acc, item)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+impl<I, S> FusedIterator for Unique<I, S>
+where
+    I: FusedIterator,
+    I::Item: Clone,
+    S: SeenSet<I::Item>,
+{
+}
+
+#[cfg(feature = "std")]
+pub struct UniqueByKey<I, F, K> {
+    orig: I,
+    key: F,
+    seen: HashSet<K>,
+}
+
+#[cfg(feature = "std")]
+impl<I, F, K> Clone for UniqueByKey<I, F, K>
+where
+    I: Clone,
+    F: Clone,
+    K: Clone,
+{
+    fn clone(&self) -> Self {
+        UniqueByKey {
+            orig: self.orig.clone(),
+            key: self.key.clone(),
+            seen: self.seen.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, F, K> fmt::Debug for UniqueByKey<I, F, K>
+where
+    I: fmt::Debug,
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniqueByKey")
+            .field("orig", &self.orig)
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, F, K> Iterator for UniqueByKey<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: Hash + Eq,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, seen) = (&mut self.key, &mut self.seen);
+        self.orig.by_ref().find(|item| seen.insert(key(item)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.orig.size_hint().1)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, F, K> FusedIterator for UniqueByKey<I, F, K>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item) -> K,
+    K: Hash + Eq,
+{
+}
+
+// The comparison `unique_sorted` uses, named so that it can be spelled out.
+type Compare<T> = fn(&T, &T) -> Ordering;
+
+pub struct UniqueSorted<I: Iterator, F> {
+    orig: I,
+    // The first item of the next group of equal items, which was read to
+    // find where the one before it ends.
+    pending: Option<I::Item>,
+    compare: F,
+}
+
+impl<I, F> Clone for UniqueSorted<I, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        UniqueSorted {
+            orig: self.orig.clone(),
+            pending: self.pending.clone(),
+            compare: self.compare.clone(),
+        }
+    }
+}
+
+impl<I, F> fmt::Debug for UniqueSorted<I, F>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniqueSorted")
+            .field("orig", &self.orig)
+            .field("pending", &self.pending)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, F> Iterator for UniqueSorted<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.pending.take().or_else(|| self.orig.next())?;
+        for item in self.orig.by_ref() {
+            match (self.compare)(&first, &item) {
+                Ordering::Equal => continue,
+                order => {
+                    debug_assert!(
+                        order == Ordering::Less,
+                        "unique_sorted: the input is not sorted"
+                    );
+                    self.pending = Some(item);
+                    break;
+                }
+            }
+        }
+        Some(first)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.orig.size_hint();
+        let pending = usize::from(self.pending.is_some());
+        let lower = lower.saturating_add(pending).min(1);
+        (lower, upper.and_then(|n| n.checked_add(pending)))
+    }
+}
+
+impl<I, F> FusedIterator for UniqueSorted<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+}
+
+pub trait UniqueExt: Iterator {
+    #[cfg(feature = "std")]
+    fn unique(self) -> Unique<Self, HashSet<Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: Clone + Hash + Eq,
+    {
+        Unique {
+            orig: self,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Like `unique`, but with the items seen so far in a `BTreeSet`, so
+    /// they need `Ord` instead of `Hash`. Works without `std`.
+    fn unique_ord(self) -> Unique<Self, BTreeSet<Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: Clone + Ord,
+    {
+        Unique {
+            orig: self,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Like `unique`, but with the items seen so far in a `HashSet` that
+    /// hashes with `hasher`.
+    #[cfg(feature = "std")]
+    fn unique_with_hasher<S>(self, hasher: S) -> Unique<Self, HashSet<Self::Item, S>>
+    where
+        Self: Sized,
+        Self::Item: Clone + Hash + Eq,
+        S: BuildHasher,
+    {
+        Unique {
+            orig: self,
+            seen: HashSet::with_hasher(hasher),
+        }
+    }
+
+    /// Yields the first item with each key, keeping only the keys, so that
+    /// no item is ever cloned.
+    #[cfg(feature = "std")]
+    fn unique_by_key_owned<K, F>(self, key: F) -> UniqueByKey<Self, F, K>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> K,
+        K: Hash + Eq,
+    {
+        UniqueByKey {
+            orig: self,
+            key,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Yields the first of each group of equal items, for input sorted in
+    /// ascending order.
+    fn unique_sorted(self) -> UniqueSorted<Self, Compare<Self::Item>>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        self.unique_sorted_by(Ord::cmp)
+    }
+
+    /// Yields the first of each group of items that `compare` finds equal,
+    /// for input sorted by `compare`.
+    fn unique_sorted_by<F>(self, compare: F) -> UniqueSorted<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        UniqueSorted {
+            orig: self,
+            pending: None,
+            compare,
+        }
+    }
+}
+
+impl<I: Iterator> UniqueExt for I {}
+
+#[cfg(feature = "std")]
+#[test]
+fn unique_keeps_first_occurrences() {
+    let v: Vec<i32> = [3, 1, 3, 2, 1, 4].into_iter().unique().collect();
+    assert_eq!(v, [3, 1, 2, 4]);
+
+    let words = "the cat saw the other cat".split(' ').unique();
+    assert_eq!(words.collect::<Vec<_>>(), ["the", "cat", "saw", "other"]);
+
+    let mut it = (0..10).map(|x| x % 3).unique();
+    assert_eq!(it.size_hint(), (0, Some(10)));
+    assert_eq!(it.next(), Some(0));
+    assert_eq!(it.sum::<i32>(), 3);
+}
+
+// Ordered, but neither `Hash` nor `Clone`, so only `unique_sorted` takes it.
+#[cfg(test)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Version(u32, u32);
+
+#[test]
+fn unique_sorted_matches_unique_on_sorted_input() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(1426);
+    for _ in 0..50 {
+        let len = rng.gen_range(0..60);
+        let mut v: Vec<u8> = (0..len).map(|_| rng.gen_range(0..20)).collect();
+        v.sort();
+        #[cfg(feature = "std")]
+        assert_iter_eq!(v.iter().unique_sorted(), v.iter().unique());
+        assert_iter_eq!(v.iter().unique_sorted(), v.iter().unique_ord());
+    }
+    assert_eq!(core::iter::empty::<u8>().unique_sorted().next(), None);
+    assert_eq!(
+        [5, 5, 5].into_iter().unique_sorted().size_hint(),
+        (1, Some(3))
+    );
+}
+
+#[test]
+fn unique_sorted_without_hash_or_clone() {
+    let versions = [
+        Version(1, 0),
+        Version(1, 0),
+        Version(1, 2),
+        Version(2, 0),
+        Version(2, 0),
+    ];
+    let distinct: Vec<Version> = versions.into_iter().unique_sorted().collect();
+    assert_eq!(distinct, [Version(1, 0), Version(1, 2), Version(2, 0)]);
+
+    // Sorted by a key: one version per major number.
+    let majors = [Version(1, 0), Version(1, 2), Version(2, 0), Version(3, 1)]
+        .into_iter()
+        .unique_sorted_by(|a, b| a.0.cmp(&b.0));
+    assert_iter_eq!(majors.map(|v| v.1), [0, 0, 1]);
+
+    // Floats, in `total_cmp` order.
+    let floats = [0.5, 0.5, 1.0, 2.5, 2.5].into_iter();
+    assert_iter_eq!(floats.unique_sorted_by(f64::total_cmp), [0.5, 1.0, 2.5]);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "unique_sorted: the input is not sorted")]
+fn unique_sorted_checks_the_order() {
+    [1, 2, 2, 1].into_iter().unique_sorted().for_each(drop);
+}
+
+// Ordered and cloneable, but not `Hash`.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Tag(&'static str);
+
+#[test]
+fn unique_ord_without_hash() {
+    let tags = ["b", "a", "b", "c", "a"].map(Tag);
+    assert_iter_eq!(tags.iter().cloned().unique_ord(), ["b", "a", "c"].map(Tag));
+    assert_eq!(tags.into_iter().unique_ord().count(), 3);
+    assert_eq!(core::iter::empty::<Tag>().unique_ord().next(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn unique_with_a_custom_hasher() {
+    use core::hash::{BuildHasherDefault, Hasher};
+
+    // FNV-1a: a hasher with no random state.
+    struct Fnv(u64);
+    impl Default for Fnv {
+        fn default() -> Self {
+            Fnv(0xcbf2_9ce4_8422_2325)
+        }
+    }
+    impl Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x100_0000_01b3);
+            }
+        }
+    }
+
+    let words = "the cat saw the other cat".split(' ');
+    let fnv = words
+        .clone()
+        .unique_with_hasher(BuildHasherDefault::<Fnv>::default());
+    assert_iter_eq!(fnv, words.clone().unique());
+
+    // A hasher that puts everything in one bucket is slow, but still right.
+    #[derive(Default)]
+    struct Constant;
+    impl Hasher for Constant {
+        fn finish(&self) -> u64 {
+            7
+        }
+        fn write(&mut self, _: &[u8]) {}
+    }
+    let constant = (0..50)
+        .map(|n| n % 7)
+        .unique_with_hasher(BuildHasherDefault::<Constant>::default());
+    assert_iter_eq!(constant, 0..7);
+}
+
+// Big, and cloning it is a bug: the adapters that take it mustn't.
+#[cfg(all(test, feature = "std"))]
+#[derive(Debug, PartialEq)]
+struct NoClone {
+    id: u32,
+    payload: Vec<u8>,
+}
+
+#[cfg(all(test, feature = "std"))]
+impl Clone for NoClone {
+    fn clone(&self) -> Self {
+        panic!("NoClone {} was cloned", self.id)
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn unique_by_key_owned_never_clones() {
+    let item = |id| NoClone {
+        id,
+        payload: vec![id as u8; 1024],
+    };
+    let items = [3, 1, 3, 2, 1].map(item);
+    let distinct: Vec<NoClone> = items.into_iter().unique_by_key_owned(|x| x.id).collect();
+    assert_eq!(distinct, [3, 1, 2].map(item));
+
+    // Items with the same key are repeats, whatever else they hold.
+    let words = ["apple", "avocado", "banana", "blueberry", "cherry"];
+    let by_initial = words.iter().unique_by_key_owned(|w| w.as_bytes()[0]);
+    assert_iter_eq!(by_initial, [&"apple", &"banana", &"cherry"]);
+}
@@ -0,0 +1,226 @@
+//! proptest strategies for finite iterators, and the laws the crate's
+//! adapters are tested against with them. Only compiled with the
+//! `proptest` feature.
+//!
+//! An `ArbitraryIter` is a list of items with either an exact `size_hint`
+//! or the vaguest one allowed, `(0, None)`, so an adapter is tried both
+//! with sources that know their length and ones that don't. The strategies
+//! choose the length, how often items repeat, and their order:
+//!
+//! ```ignore
+//! proptest! {
+//!     #[test]
+//!     fn my_adapter_keeps_every_item(it in arbitrary_iter(any::<i32>(), 0..50)) {
+//!         prop_assert_eq!(it.clone().my_adapter().count(), it.len());
+//!     }
+//! }
+//! ```
+
+use crate::adapters::{Delimiter, RleExt, SplitWhenExt, UniqueExt};
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+use std::fmt;
+use std::iter::FusedIterator;
+
+/// A finite iterator over generated items.
+#[derive(Clone)]
+pub struct ArbitraryIter<T> {
+    items: std::vec::IntoIter<T>,
+    exact_hint: bool,
+}
+
+impl<T> ArbitraryIter<T> {
+    /// `items`, reporting their exact length as the `size_hint` or not.
+    pub fn new(items: Vec<T>, exact_hint: bool) -> Self {
+        ArbitraryIter {
+            items: items.into_iter(),
+            exact_hint,
+        }
+    }
+
+    /// The items still to come.
+    pub fn as_slice(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    /// How many items are still to come, whatever `size_hint` says.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.len() == 0
+    }
+}
+
+// Shrunk failures are printed with `Debug`, so show the items rather than
+// the `IntoIter`.
+impl<T: fmt::Debug> fmt::Debug for ArbitraryIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArbitraryIter")
+            .field("items", &self.as_slice())
+            .field("exact_hint", &self.exact_hint)
+            .finish()
+    }
+}
+
+impl<T> Iterator for ArbitraryIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.items.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exact_hint {
+            self.items.size_hint()
+        } else {
+            (0, None)
+        }
+    }
+}
+
+impl<T> FusedIterator for ArbitraryIter<T> {}
+
+/// Iterators of `len` items from `element`, in any order.
+pub fn arbitrary_iter<S>(
+    element: S,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = ArbitraryIter<S::Value>>
+where
+    S: Strategy,
+{
+    (vec(element, len), any::<bool>())
+        .prop_map(|(items, exact_hint)| ArbitraryIter::new(items, exact_hint))
+}
+
+/// Iterators of `len` items, each one of only `distinct` values, so that
+/// most of them repeat.
+pub fn with_duplicates(
+    distinct: u8,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = ArbitraryIter<u8>> {
+    arbitrary_iter(0..distinct.max(1), len)
+}
+
+/// Iterators of `len` items from `element`, in ascending order.
+pub fn sorted<S>(
+    element: S,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = ArbitraryIter<S::Value>>
+where
+    S: Strategy,
+    S::Value: Ord,
+{
+    (vec(element, len), any::<bool>()).prop_map(|(mut items, exact_hint)| {
+        items.sort();
+        ArbitraryIter::new(items, exact_hint)
+    })
+}
+
+/// The same items as `it`, in every order, for laws that shouldn't depend
+/// on it.
+pub fn shuffled<T>(it: ArbitraryIter<T>) -> impl Strategy<Value = ArbitraryIter<T>>
+where
+    T: Clone + fmt::Debug,
+{
+    let exact_hint = it.exact_hint;
+    Just(it.items.collect::<Vec<_>>())
+        .prop_shuffle()
+        .prop_map(move |items| ArbitraryIter::new(items, exact_hint))
+}
+
+/// Whether `size_hint` allows for `actual` items: the bounds any iterator
+/// has to keep to.
+pub fn hint_allows((lower, upper): (usize, Option<usize>), actual: usize) -> bool {
+    lower <= actual && upper.is_none_or(|upper| actual <= upper)
+}
+
+proptest! {
+    #[test]
+    fn unique_never_yields_more(it in with_duplicates(8, 0..100)) {
+        let count = it.len();
+        let unique: Vec<u8> = it.unique().collect();
+        prop_assert!(unique.len() <= count);
+        prop_assert!(unique.len() <= 8);
+    }
+
+    #[test]
+    fn unique_keeps_the_first_of_each(it in with_duplicates(8, 0..100)) {
+        let mut expected = Vec::new();
+        for &item in it.as_slice() {
+            if !expected.contains(&item) {
+                expected.push(item);
+            }
+        }
+        prop_assert_eq!(it.unique().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn unique_of_sorted_is_dedup(it in sorted(0..20i32, 0..100)) {
+        let mut expected = it.as_slice().to_vec();
+        expected.dedup();
+        prop_assert_eq!(it.unique().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn unique_ignores_order(
+        (it, other) in with_duplicates(8, 0..50)
+            .prop_flat_map(|it| (Just(it.clone()), shuffled(it)))
+    ) {
+        let mut a: Vec<u8> = it.unique().collect();
+        let mut b: Vec<u8> = other.unique().collect();
+        a.sort_unstable();
+        b.sort_unstable();
+        prop_assert_eq!(a, b);
+    }
+
+    #[test]
+    fn chunks_flatten_to_the_original(it in arbitrary_iter(any::<i32>(), 0..100), size in 1..10usize) {
+        let items = it.as_slice().to_vec();
+        let chunks: Vec<&[i32]> = items.chunks(size).collect();
+        prop_assert!(chunks.iter().all(|chunk| !chunk.is_empty() && chunk.len() <= size));
+        prop_assert_eq!(chunks.concat(), items);
+    }
+
+    #[test]
+    fn kept_segments_flatten_to_the_original(it in with_duplicates(4, 0..100)) {
+        let items = it.as_slice().to_vec();
+        let trailing: Vec<u8> = it
+            .clone()
+            .split_when_with(|&n| n == 0, Delimiter::KeepTrailing)
+            .flatten()
+            .collect();
+        prop_assert_eq!(&trailing, &items);
+        let between: Vec<u8> = it.split_between(|a, b| a > b).flatten().collect();
+        prop_assert_eq!(&between, &items);
+    }
+
+    #[test]
+    fn rle_then_rld_round_trips(it in with_duplicates(3, 0..100)) {
+        let items = it.as_slice().to_vec();
+        let runs: Vec<(u8, usize)> = it.rle().collect();
+        prop_assert!(runs.windows(2).all(|w| w[0].0 != w[1].0));
+        prop_assert!(runs.iter().all(|&(_, n)| n > 0));
+        prop_assert_eq!(runs.into_iter().rld().collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    fn size_hints_are_kept_to(it in with_duplicates(4, 0..100)) {
+        let mut unique = it.clone().unique();
+        let mut split = it.clone().split_when(|&n| n == 0);
+        let mut between = it.split_between(|a, b| a != b);
+        // The hints have to hold at every step, not just the first.
+        loop {
+            let hints = [unique.size_hint(), split.size_hint(), between.size_hint()];
+            let counts = [unique.clone().count(), split.clone().count(), between.clone().count()];
+            for (hint, count) in hints.into_iter().zip(counts) {
+                prop_assert!(hint_allows(hint, count), "{:?} for {}", hint, count);
+            }
+            let ended = [unique.next().is_none(), split.next().is_none(), between.next().is_none()];
+            if ended.iter().all(|&ended| ended) {
+                break;
+            }
+        }
+    }
+}
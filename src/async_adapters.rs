@@ -0,0 +1,253 @@
+//! The iterator adapters from the lessons, rewritten as `futures::Stream`
+//! adapters. Only compiled with the `async` feature.
+//!
+//! The mapping from an iterator adapter to a stream adapter is mechanical:
+//!
+//! | `Iterator`                  | `Stream`                                   |
+//! |-----------------------------|--------------------------------------------|
+//! | `fn next(&mut self)`        | `fn poll_next(self: Pin<&mut Self>, cx)`   |
+//! | `Option<Item>`              | `Poll<Option<Item>>`                       |
+//! | `self.orig.next()`          | `ready!(orig.poll_next(cx))`               |
+//!
+//! `Poll::Pending` means "nothing yet, the waker in `cx` will call again";
+//! `ready!` passes it straight up, so an adapter never waits by itself. The
+//! adapters here ask for `S: Unpin`, which lets them reach the inner stream
+//! with `Pin::new` instead of pin projection.
+
+use futures::ready;
+use futures::stream::{FusedStream, Stream};
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The stream version of the `unique` adapter: each item once, the first
+/// time it turns up.
+#[derive(Debug)]
+pub struct Unique<S: Stream> {
+    orig: S,
+    seen: HashSet<S::Item>,
+}
+
+// The items are held by value and never pinned, so only `S` needs to be
+// `Unpin` for the adapter to be.
+impl<S: Stream + Unpin> Unpin for Unique<S> {}
+
+impl<S> Stream for Unique<S>
+where
+    S: Stream + Unpin,
+    S::Item: Hash + Eq + Clone,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        // The `find` of the iterator version, spelled out as a loop.
+        loop {
+            match ready!(Pin::new(&mut this.orig).poll_next(cx)) {
+                Some(item) if this.seen.insert(item.clone()) => return Poll::Ready(Some(item)),
+                Some(_) => continue,
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.orig.size_hint().1)
+    }
+}
+
+/// Groups of `size` items, and a shorter group at the end if they don't
+/// come out even.
+#[derive(Debug)]
+pub struct Chunks<S: Stream> {
+    orig: S,
+    size: usize,
+    // Items of the group being filled, kept across `Pending`s.
+    chunk: Vec<S::Item>,
+    done: bool,
+}
+
+impl<S: Stream + Unpin> Unpin for Chunks<S> {}
+
+impl<S> Stream for Chunks<S>
+where
+    S: Stream + Unpin,
+{
+    type Item = Vec<S::Item>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        while this.chunk.len() < this.size {
+            match ready!(Pin::new(&mut this.orig).poll_next(cx)) {
+                Some(item) => this.chunk.push(item),
+                None => {
+                    this.done = true;
+                    break;
+                }
+            }
+        }
+        if this.chunk.is_empty() {
+            return Poll::Ready(None);
+        }
+        let rest = Vec::with_capacity(this.size);
+        Poll::Ready(Some(mem::replace(&mut this.chunk, rest)))
+    }
+}
+
+impl<S> FusedStream for Chunks<S>
+where
+    S: Stream + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done && self.chunk.is_empty()
+    }
+}
+
+/// Like `take_while`, but also yields the first item that fails the
+/// predicate before stopping.
+#[derive(Debug)]
+pub struct TakeWhileInclusive<S, P> {
+    orig: S,
+    pred: P,
+    done: bool,
+}
+
+impl<S, P> Stream for TakeWhileInclusive<S, P>
+where
+    S: Stream + Unpin,
+    P: FnMut(&S::Item) -> bool + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let item = ready!(Pin::new(&mut this.orig).poll_next(cx));
+        match &item {
+            Some(x) => this.done = !(this.pred)(x),
+            None => this.done = true,
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            (0, self.orig.size_hint().1)
+        }
+    }
+}
+
+impl<S, P> FusedStream for TakeWhileInclusive<S, P>
+where
+    S: Stream + Unpin,
+    P: FnMut(&S::Item) -> bool + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+/// The extension trait, the same as for iterators. The names avoid the
+/// ones `futures::StreamExt` already uses, so both can be in scope.
+pub trait StreamAdaptersExt: Stream {
+    fn unique(self) -> Unique<Self>
+    where
+        Self: Sized,
+        Self::Item: Hash + Eq + Clone,
+    {
+        Unique {
+            orig: self,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    fn chunked(self, size: usize) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        assert!(size > 0, "chunk size must be positive");
+        Chunks {
+            orig: self,
+            size,
+            chunk: Vec::with_capacity(size),
+            done: false,
+        }
+    }
+
+    fn take_while_inclusive<P>(self, pred: P) -> TakeWhileInclusive<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        TakeWhileInclusive {
+            orig: self,
+            pred,
+            done: false,
+        }
+    }
+}
+
+impl<S: Stream> StreamAdaptersExt for S {}
+
+#[cfg(test)]
+use futures::stream::{self, StreamExt};
+
+#[cfg(test)]
+#[tokio::test]
+async fn unique_stream() {
+    let items: Vec<i32> = stream::iter([3, 1, 3, 2, 1, 4]).unique().collect().await;
+    assert_eq!(items, [3, 1, 2, 4]);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn chunked_stream() {
+    let chunks: Vec<Vec<i32>> = stream::iter(1..=7).chunked(3).collect().await;
+    assert_eq!(chunks, [vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+
+    let none: Vec<Vec<i32>> = stream::iter(0..0).chunked(3).collect().await;
+    assert!(none.is_empty());
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn chunked_stream_waits_for_slow_items() {
+    // Every item is `Pending` at first, so the partial chunk has to survive
+    // between polls.
+    let slow = stream::iter(1..=5).then(|x| async move {
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        x
+    });
+    let chunks: Vec<Vec<i32>> = Box::pin(slow).chunked(2).collect().await;
+    assert_eq!(chunks, [vec![1, 2], vec![3, 4], vec![5]]);
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn take_while_inclusive_stream() {
+    let mut s = stream::iter([1, 2, 5, 3, 9]).take_while_inclusive(|&x| x < 4);
+    assert_eq!(s.next().await, Some(1));
+    assert_eq!(s.next().await, Some(2));
+    assert_eq!(s.next().await, Some(5));
+    assert!(s.is_terminated());
+    assert_eq!(s.next().await, None);
+
+    // The same answers as the iterator version would give.
+    let all: Vec<i32> = stream::iter(0..3)
+        .take_while_inclusive(|_| true)
+        .collect()
+        .await;
+    assert_eq!(all, [0, 1, 2]);
+}
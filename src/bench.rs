@@ -0,0 +1,237 @@
+//! What `benches/iterators.rs` measures, kept in the crate so that the
+//! tests below can check each pair of versions gives the same answer: a
+//! benchmark that compares two functions doing different work proves
+//! nothing.
+//!
+//! Each group has a hand-written loop in the style of the lessons'
+//! "before" code, and the iterator chain that replaces it; the claim in
+//! `i2_std_iterators` is that the chain costs nothing extra.
+
+use crate::adapters::UniqueExt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// `len` numbers in `0..1000`. The same seed gives the same numbers.
+pub fn random_numbers(len: usize, seed: u64) -> Vec<i32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len).map(|_| rng.gen_range(0..1000)).collect()
+}
+
+/// `len` numbers drawn from only `distinct` different values, so most of
+/// them are repeats.
+pub fn with_duplicates(len: usize, distinct: u32, seed: u64) -> Vec<u32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len)
+        .map(|_| rng.gen_range(0..distinct.max(1)))
+        .collect()
+}
+
+/// `len` strings, about half of them numbers and the rest words.
+pub fn number_strings(len: usize, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..len)
+        .map(|_| {
+            let n: u16 = rng.gen();
+            if rng.gen_bool(0.5) {
+                n.to_string()
+            } else {
+                format!("item{n}")
+            }
+        })
+        .collect()
+}
+
+pub fn double_loop(src: &[i32]) -> Vec<i32> {
+    let mut dest = Vec::with_capacity(src.len());
+    for item in src {
+        dest.push(item * 2);
+    }
+    dest
+}
+
+pub fn double_iter(src: &[i32]) -> Vec<i32> {
+    src.iter().map(|x| x * 2).collect()
+}
+
+pub fn parse_loop(src: &[String]) -> Vec<i32> {
+    let mut dest = Vec::new();
+    for item in src {
+        if let Ok(parsed) = item.parse() {
+            dest.push(parsed);
+        }
+    }
+    dest
+}
+
+pub fn parse_iter(src: &[String]) -> Vec<i32> {
+    src.iter().filter_map(|item| item.parse().ok()).collect()
+}
+
+pub fn sum_loop(src: &[i32]) -> i64 {
+    let mut sum = 0;
+    for item in src {
+        sum += i64::from(*item);
+    }
+    sum
+}
+
+pub fn sum_fold(src: &[i32]) -> i64 {
+    src.iter().fold(0, |acc, &item| acc + i64::from(item))
+}
+
+pub fn sum_iter(src: &[i32]) -> i64 {
+    src.iter().map(|&item| i64::from(item)).sum()
+}
+
+pub fn sum_of_even_squares_loop(src: &[i32]) -> i64 {
+    let mut sum = 0;
+    for &item in src {
+        if item % 2 == 0 {
+            let square = i64::from(item) * i64::from(item);
+            sum += square;
+        }
+    }
+    sum
+}
+
+pub fn sum_of_even_squares_iter(src: &[i32]) -> i64 {
+    src.iter()
+        .filter(|&&x| x % 2 == 0)
+        .map(|&x| i64::from(x) * i64::from(x))
+        .sum()
+}
+
+/// The distinct values, in the order they first turn up, with the crate's
+/// `unique`.
+pub fn distinct_unique(src: &[u32]) -> Vec<u32> {
+    src.iter().copied().unique().collect()
+}
+
+/// The distinct values, sorted: the usual way without `unique`, when the
+/// order doesn't matter.
+pub fn distinct_sort_dedup(src: &[u32]) -> Vec<u32> {
+    let mut dest = src.to_vec();
+    dest.sort_unstable();
+    dest.dedup();
+    dest
+}
+
+/// A record told apart by its `id`, with a kilobyte of payload to make
+/// it expensive to clone and to hash.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    pub id: u32,
+    pub payload: Vec<u8>,
+}
+
+/// `len` records with ids from `0..distinct`; records with the same id are
+/// equal.
+pub fn records(len: usize, distinct: u32, seed: u64) -> Vec<Record> {
+    with_duplicates(len, distinct, seed)
+        .into_iter()
+        .map(|id| Record {
+            id,
+            payload: vec![id as u8; 1024],
+        })
+        .collect()
+}
+
+/// The distinct records with `unique`, which clones each new record into
+/// its set and hashes whole records.
+pub fn distinct_records_unique(src: Vec<Record>) -> Vec<Record> {
+    src.into_iter().unique().collect()
+}
+
+/// The distinct records with `unique_by_key_owned`, which only keeps and
+/// hashes the ids.
+pub fn distinct_records_by_key(src: Vec<Record>) -> Vec<Record> {
+    src.into_iter().unique_by_key_owned(|r| r.id).collect()
+}
+
+/// The sum of each run of `size` numbers (the last one may be shorter),
+/// three ways.
+pub fn chunk_sums_loop(src: &[i32], size: usize) -> Vec<i32> {
+    let mut sums = Vec::new();
+    let mut start = 0;
+    while start < src.len() {
+        let end = (start + size).min(src.len());
+        let mut sum = 0;
+        for item in &src[start..end] {
+            sum += item;
+        }
+        sums.push(sum);
+        start = end;
+    }
+    sums
+}
+
+pub fn chunk_sums_slice(src: &[i32], size: usize) -> Vec<i32> {
+    src.chunks(size).map(|chunk| chunk.iter().sum()).collect()
+}
+
+/// Without slices to lean on, as for an iterator that isn't backed by
+/// one: every `size`th item starts a new sum.
+pub fn chunk_sums_fold(src: &[i32], size: usize) -> Vec<i32> {
+    src.iter()
+        .enumerate()
+        .fold(Vec::new(), |mut sums, (i, &x)| {
+            if i % size == 0 {
+                sums.push(x);
+            } else {
+                *sums.last_mut().unwrap() += x;
+            }
+            sums
+        })
+}
+
+#[test]
+fn bench_data_is_reproducible() {
+    assert_eq!(random_numbers(100, 1), random_numbers(100, 1));
+    assert_ne!(random_numbers(100, 1), random_numbers(100, 2));
+    assert!(random_numbers(1000, 3)
+        .iter()
+        .all(|n| (0..1000).contains(n)));
+    assert!(with_duplicates(1000, 10, 4).iter().all(|&n| n < 10));
+
+    let strings = number_strings(1000, 5);
+    let numbers = strings.iter().filter(|s| s.parse::<i32>().is_ok()).count();
+    assert!((400..600).contains(&numbers), "{numbers}");
+}
+
+#[test]
+fn loops_and_chains_agree() {
+    let numbers = random_numbers(1000, 1394);
+    assert_eq!(double_loop(&numbers), double_iter(&numbers));
+    assert_eq!(sum_loop(&numbers), sum_fold(&numbers));
+    assert_eq!(sum_loop(&numbers), sum_iter(&numbers));
+    assert_eq!(
+        sum_of_even_squares_loop(&numbers),
+        sum_of_even_squares_iter(&numbers)
+    );
+
+    let strings = number_strings(1000, 1394);
+    assert_eq!(parse_loop(&strings), parse_iter(&strings));
+}
+
+#[test]
+fn distinct_and_chunking_strategies_agree() {
+    let repeats = with_duplicates(5000, 50, 1394);
+    let mut unique = distinct_unique(&repeats);
+    unique.sort_unstable();
+    assert_eq!(unique, distinct_sort_dedup(&repeats));
+    assert_eq!(unique.len(), 50);
+
+    let numbers = random_numbers(1001, 1394);
+    for size in [1, 7, 64, 2000] {
+        let sums = chunk_sums_loop(&numbers, size);
+        assert_eq!(sums.len(), numbers.len().div_ceil(size));
+        assert_eq!(chunk_sums_slice(&numbers, size), sums);
+        assert_eq!(chunk_sums_fold(&numbers, size), sums);
+    }
+    assert!(chunk_sums_fold(&[], 3).is_empty());
+
+    let records = records(2000, 40, 1394);
+    let by_key = distinct_records_by_key(records.clone());
+    assert_eq!(by_key.len(), 40);
+    assert_eq!(distinct_records_unique(records), by_key);
+}
@@ -0,0 +1,187 @@
+//! `iterdemo`: some of the crate's pipelines, from the command line.
+//!
+//! ```text
+//! iterdemo passwords [--count N] [--length N] [--seed N] [--symbols]
+//! iterdemo primes --below N
+//! iterdemo stats < numbers.txt
+//! iterdemo worm --steps N [--seed N]
+//! ```
+//!
+//! Each subcommand is a function from its arguments, its input and its
+//! output to a `Result`, so that `tests/iterdemo.rs` can run them without
+//! starting a process.
+
+use iterators::consumers::ExtremesExt;
+use iterators::i3_mini_project::simulation;
+use iterators::sources::PasswordGenerator;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+const USAGE: &str = "usage:
+    iterdemo passwords [--count N] [--length N] [--seed N] [--symbols]
+    iterdemo primes --below N
+    iterdemo stats < numbers.txt
+    iterdemo worm --steps N [--seed N]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args, io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("iterdemo: {message}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the subcommand named by the first argument.
+pub fn run(args: &[String], input: impl Read, out: &mut impl Write) -> Result<(), String> {
+    let Some((command, args)) = args.split_first() else {
+        return Err("no subcommand".into());
+    };
+    match command.as_str() {
+        "passwords" => passwords(
+            &Options::parse(args, &["count", "length", "seed"], &["symbols"])?,
+            out,
+        ),
+        "primes" => primes(&Options::parse(args, &["below"], &[])?, out),
+        "stats" => stats(&Options::parse(args, &[], &[])?, input, out),
+        "worm" => worm(&Options::parse(args, &["steps", "seed"], &[])?, out),
+        "help" | "--help" | "-h" => writeln!(out, "{USAGE}").map_err(|e| e.to_string()),
+        other => Err(format!("unknown subcommand `{other}`")),
+    }
+}
+
+/// `--name value` pairs and `--name` flags, in any order.
+#[derive(Debug, Default)]
+pub struct Options<'a> {
+    values: Vec<(&'a str, &'a str)>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> Options<'a> {
+    /// Reads `args`, where the names in `values` take a value and those in
+    /// `flags` don't. Anything else is an error.
+    pub fn parse(args: &'a [String], values: &[&str], flags: &[&str]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument `{arg}`"))?;
+            if flags.contains(&name) {
+                options.flags.push(name);
+            } else if values.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("--{name} needs a value"))?;
+                options.values.push((name, value));
+            } else {
+                return Err(format!("unknown option `{arg}`"));
+            }
+        }
+        Ok(options)
+    }
+
+    /// The value of `--name`, the last one if it was given more than once.
+    pub fn value<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.values
+            .iter()
+            .rev()
+            .find(|&&(n, _)| n == name)
+            .map(|&(_, value)| {
+                value
+                    .parse()
+                    .map_err(|_| format!("--{name}: `{value}` is not a valid value"))
+            })
+            .transpose()
+    }
+
+    pub fn required<T: FromStr>(&self, name: &str) -> Result<T, String> {
+        self.value(name)?
+            .ok_or_else(|| format!("--{name} is required"))
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+}
+
+/// `--count` passwords from the generator's builder, one per line.
+pub fn passwords(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let mut builder = PasswordGenerator::builder()
+        .length(options.value("length")?.unwrap_or(16))
+        .symbols(options.flag("symbols"));
+    if let Some(seed) = options.value("seed")? {
+        builder = builder.seed(seed);
+    }
+    let generator = builder.build().map_err(|e| e.to_string())?;
+    generator
+        .take(options.value("count")?.unwrap_or(5))
+        .try_for_each(|password| writeln!(out, "{password}"))
+        .map_err(|e| e.to_string())
+}
+
+/// The primes below `--below`, one per line, by trial division.
+pub fn primes(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let below: u64 = options.required("below")?;
+    let is_prime = |n: &u64| {
+        (2..)
+            .take_while(|d| d * d <= *n)
+            .all(|d| !n.is_multiple_of(d))
+    };
+    (2..below)
+        .filter(is_prime)
+        .try_for_each(|p| writeln!(out, "{p}"))
+        .map_err(|e| e.to_string())
+}
+
+/// The count, sum, mean, median, minimum and maximum of the numbers in
+/// `input`, which are separated by whitespace.
+pub fn stats(_options: &Options, mut input: impl Read, out: &mut impl Write) -> Result<(), String> {
+    let mut text = String::new();
+    input.read_to_string(&mut text).map_err(|e| e.to_string())?;
+    let mut numbers: Vec<f64> = text
+        .split_whitespace()
+        .map(|word| {
+            word.parse()
+                .map_err(|_| format!("`{word}` is not a number"))
+        })
+        .collect::<Result<_, _>>()?;
+    if numbers.is_empty() {
+        return Err("no numbers on standard input".into());
+    }
+
+    let count = numbers.len();
+    let sum: f64 = numbers.iter().sum();
+    let min = *numbers.iter().min_set_by(|a, b| a.total_cmp(b))[0];
+    let max = *numbers.iter().max_set_by(|a, b| a.total_cmp(b))[0];
+    numbers.sort_by(f64::total_cmp);
+    let median = match count % 2 {
+        1 => numbers[count / 2],
+        _ => (numbers[count / 2 - 1] + numbers[count / 2]) / 2.0,
+    };
+    [
+        ("count", count as f64),
+        ("sum", sum),
+        ("mean", sum / count as f64),
+        ("median", median),
+        ("min", min),
+        ("max", max),
+    ]
+    .iter()
+    .try_for_each(|(name, value)| writeln!(out, "{name:<6} {value}"))
+    .map_err(|e| e.to_string())
+}
+
+/// `--steps` turns of the mini project's game, each one drawn as a frame.
+pub fn worm(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let steps: usize = options.required("steps")?;
+    let seed = options.value("seed")?.unwrap_or(1);
+    simulation(seed)
+        .take(steps)
+        .enumerate()
+        .try_for_each(|(i, frame)| writeln!(out, "turn {}\n{frame}\n", i + 1))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,23 @@
+//! Collections that plug into the iterator ecosystem: each one can be built
+//! with `collect()` (`FromIterator`), grown with `extend()` (`Extend`), and
+//! walked with a `for` loop (`IntoIterator`).
+
+pub mod bit_set;
+pub mod bst;
+pub mod list;
+#[cfg(feature = "std")]
+pub mod multi_map;
+pub mod my_vec;
+pub mod ring_buffer;
+pub mod sorted_vec;
+pub mod trie;
+
+pub use bit_set::BitSet;
+pub use bst::Bst;
+pub use list::List;
+#[cfg(feature = "std")]
+pub use multi_map::MultiMap;
+pub use my_vec::MyVec;
+pub use ring_buffer::RingBuffer;
+pub use sorted_vec::SortedVec;
+pub use trie::Trie;
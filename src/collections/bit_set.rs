@@ -0,0 +1,196 @@
+//! A set of small non-negative integers stored one bit each. Walking the set
+//! doesn't test every bit: `ones()` jumps straight to the next set bit with
+//! `trailing_zeros`, and skips empty words entirely.
+
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Default)]
+pub struct BitSet {
+    // Bit `i % 64` of `words[i / 64]` is set when `i` is in the set.
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `index`, returning `false` if it was already present. The
+    /// set grows to make room for it.
+    pub fn insert(&mut self, index: usize) -> bool {
+        let (word, mask) = (index / WORD_BITS, 1 << (index % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        if self.words[word] & mask != 0 {
+            return false;
+        }
+        self.words[word] |= mask;
+        self.len += 1;
+        true
+    }
+
+    /// Removes `index`, returning `false` if it wasn't present.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let (word, mask) = (index / WORD_BITS, 1 << (index % WORD_BITS));
+        match self.words.get_mut(word) {
+            Some(bits) if *bits & mask != 0 => {
+                *bits &= !mask;
+                self.len -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / WORD_BITS)
+            .is_some_and(|bits| bits & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    /// The members of the set, in increasing order.
+    pub fn ones(&self) -> Ones<'_> {
+        let mut words = self.words.iter();
+        Ones {
+            current: words.next().copied().unwrap_or(0),
+            base: 0,
+            words,
+            remaining: self.len,
+        }
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.insert(index);
+        }
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ones()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ones<'a> {
+    // The bits of the current word that haven't been yielded yet.
+    current: u64,
+    // The index of bit 0 of the current word.
+    base: usize,
+    words: core::slice::Iter<'a, u64>,
+    remaining: usize,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base += WORD_BITS;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.current &= self.current - 1;
+        self.remaining -= 1;
+        Some(self.base + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Ones<'_> {}
+
+impl FusedIterator for Ones<'_> {}
+
+#[test]
+fn bit_set_insert_contains_remove() {
+    let mut set = BitSet::new();
+    assert!(set.insert(3));
+    assert!(set.insert(200));
+    assert!(!set.insert(3));
+    assert_eq!(set.len(), 2);
+
+    assert!(set.contains(3));
+    assert!(set.contains(200));
+    assert!(!set.contains(4));
+    assert!(!set.contains(10_000));
+
+    assert!(set.remove(3));
+    assert!(!set.remove(3));
+    assert!(!set.remove(10_000));
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn bit_set_ones_across_word_boundaries() {
+    let set: BitSet = [64, 0, 63, 300, 127, 128, 1].into_iter().collect();
+    let mut ones = set.ones();
+    assert_eq!(ones.len(), 7);
+    assert_eq!(ones.next(), Some(0));
+    assert_eq!(ones.len(), 6);
+    assert_eq!(ones.collect::<Vec<_>>(), [1, 63, 64, 127, 128, 300]);
+
+    assert_eq!(BitSet::new().ones().next(), None);
+
+    // Words 1 to 15 are empty and skipped as a whole.
+    let sparse: BitSet = [5, 1_000].into_iter().collect();
+    assert_eq!((&sparse).into_iter().collect::<Vec<_>>(), [5, 1_000]);
+}
+
+#[test]
+fn bit_set_matches_hash_set() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    let mut rng = StdRng::seed_from_u64(1349);
+    for _ in 0..20 {
+        let max = rng.gen_range(1..2_000);
+        let values: Vec<usize> = (0..rng.gen_range(0..500))
+            .map(|_| rng.gen_range(0..max))
+            .collect();
+
+        let mut set: BitSet = values.iter().copied().collect();
+        let mut oracle: HashSet<usize> = values.iter().copied().collect();
+        for _ in 0..50 {
+            let n = rng.gen_range(0..max);
+            assert_eq!(set.remove(n), oracle.remove(&n));
+        }
+
+        let mut expected: Vec<usize> = oracle.iter().copied().collect();
+        expected.sort();
+        assert_eq!(set.len(), oracle.len());
+        assert_eq!(set.ones().collect::<Vec<_>>(), expected);
+        assert!((0..max).all(|n| set.contains(n) == oracle.contains(&n)));
+    }
+}
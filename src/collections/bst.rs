@@ -0,0 +1,303 @@
+//! A binary search tree with three traversal orders. Recursion makes tree
+//! traversals easy to write but impossible to pause, so each iterator keeps
+//! an explicit stack of the nodes it still has to visit.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+pub struct Bst<T> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+}
+
+struct Node<T> {
+    value: T,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+impl<T: Ord> Bst<T> {
+    pub fn new() -> Self {
+        Bst { root: None, len: 0 }
+    }
+
+    /// Inserts `value`, returning `false` if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        let mut link = &mut self.root;
+        while let Some(node) = link {
+            link = match value.cmp(&node.value) {
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+                Ordering::Equal => return false,
+            };
+        }
+        *link = Some(Box::new(Node {
+            value,
+            left: None,
+            right: None,
+        }));
+        self.len += 1;
+        true
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let mut link = &self.root;
+        while let Some(node) = link {
+            link = match value.cmp(&node.value) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+}
+
+impl<T> Bst<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Left subtree, node, right subtree: the values in sorted order.
+    pub fn in_order(&self) -> InOrder<'_, T> {
+        let mut iter = InOrder {
+            stack: Vec::new(),
+            remaining: self.len,
+        };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+
+    /// Node, left subtree, right subtree.
+    pub fn pre_order(&self) -> PreOrder<'_, T> {
+        PreOrder {
+            stack: self.root.as_deref().into_iter().collect(),
+            remaining: self.len,
+        }
+    }
+
+    /// Left subtree, right subtree, node.
+    pub fn post_order(&self) -> PostOrder<'_, T> {
+        PostOrder {
+            stack: self
+                .root
+                .as_deref()
+                .map(|n| (n, false))
+                .into_iter()
+                .collect(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<T: Ord> Default for Bst<T> {
+    fn default() -> Self {
+        Bst::new()
+    }
+}
+
+// A tree built from sorted input degenerates into a list, and the
+// compiler-generated drop would recurse once per level.
+impl<T> Drop for Bst<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for Bst<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Bst<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Bst::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Bst<T> {
+    type Item = &'a T;
+    type IntoIter = InOrder<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.in_order()
+    }
+}
+
+pub struct InOrder<'a, T> {
+    // Nodes whose left subtree is being (or has been) visited, but which
+    // haven't been yielded themselves yet.
+    stack: Vec<&'a Node<T>>,
+    remaining: usize,
+}
+
+impl<'a, T> InOrder<'a, T> {
+    fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+pub struct PreOrder<'a, T> {
+    // Roots of the subtrees still to visit; the next one is on top.
+    stack: Vec<&'a Node<T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        // Right goes in first so that left comes out first.
+        self.stack.extend(node.right.as_deref());
+        self.stack.extend(node.left.as_deref());
+        self.remaining -= 1;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+pub struct PostOrder<'a, T> {
+    // Each node is pushed twice: first unexpanded, then, once its children
+    // have been pushed on top of it, as ready to yield.
+    stack: Vec<(&'a Node<T>, bool)>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                self.remaining -= 1;
+                return Some(&node.value);
+            }
+            self.stack.push((node, true));
+            self.stack.extend(node.right.as_deref().map(|n| (n, false)));
+            self.stack.extend(node.left.as_deref().map(|n| (n, false)));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for InOrder<'_, T> {}
+impl<T> ExactSizeIterator for PreOrder<'_, T> {}
+impl<T> ExactSizeIterator for PostOrder<'_, T> {}
+impl<T> FusedIterator for InOrder<'_, T> {}
+impl<T> FusedIterator for PreOrder<'_, T> {}
+impl<T> FusedIterator for PostOrder<'_, T> {}
+
+#[cfg(test)]
+fn sample() -> Bst<i32> {
+    //         8
+    //       /   \
+    //      3     10
+    //     / \      \
+    //    1   6      14
+    //       / \    /
+    //      4   7  13
+    [8, 3, 10, 1, 6, 14, 4, 7, 13].into_iter().collect()
+}
+
+#[test]
+fn bst_in_order_is_sorted() {
+    let tree = sample();
+    assert_eq!(
+        tree.in_order().copied().collect::<Vec<_>>(),
+        [1, 3, 4, 6, 7, 8, 10, 13, 14]
+    );
+
+    let words: Bst<&str> = "the quick brown fox jumps over the lazy dog"
+        .split(' ')
+        .collect();
+    let mut sorted: Vec<&str> = words.in_order().copied().collect();
+    assert_eq!(
+        sorted,
+        ["brown", "dog", "fox", "jumps", "lazy", "over", "quick", "the"]
+    );
+    sorted.dedup();
+    assert_eq!(sorted.len(), words.len());
+}
+
+#[test]
+fn bst_pre_order() {
+    assert_eq!(
+        sample().pre_order().copied().collect::<Vec<_>>(),
+        [8, 3, 1, 6, 4, 7, 10, 14, 13]
+    );
+}
+
+#[test]
+fn bst_post_order() {
+    assert_eq!(
+        sample().post_order().copied().collect::<Vec<_>>(),
+        [1, 4, 7, 6, 3, 13, 14, 10, 8]
+    );
+}
+
+#[test]
+fn bst_insert_and_contains() {
+    let mut tree = sample();
+    assert!(!tree.insert(6));
+    assert!(tree.insert(5));
+    assert_eq!(tree.len(), 10);
+    assert!(tree.contains(&5));
+    assert!(!tree.contains(&9));
+    assert_eq!(tree.in_order().len(), 10);
+}
+
+#[test]
+fn bst_empty_and_degenerate_trees() {
+    let empty: Bst<i32> = Bst::new();
+    assert_eq!(empty.in_order().next(), None);
+    assert_eq!(empty.pre_order().next(), None);
+    assert_eq!(empty.post_order().next(), None);
+
+    // Sorted input builds a right-leaning chain, the worst case for stack
+    // depth. (It's also the worst case for `insert`, so keep it modest.)
+    let chain: Bst<u32> = (0..5_000).collect();
+    assert_iter_eq!(chain.in_order().copied(), 0..5_000);
+    assert_iter_eq!(chain.pre_order().copied(), 0..5_000);
+    assert_iter_eq!(chain.post_order().copied(), (0..5_000).rev());
+}
@@ -0,0 +1,296 @@
+//! The cons list from the `from_iter_exercise`, completed: it can be
+//! collected into, extended, and iterated by reference or by value.
+
+use alloc::boxed::Box;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+
+pub struct List<T> {
+    head: Link<T>,
+    len: usize,
+}
+
+enum Link<T> {
+    Cons(T, Box<Link<T>>),
+    Nil,
+}
+
+use Link::{Cons, Nil};
+
+impl<T> List<T> {
+    pub fn new() -> Self {
+        List { head: Nil, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let rest = mem::replace(&mut self.head, Nil);
+        self.head = Cons(value, Box::new(rest));
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        match mem::replace(&mut self.head, Nil) {
+            Cons(value, rest) => {
+                self.head = *rest;
+                self.len -= 1;
+                Some(value)
+            }
+            Nil => None,
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            link: &self.head,
+            len: self.len,
+        }
+    }
+
+    // The `Nil` at the end of the list, where `extend` appends, and the
+    // length, to count the appended nodes in.
+    fn tail_mut(&mut self) -> (&mut Link<T>, &mut usize) {
+        let mut link = &mut self.head;
+        loop {
+            match link {
+                Cons(_, rest) => link = rest,
+                nil => return (nil, &mut self.len),
+            }
+        }
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+// The compiler-generated drop would recurse once per node and overflow
+// the stack on long lists, so unlink the nodes one at a time instead.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other)
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+/// Iterates over `&T`, front to back.
+pub struct Iter<'a, T> {
+    link: &'a Link<T>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.link {
+            Cons(value, rest) => {
+                self.link = rest;
+                self.len -= 1;
+                Some(value)
+            }
+            Nil => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// Iterates over `T`, front to back, freeing each node as it goes.
+pub struct IntoIter<T> {
+    list: List<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Appends to the back, so `extend` keeps the order of `iter`.
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let (mut tail, len) = self.tail_mut();
+        for value in iter {
+            *tail = Cons(value, Box::new(Nil));
+            // Counted as each node is linked in, so that the length is
+            // still right if `iter` panics partway.
+            *len += 1;
+            match tail {
+                Cons(_, rest) => tail = rest,
+                Nil => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Collecting keeps the order of the iterator: the first item becomes the
+/// head of the list.
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+/// A list is written as a sequence, head first.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for List<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for List<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        alloc::vec::Vec::<T>::deserialize(deserializer).map(|items| items.into_iter().collect())
+    }
+}
+
+#[test]
+fn list_round_trip() {
+    let src = vec![1, 2, 3];
+
+    let list: List<i32> = src.clone().into_iter().collect();
+    let back: Vec<i32> = list.into_iter().collect();
+
+    assert_eq!(back, src);
+}
+
+#[test]
+fn list_iter_borrows() {
+    let list: List<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+
+    let lens: Vec<usize> = list.iter().map(|s| s.len()).collect();
+    assert_eq!(lens, [1, 1]);
+
+    let mut joined = String::new();
+    for s in &list {
+        joined += s;
+    }
+    assert_eq!(joined, "ab");
+    assert_eq!(list.len(), 2);
+}
+
+#[test]
+fn list_extend_appends() {
+    let mut list: List<i32> = (1..=2).collect();
+    list.extend(3..=4);
+    list.extend(std::iter::empty());
+    list.push_front(0);
+
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+    assert_eq!(list.len(), 5);
+    assert_eq!(format!("{list:?}"), "[0, 1, 2, 3, 4]");
+
+    let mut empty = List::new();
+    empty.extend(["x"]);
+    assert_eq!(empty, ["x"].into_iter().collect());
+}
+
+#[test]
+fn list_into_iter_is_exact_size() {
+    let mut it = (0..5).collect::<List<_>>().into_iter();
+    assert_eq!(it.len(), 5);
+    it.next();
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.collect::<Vec<_>>(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn list_extend_counts_what_it_linked_before_a_panic() {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let mut list: List<u32> = (0..2).collect();
+    let source = (2..).inspect(|&n| assert!(n < 5, "the source gave out"));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| list.extend(source)));
+    assert!(result.is_err());
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.iter().count(), 5);
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn list_long_lists_drop_without_overflowing() {
+    let list: List<u32> = (0..1_000_000).collect();
+    assert_eq!(list.len(), 1_000_000);
+    drop(list);
+
+    // A half-consumed `IntoIter` drops the rest the same way.
+    let mut it = (0..1_000_000).collect::<List<u32>>().into_iter();
+    it.next();
+    drop(it);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn list_serde_round_trip() {
+    let list: List<(u8, char)> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    let json = crate::json::to_string(&list).unwrap();
+    assert_eq!(json, r#"[[1,"a"],[2,"b"]]"#);
+    assert_eq!(crate::json::from_str::<List<(u8, char)>>(&json), Ok(list));
+    assert_eq!(crate::json::from_str::<List<u8>>("[]"), Ok(List::new()));
+}
@@ -0,0 +1,215 @@
+//! A map from each key to all the values collected for it. Building one
+//! from `(key, value)` pairs is a grouping operation: `collect()` sorts the
+//! pairs into buckets, keeping each key's values in the order they came.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::{self, HashMap};
+use std::hash::Hash;
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct MultiMap<K, V> {
+    map: HashMap<K, Vec<V>>,
+    // The number of values, across all keys.
+    len: usize,
+}
+
+impl<K: Hash + Eq, V> MultiMap<K, V> {
+    pub fn new() -> Self {
+        MultiMap {
+            map: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Appends `value` to the values of `key`.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// The values of `key`, in insertion order. A missing key has none.
+    pub fn get<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Removes `key`, returning all of its values.
+    pub fn remove<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let values = self.map.remove(key).unwrap_or_default();
+        self.len -= values.len();
+        values
+    }
+}
+
+impl<K, V> MultiMap<K, V> {
+    /// The number of values, counting every value of every key.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn keys_len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Each key with all of its values, in arbitrary key order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, &[V])> {
+        self.map.iter().map(|(k, vs)| (k, vs.as_slice()))
+    }
+
+    /// Every value paired with its key. Keys come in arbitrary order, but
+    /// the values of one key are adjacent and in insertion order.
+    pub fn iter_flat(&self) -> IterFlat<'_, K, V> {
+        IterFlat {
+            keys: self.map.iter(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        MultiMap::new()
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for MultiMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = MultiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a MultiMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = IterFlat<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_flat()
+    }
+}
+
+pub struct IterFlat<'a, K, V> {
+    keys: hash_map::Iter<'a, K, Vec<V>>,
+    // The key being walked, and its values still to yield.
+    current: Option<(&'a K, std::slice::Iter<'a, V>)>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for IterFlat<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, values)) = &mut self.current {
+                if let Some(value) = values.next() {
+                    self.remaining -= 1;
+                    return Some((key, value));
+                }
+            }
+            let (key, values) = self.keys.next()?;
+            self.current = Some((key, values.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterFlat<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterFlat<'_, K, V> {}
+
+impl<K, V> Clone for IterFlat<'_, K, V> {
+    fn clone(&self) -> Self {
+        IterFlat {
+            keys: self.keys.clone(),
+            current: self.current.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+#[test]
+fn multi_map_groups_in_insertion_order() {
+    let words = ["apple", "bob", "avocado", "cherry", "banana", "apricot"];
+    let by_initial: MultiMap<char, &str> = words
+        .into_iter()
+        .map(|w| (w.chars().next().unwrap(), w))
+        .collect();
+
+    assert_eq!(by_initial.len(), 6);
+    assert_eq!(by_initial.keys_len(), 3);
+    assert_eq!(by_initial.get(&'a'), ["apple", "avocado", "apricot"]);
+    assert_eq!(by_initial.get(&'b'), ["bob", "banana"]);
+    assert_eq!(by_initial.get(&'c'), ["cherry"]);
+    assert!(by_initial.get(&'z').is_empty());
+}
+
+#[test]
+fn multi_map_extend_appends_to_existing_keys() {
+    let mut map: MultiMap<String, i32> = [("x".to_string(), 1), ("y".to_string(), 2)]
+        .into_iter()
+        .collect();
+    map.extend([
+        ("x".to_string(), 3),
+        ("z".to_string(), 4),
+        ("x".to_string(), 5),
+    ]);
+
+    assert_eq!(map.get("x"), [1, 3, 5]);
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.remove("x"), [1, 3, 5]);
+    assert_eq!(map.remove("x"), []);
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn multi_map_iter_flat() {
+    let map: MultiMap<u8, char> = [(1, 'a'), (2, 'b'), (1, 'c'), (3, 'd'), (1, 'e')]
+        .into_iter()
+        .collect();
+
+    let flat = map.iter_flat();
+    assert_eq!(flat.len(), 5);
+    let pairs: Vec<(u8, char)> = flat.map(|(&k, &v)| (k, v)).collect();
+    assert_eq!(pairs.len(), 5);
+
+    // Whatever order the keys come in, each key's values are adjacent and
+    // in insertion order.
+    for (key, values) in map.iter() {
+        let start = pairs.iter().position(|&(k, _)| k == *key).unwrap();
+        let run: Vec<char> = pairs[start..start + values.len()]
+            .iter()
+            .map(|&(k, v)| {
+                assert_eq!(k, *key);
+                v
+            })
+            .collect();
+        assert_eq!(run, values);
+    }
+
+    assert_eq!((&MultiMap::<u8, char>::new()).into_iter().next(), None);
+}
@@ -0,0 +1,149 @@
+//! `MyVec<T>` is the `MyCollection` from `from_iter_demo`, grown into the
+//! canonical example of wiring a collection into the iterator ecosystem.
+//! It's just a wrapper over `Vec<T>`, so every iterator it hands out is the
+//! corresponding `Vec`/slice iterator; what matters is which trait provides
+//! which entry point:
+//!
+//! | You write                     | Trait / method                      |
+//! |-------------------------------|-------------------------------------|
+//! | `iter.collect::<MyVec<_>>()`  | `FromIterator<T> for MyVec<T>`      |
+//! | `v.extend(iter)`              | `Extend<T> for MyVec<T>`            |
+//! | `for x in v`                  | `IntoIterator for MyVec<T>`         |
+//! | `for x in &v` / `v.iter()`    | `IntoIterator for &MyVec<T>`        |
+//! | `for x in &mut v`/`iter_mut()`| `IntoIterator for &mut MyVec<T>`    |
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::slice;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MyVec<T>(Vec<T>);
+
+impl<T> MyVec<T> {
+    pub fn new() -> MyVec<T> {
+        MyVec(Vec::new())
+    }
+
+    pub fn add(&mut self, elem: T) {
+        self.0.push(elem);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.0.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for MyVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut my_vec = MyVec::new();
+        my_vec.extend(iter);
+        my_vec
+    }
+}
+
+impl<T> Extend<T> for MyVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.add(elem);
+        }
+    }
+}
+
+impl<T> IntoIterator for MyVec<T> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MyVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut MyVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[test]
+fn my_vec_from_iterator() {
+    let v = MyVec::from_iter(0..3);
+    assert_eq!(v.as_slice(), [0, 1, 2]);
+
+    let v: MyVec<char> = "abc".chars().collect();
+    assert_eq!(v.as_slice(), ['a', 'b', 'c']);
+}
+
+#[test]
+fn my_vec_extend() {
+    let mut v: MyVec<i32> = MyVec::new();
+    v.extend([1, 2]);
+    v.extend(vec![3]);
+
+    assert_eq!(v.as_slice(), [1, 2, 3]);
+}
+
+#[test]
+fn my_vec_into_iter_by_value() {
+    let v: MyVec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+
+    let mut owned = Vec::new();
+    for s in v {
+        owned.push(s);
+    }
+
+    assert_eq!(owned, ["a", "b"]);
+}
+
+#[test]
+fn my_vec_into_iter_by_ref() {
+    let v: MyVec<i32> = (1..=3).collect();
+
+    let mut sum = 0;
+    for x in &v {
+        sum += x;
+    }
+
+    assert_eq!(sum, 6);
+    assert_eq!(v.iter().rev().collect::<Vec<_>>(), [&3, &2, &1]);
+    assert_eq!(v.len(), 3);
+}
+
+#[test]
+fn my_vec_into_iter_by_mut_ref() {
+    let mut v: MyVec<i32> = (1..=3).collect();
+
+    for x in &mut v {
+        *x *= 10;
+    }
+    v.iter_mut().for_each(|x| *x += 1);
+
+    assert_eq!(v.as_slice(), [11, 21, 31]);
+}
@@ -0,0 +1,201 @@
+//! A fixed-capacity ring buffer. Once full, every push overwrites the
+//! oldest entry, so the buffer always holds the most recent `N` items.
+//! Iteration runs from oldest to newest, wrapping around the end of the
+//! underlying storage.
+
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T, const N: usize> {
+    // Grows to `N` items, then stays that size.
+    buf: Vec<T>,
+    // Index of the oldest item once the buffer has wrapped.
+    start: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub fn new() -> Self {
+        RingBuffer {
+            buf: Vec::with_capacity(N),
+            start: 0,
+        }
+    }
+
+    /// Appends `value`, returning the oldest item if it had to make room.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            return Some(value);
+        }
+        if self.buf.len() < N {
+            self.buf.push(value);
+            return None;
+        }
+        let evicted = core::mem::replace(&mut self.buf[self.start], value);
+        self.start = (self.start + 1) % N;
+        Some(evicted)
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.buf.len() == N
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Iterates from the oldest item to the newest.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            buf: &self.buf,
+            start: self.start,
+            front: 0,
+            back: self.buf.len(),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for RingBuffer<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Keeps the last `N` items of the iterator.
+impl<T, const N: usize> FromIterator<T> for RingBuffer<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut ring = RingBuffer::new();
+        ring.extend(iter);
+        ring
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    buf: &'a [T],
+    start: usize,
+    // Logical positions `front..back` are left, counted from the oldest item.
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn at(&self, position: usize) -> &'a T {
+        &self.buf[(self.start + position) % self.buf.len()]
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.at(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+#[test]
+fn ring_buffer_before_wrapping() {
+    let mut ring: RingBuffer<i32, 4> = RingBuffer::new();
+    assert_eq!(ring.push(1), None);
+    assert_eq!(ring.push(2), None);
+
+    assert_eq!(ring.iter().collect::<Vec<_>>(), [&1, &2]);
+    assert!(!ring.is_full());
+}
+
+#[test]
+fn ring_buffer_wraps_around() {
+    let mut ring: RingBuffer<i32, 3> = RingBuffer::new();
+    ring.extend(1..=3);
+    assert_eq!(ring.push(4), Some(1));
+    assert_eq!(ring.push(5), Some(2));
+
+    // Storage is now [4, 5, 3]; iteration starts at the oldest item.
+    assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+    assert_eq!(ring.iter().rev().copied().collect::<Vec<_>>(), [5, 4, 3]);
+    assert_eq!(ring.len(), 3);
+}
+
+#[test]
+fn ring_buffer_iterates_from_both_ends_across_the_wrap() {
+    let ring: RingBuffer<i32, 4> = (1..=6).collect();
+
+    let mut it = ring.iter();
+    assert_eq!(it.len(), 4);
+    assert_eq!(it.next(), Some(&3));
+    assert_eq!(it.next_back(), Some(&6));
+    assert_eq!(it.next_back(), Some(&5));
+    assert_eq!(it.next(), Some(&4));
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
+
+#[test]
+fn ring_buffer_from_iter_keeps_last_items() {
+    let ring: RingBuffer<char, 3> = "abcdefg".chars().collect();
+    assert_eq!(ring.iter().collect::<String>(), "efg");
+
+    let short: RingBuffer<char, 3> = "ab".chars().collect();
+    assert_eq!(short.iter().collect::<String>(), "ab");
+
+    let mut total = 0;
+    for n in &(0..100).collect::<RingBuffer<i32, 10>>() {
+        total += n;
+    }
+    assert_eq!(total, (90..100).sum());
+}
+
+#[test]
+fn ring_buffer_zero_capacity() {
+    let mut ring: RingBuffer<i32, 0> = RingBuffer::new();
+    assert_eq!(ring.push(1), Some(1));
+    assert!(ring.is_empty());
+    assert_eq!(ring.iter().next(), None);
+}
@@ -0,0 +1,213 @@
+//! `SortedVec<T>` keeps its items in ascending order at all times. Collecting
+//! into it sorts once; extending it inserts each new item in place. Equal
+//! items are all kept, in the order they arrived.
+
+use crate::consumers::SortedExt;
+use alloc::vec::Vec;
+use core::iter::{FusedIterator, Peekable};
+use core::slice;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedVec<T>(Vec<T>);
+
+impl<T: Ord> SortedVec<T> {
+    pub fn new() -> Self {
+        SortedVec(Vec::new())
+    }
+
+    /// Inserts `value` after any items equal to it.
+    pub fn insert(&mut self, value: T) {
+        let index = self.0.partition_point(|item| item <= &value);
+        self.0.insert(index, value);
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.0.binary_search(value).is_ok()
+    }
+
+    /// Walks both vectors at once, yielding all their items in order. Where
+    /// items are equal, those from `self` come first.
+    pub fn merge<'a>(&'a self, other: &'a SortedVec<T>) -> Merge<'a, T> {
+        // `Merge` is only right if both are sorted, which every method
+        // that changes a `SortedVec` should keep to.
+        debug_assert!(self.iter().is_ordered() && other.iter().is_ordered());
+        Merge {
+            left: self.0.iter().peekable(),
+            right: other.0.iter().peekable(),
+        }
+    }
+}
+
+impl<T> SortedVec<T> {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        SortedVec::new()
+    }
+}
+
+/// Sorts once, after collecting everything. The sort is stable, so equal
+/// items keep their original order.
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut items: Vec<T> = iter.into_iter().collect();
+        items.sort();
+        SortedVec(items)
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Merge<'a, T> {
+    left: Peekable<slice::Iter<'a, T>>,
+    right: Peekable<slice::Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Iterator for Merge<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) if r < l => self.right.next(),
+            (Some(_), _) => self.left.next(),
+            (None, _) => self.right.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.left.len() + self.right.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for Merge<'_, T> {}
+
+impl<T: Ord> FusedIterator for Merge<'_, T> {}
+
+#[test]
+fn sorted_vec_sorts_on_collect() {
+    let v: SortedVec<i32> = [5, 1, 4, 1, 3].into_iter().collect();
+    assert_eq!(v.as_slice(), [1, 1, 3, 4, 5]);
+    assert!(v.contains(&4));
+    assert!(!v.contains(&2));
+}
+
+#[test]
+fn sorted_vec_extend_inserts_in_order() {
+    let mut v: SortedVec<i32> = [10, 30].into_iter().collect();
+    v.extend([20, 40, 0, 30]);
+
+    assert_eq!(v.as_slice(), [0, 10, 20, 30, 30, 40]);
+}
+
+// Ordered by the number only; the letter tells equal items apart.
+#[cfg(test)]
+#[derive(Debug)]
+struct Tagged(u8, char);
+
+#[cfg(test)]
+mod tagged {
+    use super::Tagged;
+    use std::cmp::Ordering;
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Tagged {}
+
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+}
+
+#[test]
+fn sorted_vec_keeps_duplicates_in_arrival_order() {
+    let mut v: SortedVec<Tagged> = [Tagged(2, 'a'), Tagged(1, 'b'), Tagged(2, 'c')]
+        .into_iter()
+        .collect();
+    v.extend([Tagged(2, 'd'), Tagged(1, 'e')]);
+
+    let letters: String = v.iter().map(|t| t.1).collect();
+    assert_eq!(letters, "beacd");
+}
+
+#[test]
+fn sorted_vec_merge() {
+    let a: SortedVec<i32> = [1, 4, 4, 9].into_iter().collect();
+    let b: SortedVec<i32> = [0, 4, 10].into_iter().collect();
+
+    let merged: Vec<_> = a.merge(&b).copied().collect();
+    assert_eq!(merged, [0, 1, 4, 4, 4, 9, 10]);
+    assert_eq!(a.merge(&b).len(), 7);
+
+    let empty = SortedVec::new();
+    assert_iter_eq!(a.merge(&empty), a.iter());
+    assert_iter_eq!(empty.merge(&a), a.iter());
+}
+
+#[test]
+fn sorted_vec_merge_prefers_self_on_ties() {
+    let a: SortedVec<Tagged> = [Tagged(1, 'a'), Tagged(2, 'b')].into_iter().collect();
+    let b: SortedVec<Tagged> = [Tagged(1, 'x'), Tagged(2, 'y')].into_iter().collect();
+
+    let letters: String = a.merge(&b).map(|t| t.1).collect();
+    assert_eq!(letters, "axby");
+
+    let letters: String = b.merge(&a).map(|t| t.1).collect();
+    assert_eq!(letters, "xayb");
+}
@@ -0,0 +1,243 @@
+//! A trie (prefix tree) of strings. Keys that share a prefix share the path
+//! to it, so every key starting with some prefix lives in one subtree, and
+//! `iter_prefix` only has to walk that subtree.
+
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+pub struct Trie {
+    root: Node,
+    len: usize,
+}
+
+#[derive(Default)]
+struct Node {
+    // A `BTreeMap` keeps the children sorted, which is what makes a
+    // depth-first walk come out in lexicographic order.
+    children: BTreeMap<char, Node>,
+    // Whether the path to this node is a key, rather than just a prefix.
+    is_key: bool,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Trie {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `key`, returning `false` if it was already present.
+    pub fn insert(&mut self, key: &str) -> bool {
+        let node = key.chars().fold(&mut self.root, |node, c| {
+            node.children.entry(c).or_default()
+        });
+        if node.is_key {
+            return false;
+        }
+        node.is_key = true;
+        self.len += 1;
+        true
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.find(key).is_some_and(|node| node.is_key)
+    }
+
+    /// All keys, in lexicographic order.
+    pub fn iter(&self) -> Prefixed<'_> {
+        self.iter_prefix("")
+    }
+
+    /// The keys starting with `prefix`, in lexicographic order. Nothing is
+    /// collected up front: each call to `next()` walks just far enough into
+    /// the subtree to reach the following key.
+    pub fn iter_prefix(&self, prefix: &str) -> Prefixed<'_> {
+        match self.find(prefix) {
+            Some(node) => Prefixed {
+                key: prefix.to_string(),
+                stack: vec![node.children.iter()],
+                pending: node.is_key,
+            },
+            None => Prefixed {
+                key: String::new(),
+                stack: Vec::new(),
+                pending: false,
+            },
+        }
+    }
+
+    fn find(&self, prefix: &str) -> Option<&Node> {
+        prefix
+            .chars()
+            .try_fold(&self.root, |node, c| node.children.get(&c))
+    }
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie::new()
+    }
+}
+
+// Like `Bst`, a long key makes a deep chain of nodes, and the
+// compiler-generated drop would recurse once per character.
+impl Drop for Trie {
+    fn drop(&mut self) {
+        let mut stack: Vec<Node> = vec![core::mem::take(&mut self.root)];
+        while let Some(mut node) = stack.pop() {
+            stack.extend(core::mem::take(&mut node.children).into_values());
+        }
+    }
+}
+
+impl<'s> Extend<&'s str> for Trie {
+    fn extend<I: IntoIterator<Item = &'s str>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl<'s> FromIterator<&'s str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'s str>>(iter: I) -> Self {
+        let mut trie = Trie::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<'a> IntoIterator for &'a Trie {
+    type Item = String;
+    type IntoIter = Prefixed<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Prefixed<'a> {
+    // The path to the node whose children are on top of `stack`.
+    key: String,
+    // One iterator over the remaining children per level of the walk.
+    stack: Vec<btree_map::Iter<'a, char, Node>>,
+    // Whether the prefix itself is a key that hasn't been yielded yet.
+    pending: bool,
+}
+
+impl Iterator for Prefixed<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if core::mem::take(&mut self.pending) {
+            return Some(self.key.clone());
+        }
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some((&c, node)) => {
+                    self.key.push(c);
+                    self.stack.push(node.children.iter());
+                    if node.is_key {
+                        return Some(self.key.clone());
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    // The prefix's own level has no character to take off.
+                    if !self.stack.is_empty() {
+                        self.key.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FusedIterator for Prefixed<'_> {}
+
+#[cfg(test)]
+fn sample() -> Trie {
+    ["tea", "ten", "to", "inn", "in", "tend", "t", "team"]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn trie_iter_is_sorted() {
+    let trie = sample();
+    assert_eq!(trie.len(), 8);
+    assert_eq!(
+        trie.iter().collect::<Vec<_>>(),
+        ["in", "inn", "t", "tea", "team", "ten", "tend", "to"]
+    );
+}
+
+#[test]
+fn trie_iter_prefix_with_overlapping_prefixes() {
+    let trie = sample();
+    assert_eq!(
+        trie.iter_prefix("te").collect::<Vec<_>>(),
+        ["tea", "team", "ten", "tend"]
+    );
+    // A prefix that is itself a key comes first.
+    assert_eq!(trie.iter_prefix("ten").collect::<Vec<_>>(), ["ten", "tend"]);
+    assert_eq!(trie.iter_prefix("in").collect::<Vec<_>>(), ["in", "inn"]);
+    assert_eq!(trie.iter_prefix("team").collect::<Vec<_>>(), ["team"]);
+    assert_eq!(trie.iter_prefix("x").next(), None);
+    assert_eq!(trie.iter_prefix("teams").next(), None);
+}
+
+#[test]
+fn trie_iter_prefix_is_lazy() {
+    let trie: Trie = ["a", "ab", "abc", "b"].into_iter().collect();
+    let mut keys = trie.iter_prefix("a");
+    assert_eq!(keys.next().as_deref(), Some("a"));
+    assert_eq!(keys.next().as_deref(), Some("ab"));
+    // The walk is paused inside the subtree, one level per character.
+    assert_eq!(keys.stack.len(), 2);
+    assert_eq!(keys.next().as_deref(), Some("abc"));
+    assert_eq!(keys.next(), None);
+    assert_eq!(keys.next(), None);
+}
+
+#[test]
+fn trie_insert_and_contains() {
+    let mut trie = sample();
+    assert!(!trie.insert("ten"));
+    assert!(trie.insert("te"));
+    assert!(trie.insert(""));
+    assert_eq!(trie.len(), 10);
+
+    assert!(trie.contains("te"));
+    assert!(trie.contains(""));
+    assert!(!trie.contains("i"));
+    assert!(!trie.contains("tens"));
+    assert_eq!(trie.iter().next().as_deref(), Some(""));
+}
+
+#[test]
+fn trie_unicode_and_long_keys() {
+    let trie: Trie = ["naïve", "naive", "nai"].into_iter().collect();
+    assert_eq!(
+        (&trie).into_iter().collect::<Vec<_>>(),
+        ["nai", "naive", "naïve"]
+    );
+
+    let long = "a".repeat(100_000);
+    let trie: Trie = [long.as_str()].into_iter().collect();
+    assert_eq!(
+        trie.iter_prefix("aaa").next().map(|k| k.len()),
+        Some(100_000)
+    );
+}
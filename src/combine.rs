@@ -0,0 +1,14 @@
+//! Free functions that combine several iterators into one, as typed
+//! counterparts to the `chain!` and `izip!` macros: for when the number of
+//! iterators is only known at run time, or the combined iterator needs a
+//! type that can be named.
+
+pub mod chain_many;
+#[cfg(feature = "std")]
+pub mod hash_join;
+pub mod zip;
+
+pub use chain_many::{chain_many, ChainMany};
+#[cfg(feature = "std")]
+pub use hash_join::{hash_join, hash_join_left, HashJoin, HashJoinLeft};
+pub use zip::{zip3, zip4, Zip3, Zip4};
@@ -0,0 +1,125 @@
+//! `chain_many(sources)` yields all the items of the first source, then
+//! all of the second, and so on. `a.chain(b).chain(c)` and `chain!` fix the
+//! number of sources when the code is written; here they can come from a
+//! `Vec` built at run time, and they all have the same type.
+
+use alloc::collections::VecDeque;
+use core::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct ChainMany<I> {
+    // The sources still to finish, the current one at the front. Keeping
+    // them all means `size_hint` can add up every one, not just the
+    // current one.
+    sources: VecDeque<I>,
+}
+
+/// Chains every iterable in `sources`, in order.
+pub fn chain_many<S>(sources: S) -> ChainMany<<S::Item as IntoIterator>::IntoIter>
+where
+    S: IntoIterator,
+    S::Item: IntoIterator,
+{
+    ChainMany {
+        sources: sources.into_iter().map(IntoIterator::into_iter).collect(),
+    }
+}
+
+impl<I: Iterator> Iterator for ChainMany<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.sources.front_mut()?.next() {
+                Some(item) => return Some(item),
+                None => {
+                    self.sources.pop_front();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources
+            .iter()
+            .map(Iterator::size_hint)
+            .fold((0, Some(0)), |(lower, upper), (l, u)| {
+                let upper = match (upper, u) {
+                    (Some(a), Some(b)) => a.checked_add(b),
+                    _ => None,
+                };
+                (lower.saturating_add(l), upper)
+            })
+    }
+
+    fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        self.sources
+            .into_iter()
+            .fold(init, |acc, source| source.fold(acc, &mut g))
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for ChainMany<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.sources.back_mut()?.next_back() {
+                Some(item) => return Some(item),
+                None => {
+                    self.sources.pop_back();
+                }
+            }
+        }
+    }
+}
+
+// Once the sources have all been dropped there's nothing left to call.
+impl<I: Iterator> FusedIterator for ChainMany<I> {}
+
+#[cfg(test)]
+use alloc::{vec, vec::Vec};
+
+#[test]
+fn chain_many_goes_through_the_sources_in_order() {
+    let sources = vec![vec![1, 2], vec![], vec![3], vec![4, 5, 6]];
+    assert_iter_eq!(chain_many(sources.clone()), [1, 2, 3, 4, 5, 6]);
+    assert_eq!(chain_many(&sources).copied().sum::<i32>(), 21);
+
+    // The number of sources is up to the caller.
+    let ranges: Vec<_> = (1..4).map(|n| 0..n).collect();
+    assert_iter_eq!(chain_many(ranges), [0, 0, 1, 0, 1, 2]);
+}
+
+#[test]
+fn chain_many_with_zero_or_one_source() {
+    let mut none = chain_many(Vec::<core::ops::Range<u8>>::new());
+    assert_eq!(none.size_hint(), (0, Some(0)));
+    assert_eq!(none.next(), None);
+    assert_eq!(none.next_back(), None);
+
+    assert_iter_eq!(chain_many(core::iter::once(7..10)), [7, 8, 9]);
+    assert_eq!(
+        chain_many(core::iter::once(7..10)).size_hint(),
+        (3, Some(3))
+    );
+}
+
+#[test]
+fn chain_many_size_hint_adds_up_every_source() {
+    let mut it = chain_many([0..2, 0..0, 5..8]);
+    assert_eq!(it.size_hint(), (5, Some(5)));
+    it.next();
+    it.next_back();
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_iter_eq!(it.rev(), [6, 5, 1]);
+
+    // Filters can only say how many items they might yield, and the sum
+    // of the bounds stays a bound; one unbounded source unbounds the lot.
+    let odd = |n: &i32| n % 2 == 1;
+    let filtered = chain_many([(0..4).filter(odd).take(10), (0..6).filter(odd).take(1)]);
+    assert_eq!(filtered.size_hint(), (0, Some(5)));
+    let open = chain_many([0..usize::MAX, 0..usize::MAX]);
+    assert_eq!(open.size_hint(), (usize::MAX, None));
+}
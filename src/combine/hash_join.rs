@@ -0,0 +1,370 @@
+//! `hash_join(left, right, key_l, key_r)` pairs up the records of two
+//! iterators whose keys are equal, without either having to be sorted
+//! (for sorted input, `merge_join_by` needs no table at all). One side is
+//! collected into a `HashMap` from key to records; the other is streamed
+//! past it, each record yielding a pair for every record with its key.
+//!
+//! `hash_join` builds the table from whichever side's `size_hint` says is
+//! smaller, and its pairs come in the order of the other side.
+//! `hash_join_left` is a left outer join: each left record with no match
+//! still comes out, paired with `None`. It has to stream the left side to
+//! see them, so it always builds from the right.
+//!
+//! Every record in the table is cloned for each match it has. A streamed
+//! record is cloned for each of its matches but the last, which gets the
+//! record itself.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+
+/// Streams `S` past a table of `B`s, yielding each streamed record with
+/// each of its matches, or with `None` if it has none and `outer` is set.
+struct Probe<S: Iterator, B, K, F> {
+    stream: S,
+    key: F,
+    table: HashMap<K, Vec<B>>,
+    outer: bool,
+    // The streamed record whose matches are being yielded, its key, and
+    // the index of the next match.
+    current: Option<(S::Item, K, usize)>,
+}
+
+impl<S, B, K, F> Probe<S, B, K, F>
+where
+    S: Iterator,
+    K: Hash + Eq,
+    F: FnMut(&S::Item) -> K,
+{
+    fn new<I, G>(stream: S, key: F, build: I, mut build_key: G, outer: bool) -> Self
+    where
+        I: Iterator<Item = B>,
+        G: FnMut(&B) -> K,
+    {
+        let mut table: HashMap<K, Vec<B>> = HashMap::new();
+        for record in build {
+            table.entry(build_key(&record)).or_default().push(record);
+        }
+        Probe {
+            stream,
+            key,
+            table,
+            outer,
+            current: None,
+        }
+    }
+}
+
+impl<S, B, K, F> Iterator for Probe<S, B, K, F>
+where
+    S: Iterator,
+    S::Item: Clone,
+    B: Clone,
+    K: Hash + Eq,
+    F: FnMut(&S::Item) -> K,
+{
+    type Item = (S::Item, Option<B>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((record, key, index)) = &mut self.current {
+                let matches = self.table.get(key).map_or(&[][..], Vec::as_slice);
+                if let Some(other) = matches.get(*index) {
+                    let other = other.clone();
+                    *index += 1;
+                    if *index < matches.len() {
+                        return Some((record.clone(), Some(other)));
+                    }
+                    let (record, _, _) = self.current.take().unwrap();
+                    return Some((record, Some(other)));
+                }
+                let unmatched = matches.is_empty();
+                let (record, _, _) = self.current.take().unwrap();
+                if unmatched && self.outer {
+                    return Some((record, None));
+                }
+            }
+            let record = self.stream.next()?;
+            let key = (self.key)(&record);
+            self.current = Some((record, key, 0));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any number of matches per record, and maybe none.
+        match self.stream.size_hint() {
+            (_, Some(0)) if self.current.is_none() => (0, Some(0)),
+            _ => (0, None),
+        }
+    }
+}
+
+impl<S: Iterator + fmt::Debug, B, K, F> fmt::Debug for Probe<S, B, K, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Probe")
+            .field("stream", &self.stream)
+            .field("keys", &self.table.len())
+            .field("outer", &self.outer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Streams `S` past a table of the items of `B`.
+type ProbeWith<S, B, K, F> = Probe<S, <B as Iterator>::Item, K, F>;
+
+/// Which side `hash_join` streams past a table of the other.
+#[derive(Debug)]
+enum Sides<L: Iterator, R: Iterator, K, FL, FR> {
+    StreamLeft(ProbeWith<L, R, K, FL>),
+    StreamRight(ProbeWith<R, L, K, FR>),
+}
+
+/// Matching pairs from `hash_join`.
+#[derive(Debug)]
+pub struct HashJoin<L, R, K, FL, FR>
+where
+    L: Iterator,
+    R: Iterator,
+{
+    probe: Sides<L, R, K, FL, FR>,
+}
+
+/// Left records with their matches from `hash_join_left`.
+#[derive(Debug)]
+pub struct HashJoinLeft<L, R, K, F>
+where
+    L: Iterator,
+    R: Iterator,
+{
+    probe: ProbeWith<L, R, K, F>,
+}
+
+/// What `size_hint` says an iterator could yield at most.
+fn at_most(iter: &impl Iterator) -> usize {
+    iter.size_hint().1.unwrap_or(usize::MAX)
+}
+
+/// The pairs of a left and a right record with equal keys.
+pub fn hash_join<L, R, K, FL, FR>(
+    left: L,
+    right: R,
+    key_l: FL,
+    key_r: FR,
+) -> HashJoin<L::IntoIter, R::IntoIter, K, FL, FR>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    K: Hash + Eq,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    let (left, right) = (left.into_iter(), right.into_iter());
+    let probe = if at_most(&left) < at_most(&right) {
+        Sides::StreamRight(Probe::new(right, key_r, left, key_l, false))
+    } else {
+        Sides::StreamLeft(Probe::new(left, key_l, right, key_r, false))
+    };
+    HashJoin { probe }
+}
+
+/// Each left record with each right record whose key is equal, or with
+/// `None` if there are none.
+pub fn hash_join_left<L, R, K, FL, FR>(
+    left: L,
+    right: R,
+    key_l: FL,
+    key_r: FR,
+) -> HashJoinLeft<L::IntoIter, R::IntoIter, K, FL>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    K: Hash + Eq,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    HashJoinLeft {
+        probe: Probe::new(left.into_iter(), key_l, right.into_iter(), key_r, true),
+    }
+}
+
+impl<L, R, K, FL, FR> Iterator for HashJoin<L, R, K, FL, FR>
+where
+    L: Iterator,
+    L::Item: Clone,
+    R: Iterator,
+    R::Item: Clone,
+    K: Hash + Eq,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+    type Item = (L::Item, R::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // An inner join only ever pairs a record with `Some` match.
+        match &mut self.probe {
+            Sides::StreamLeft(probe) => probe.next().map(|(l, r)| (l, r.unwrap())),
+            Sides::StreamRight(probe) => probe.next().map(|(r, l)| (l.unwrap(), r)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.probe {
+            Sides::StreamLeft(probe) => probe.size_hint(),
+            Sides::StreamRight(probe) => probe.size_hint(),
+        }
+    }
+}
+
+impl<L, R, K, F> Iterator for HashJoinLeft<L, R, K, F>
+where
+    L: Iterator,
+    L::Item: Clone,
+    R: Iterator,
+    R::Item: Clone,
+    K: Hash + Eq,
+    F: FnMut(&L::Item) -> K,
+{
+    type Item = (L::Item, Option<R::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.probe.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every left record still to come comes out at least once.
+        let (lower, _) = self.probe.stream.size_hint();
+        (lower, self.probe.size_hint().1)
+    }
+}
+
+impl<L, R, K, FL, FR> FusedIterator for HashJoin<L, R, K, FL, FR>
+where
+    L: FusedIterator,
+    L::Item: Clone,
+    R: FusedIterator,
+    R::Item: Clone,
+    K: Hash + Eq,
+    FL: FnMut(&L::Item) -> K,
+    FR: FnMut(&R::Item) -> K,
+{
+}
+
+impl<L, R, K, F> FusedIterator for HashJoinLeft<L, R, K, F>
+where
+    L: FusedIterator,
+    L::Item: Clone,
+    R: Iterator,
+    R::Item: Clone,
+    K: Hash + Eq,
+    F: FnMut(&L::Item) -> K,
+{
+}
+
+#[cfg(test)]
+fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+    v.sort();
+    v
+}
+
+// A record that counts how often it is cloned.
+#[cfg(test)]
+#[derive(Debug)]
+struct Counted<'a>(i32, &'a std::cell::Cell<usize>);
+
+#[cfg(test)]
+impl Clone for Counted<'_> {
+    fn clone(&self) -> Self {
+        self.1.set(self.1.get() + 1);
+        Counted(self.0, self.1)
+    }
+}
+
+#[test]
+fn hash_join_pairs_equal_keys() {
+    let people = [(1, "ann"), (2, "bob"), (3, "cy")];
+    let orders = [(2, "tea"), (1, "jam"), (9, "ham")];
+    let joined: Vec<_> = hash_join(people, orders, |p| p.0, |o| o.0)
+        .map(|(p, o)| (p.1, o.1))
+        .collect();
+    assert_eq!(sorted(joined), [("ann", "jam"), ("bob", "tea")]);
+
+    let none: Vec<_> = hash_join(people, Vec::<(i32, &str)>::new(), |p| p.0, |o| o.0).collect();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn hash_join_with_duplicate_keys_on_both_sides() {
+    let left = [(1, 'a'), (1, 'b'), (2, 'c'), (4, 'd')];
+    let right = [(1, 'x'), (3, 'y'), (1, 'z'), (2, 'w'), (2, 'v')];
+    let expected = [
+        ('a', 'x'),
+        ('a', 'z'),
+        ('b', 'x'),
+        ('b', 'z'),
+        ('c', 'v'),
+        ('c', 'w'),
+    ];
+
+    // The shorter side becomes the table, so this builds from the left...
+    let pairs: Vec<_> = hash_join(left, right, |l| l.0, |r| r.0)
+        .map(|(l, r)| (l.1, r.1))
+        .collect();
+    assert_eq!(sorted(pairs), expected);
+
+    // ... and this from the right, with the same pairs.
+    let pairs: Vec<_> = hash_join(left.iter().chain(&[(7, 'e'); 3]), right, |l| l.0, |r| r.0)
+        .map(|(l, r)| (l.1, r.1))
+        .collect();
+    assert_eq!(sorted(pairs), expected);
+}
+
+#[test]
+fn hash_join_moves_a_streamed_record_into_its_last_match() {
+    let clones = std::cell::Cell::new(0);
+    let left = [
+        Counted(1, &clones),
+        Counted(2, &clones),
+        Counted(3, &clones),
+    ];
+    let right = [(1, 'x'), (1, 'y'), (1, 'z'), (2, 'w')];
+    let joined: Vec<_> = hash_join_left(left, right, |l| l.0, |r| r.0)
+        .map(|(l, r)| (l.0, r.map(|r| r.1)))
+        .collect();
+    assert_eq!(
+        joined,
+        [
+            (1, Some('x')),
+            (1, Some('y')),
+            (1, Some('z')),
+            (2, Some('w')),
+            (3, None)
+        ]
+    );
+    // Two for the first record's three matches, and none for the others.
+    assert_eq!(clones.get(), 2);
+}
+
+#[test]
+fn hash_join_left_keeps_unmatched_left_records() {
+    let people = [(1, "ann"), (2, "bob"), (3, "cy")];
+    let orders = [(2, "tea"), (1, "jam"), (2, "pie")];
+    let joined: Vec<_> = hash_join_left(people, orders, |p| p.0, |o| o.0)
+        .map(|(p, o)| (p.1, o.map(|o| o.1)))
+        .collect();
+    // Left records come in their own order, each with its matches in the
+    // right side's order.
+    assert_eq!(
+        joined,
+        [
+            ("ann", Some("jam")),
+            ("bob", Some("tea")),
+            ("bob", Some("pie")),
+            ("cy", None),
+        ]
+    );
+
+    let it = hash_join_left(people, Vec::<(i32, &str)>::new(), |p| p.0, |o| o.0);
+    assert_eq!(it.size_hint().0, 3);
+    assert!(it.map(|(_, o)| o).all(|o| o.is_none()));
+}
@@ -0,0 +1,189 @@
+//! `zip3(a, b, c)` and `zip4(a, b, c, d)` yield flat tuples, `(a, b, c)`
+//! rather than the `((a, b), c)` of two `zip`s, and stop at the shortest
+//! input. `izip!` does the same for any number of iterators, but its type
+//! can't be written down; these have a struct of their own that can go in
+//! a field or a signature.
+
+use core::iter::FusedIterator;
+
+/// The `size_hint` of zipping iterators with these hints: no more items
+/// than the shortest has.
+fn min_hint(hints: &[(usize, Option<usize>)]) -> (usize, Option<usize>) {
+    hints
+        .iter()
+        .fold((usize::MAX, None), |(lower, upper), &(l, u)| {
+            let upper = match (upper, u) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            (lower.min(l), upper)
+        })
+}
+
+#[derive(Debug, Clone)]
+pub struct Zip3<A, B, C> {
+    a: A,
+    b: B,
+    c: C,
+}
+
+#[derive(Debug, Clone)]
+pub struct Zip4<A, B, C, D> {
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+}
+
+pub fn zip3<A, B, C>(a: A, b: B, c: C) -> Zip3<A::IntoIter, B::IntoIter, C::IntoIter>
+where
+    A: IntoIterator,
+    B: IntoIterator,
+    C: IntoIterator,
+{
+    Zip3 {
+        a: a.into_iter(),
+        b: b.into_iter(),
+        c: c.into_iter(),
+    }
+}
+
+pub fn zip4<A, B, C, D>(
+    a: A,
+    b: B,
+    c: C,
+    d: D,
+) -> Zip4<A::IntoIter, B::IntoIter, C::IntoIter, D::IntoIter>
+where
+    A: IntoIterator,
+    B: IntoIterator,
+    C: IntoIterator,
+    D: IntoIterator,
+{
+    Zip4 {
+        a: a.into_iter(),
+        b: b.into_iter(),
+        c: c.into_iter(),
+        d: d.into_iter(),
+    }
+}
+
+impl<A, B, C> Iterator for Zip3<A, B, C>
+where
+    A: Iterator,
+    B: Iterator,
+    C: Iterator,
+{
+    type Item = (A::Item, B::Item, C::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Like `zip`, an earlier iterator may have yielded an item that is
+        // then dropped, when a later one runs out first.
+        Some((self.a.next()?, self.b.next()?, self.c.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        min_hint(&[self.a.size_hint(), self.b.size_hint(), self.c.size_hint()])
+    }
+}
+
+impl<A, B, C, D> Iterator for Zip4<A, B, C, D>
+where
+    A: Iterator,
+    B: Iterator,
+    C: Iterator,
+    D: Iterator,
+{
+    type Item = (A::Item, B::Item, C::Item, D::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((
+            self.a.next()?,
+            self.b.next()?,
+            self.c.next()?,
+            self.d.next()?,
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        min_hint(&[
+            self.a.size_hint(),
+            self.b.size_hint(),
+            self.c.size_hint(),
+            self.d.size_hint(),
+        ])
+    }
+}
+
+impl<A, B, C> ExactSizeIterator for Zip3<A, B, C>
+where
+    A: ExactSizeIterator,
+    B: ExactSizeIterator,
+    C: ExactSizeIterator,
+{
+}
+
+impl<A, B, C, D> ExactSizeIterator for Zip4<A, B, C, D>
+where
+    A: ExactSizeIterator,
+    B: ExactSizeIterator,
+    C: ExactSizeIterator,
+    D: ExactSizeIterator,
+{
+}
+
+impl<A, B, C> FusedIterator for Zip3<A, B, C>
+where
+    A: FusedIterator,
+    B: FusedIterator,
+    C: FusedIterator,
+{
+}
+
+impl<A, B, C, D> FusedIterator for Zip4<A, B, C, D>
+where
+    A: FusedIterator,
+    B: FusedIterator,
+    C: FusedIterator,
+    D: FusedIterator,
+{
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn zip3_and_zip4_yield_flat_tuples() {
+    let names = ["a", "b", "c"];
+    let zipped: Vec<_> = zip3(names, 1.., [true, false, true]).collect();
+    assert_eq!(zipped, [("a", 1, true), ("b", 2, false), ("c", 3, true)]);
+    assert_iter_eq!(
+        zip3(names, 1.., [true, false, true]),
+        izip!(names, 1.., [true, false, true])
+    );
+
+    let zipped: Vec<_> = zip4(0..2, "xy".chars(), [1.5, 2.5], 10..).collect();
+    assert_eq!(zipped, [(0, 'x', 1.5, 10), (1, 'y', 2.5, 11)]);
+}
+
+#[test]
+fn zip_stops_at_the_shortest() {
+    assert_eq!(zip3(0..5, 0..0, 0..5).count(), 0);
+    assert_eq!(zip4(0..5, 0..4, 0..3, 0..6).count(), 3);
+    assert_eq!(zip4(0.., 1.., 2.., 0..0).next(), None);
+}
+
+#[test]
+fn zip_size_hint_is_the_shortest() {
+    let mut it = zip3(0..5, [1, 2, 3], 0..10);
+    assert_eq!(it.len(), 3);
+    it.next();
+    assert_eq!(it.len(), 2);
+
+    // An endless input doesn't bound anything; a filtered one only bounds
+    // from above.
+    assert_eq!(zip3(0.., 0.., 0..).size_hint(), (usize::MAX, None));
+    assert_eq!(zip4(0.., 0..7, 0.., 0..9).size_hint(), (7, Some(7)));
+    let evens = (0..6).filter(|n| n % 2 == 0);
+    assert_eq!(zip3(evens, 0..4, 0..).size_hint(), (0, Some(4)));
+}
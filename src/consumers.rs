@@ -0,0 +1,21 @@
+//! Consumers that std doesn't have: methods that run an iterator to the
+//! end (or until they know the answer) and return a value, on extension
+//! traits in the same way as the adapters.
+
+pub mod compare;
+pub mod extremes;
+pub mod partition;
+#[cfg(feature = "std")]
+pub mod sample;
+#[cfg(feature = "std")]
+pub mod shuffle;
+pub mod sorted;
+
+pub use compare::CompareExt;
+pub use extremes::ExtremesExt;
+pub use partition::PartitionExt;
+#[cfg(feature = "std")]
+pub use sample::SampleExt;
+#[cfg(feature = "std")]
+pub use shuffle::ShuffleExt;
+pub use sorted::SortedExt;
@@ -0,0 +1,155 @@
+//! Lexicographic comparison of two iterators with a comparator of your
+//! own, item by item and without collecting either one: the first pair
+//! that differs decides, and if one runs out first it is the lesser.
+//!
+//! `Iterator::cmp`, `partial_cmp` and `eq` do this with the items' own
+//! `Ord`, `PartialOrd` and `PartialEq`. std's `cmp_by` and friends, which
+//! take a closure, are still unstable, so these are `lex_`-prefixed to
+//! stay out of their way. The two sides can have different item types.
+
+use core::cmp::Ordering;
+
+pub trait CompareExt: Iterator {
+    /// Compares with `other`, item by item, using `cmp`.
+    fn lex_cmp_by<I, F>(self, other: I, mut cmp: F) -> Ordering
+    where
+        Self: Sized,
+        I: IntoIterator,
+        F: FnMut(Self::Item, I::Item) -> Ordering,
+    {
+        self.lex_partial_cmp_by(other, |a, b| Some(cmp(a, b)))
+            .expect("a total order always compares")
+    }
+
+    /// Compares with `other`, item by item, using `partial_cmp`; `None` as
+    /// soon as a pair can't be compared.
+    fn lex_partial_cmp_by<I, F>(mut self, other: I, mut partial_cmp: F) -> Option<Ordering>
+    where
+        Self: Sized,
+        I: IntoIterator,
+        F: FnMut(Self::Item, I::Item) -> Option<Ordering>,
+    {
+        let mut other = other.into_iter();
+        loop {
+            match (self.next(), other.next()) {
+                (None, None) => return Some(Ordering::Equal),
+                (None, Some(_)) => return Some(Ordering::Less),
+                (Some(_), None) => return Some(Ordering::Greater),
+                (Some(a), Some(b)) => match partial_cmp(a, b)? {
+                    Ordering::Equal => {}
+                    decided => return Some(decided),
+                },
+            }
+        }
+    }
+
+    /// Whether `other` has as many items, each `eq` to the one here.
+    fn lex_eq_by<I, F>(mut self, other: I, mut eq: F) -> bool
+    where
+        Self: Sized,
+        I: IntoIterator,
+        F: FnMut(Self::Item, I::Item) -> bool,
+    {
+        let mut other = other.into_iter();
+        loop {
+            match (self.next(), other.next()) {
+                (None, None) => return true,
+                (Some(a), Some(b)) => {
+                    if !eq(a, b) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl<I: Iterator> CompareExt for I {}
+
+#[test]
+fn lex_cmp_by_compares_item_by_item() {
+    let by_len = |a: &str, b: &str| a.len().cmp(&b.len());
+    let words = ["one", "three"];
+    assert_eq!(
+        words.into_iter().lex_cmp_by(["two", "seven"], by_len),
+        Ordering::Equal
+    );
+    assert_eq!(
+        words.into_iter().lex_cmp_by(["two", "six"], by_len),
+        Ordering::Greater
+    );
+    assert_eq!(
+        words.into_iter().lex_cmp_by(["eleven"], by_len),
+        Ordering::Less
+    );
+
+    // With the same comparator as `Ord`, it agrees with `Iterator::cmp`.
+    assert_eq!((0..3).lex_cmp_by(0..3, |a, b| a.cmp(&b)), (0..3).cmp(0..3));
+}
+
+#[test]
+fn lex_cmp_by_with_different_lengths() {
+    // A prefix is less than the whole, whichever side it's on.
+    assert_eq!(
+        [1, 2].iter().lex_cmp_by(&[1, 2, 3], |a, b| a.cmp(b)),
+        Ordering::Less
+    );
+    assert_eq!(
+        [1, 2, 3].iter().lex_cmp_by(&[1, 2], |a, b| a.cmp(b)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        core::iter::empty::<u8>().lex_cmp_by(0..0, |a, b| a.cmp(&b)),
+        Ordering::Equal
+    );
+    // The first difference decides before the lengths do.
+    assert_eq!(
+        [9].iter().lex_cmp_by(&[1, 2, 3], |a, b| a.cmp(b)),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn lex_cmp_by_with_an_inverted_comparator() {
+    let descending = |a: i32, b: i32| b.cmp(&a);
+    assert_eq!(
+        [3, 1].into_iter().lex_cmp_by([3, 2], descending),
+        Ordering::Greater
+    );
+    assert_eq!(
+        [3, 2].into_iter().lex_cmp_by([3, 1], descending),
+        Ordering::Less
+    );
+    // Running out first is still less: only the items' order is inverted.
+    assert_eq!(
+        [3].into_iter().lex_cmp_by([3, 1], descending),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn lex_partial_cmp_by_and_lex_eq_by() {
+    let cmp = |a: f64, b: f64| a.partial_cmp(&b);
+    assert_eq!(
+        [1.0, 2.0].into_iter().lex_partial_cmp_by([1.0, 2.5], cmp),
+        Some(Ordering::Less)
+    );
+    assert_eq!([f64::NAN].into_iter().lex_partial_cmp_by([1.0], cmp), None);
+    // A difference before the NaN decides.
+    assert_eq!(
+        [0.0, f64::NAN]
+            .into_iter()
+            .lex_partial_cmp_by([1.0, 1.0], cmp),
+        Some(Ordering::Less)
+    );
+
+    let same_letter = |a: char, b: char| a.eq_ignore_ascii_case(&b);
+    assert!("Rust".chars().lex_eq_by("rUST".chars(), same_letter));
+    assert!(!"Rust".chars().lex_eq_by("rus".chars(), same_letter));
+    assert!(!"Rus".chars().lex_eq_by("rust".chars(), same_letter));
+    // Different item types on each side.
+    assert!([1u8, 2]
+        .into_iter()
+        .lex_eq_by(["1", "2"], |n, s| s.parse() == Ok(n)));
+}
@@ -0,0 +1,124 @@
+//! `min_set()` and `max_set()` return every item that ties for the
+//! minimum or maximum, where `min()` and `max()` pick just one of them.
+//! Both go over the items once, keeping the ties for the best value so
+//! far and starting again when a better one turns up.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// The items that nothing compares `Less` than, in the order they came.
+fn extreme_set<I, F>(iter: I, mut compare: F) -> Vec<I::Item>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    let mut set = Vec::new();
+    for item in iter {
+        match set.first().map(|kept| compare(&item, kept)) {
+            None | Some(Ordering::Equal) => set.push(item),
+            Some(Ordering::Less) => {
+                set.clear();
+                set.push(item);
+            }
+            Some(Ordering::Greater) => {}
+        }
+    }
+    set
+}
+
+pub trait ExtremesExt: Iterator {
+    /// Every item equal to the minimum, in the order they came. Empty if
+    /// the iterator is.
+    fn min_set(self) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        extreme_set(self, Ord::cmp)
+    }
+
+    /// Every item that `compare` finds to be the minimum.
+    fn min_set_by<F>(self, compare: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        extreme_set(self, compare)
+    }
+
+    /// Every item whose key is the minimum key.
+    fn min_set_by_key<K, F>(self, mut key: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        extreme_set(self, |a, b| key(a).cmp(&key(b)))
+    }
+
+    /// Every item equal to the maximum, in the order they came. Empty if
+    /// the iterator is.
+    fn max_set(self) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        extreme_set(self, |a, b| b.cmp(a))
+    }
+
+    /// Every item that `compare` finds to be the maximum.
+    fn max_set_by<F>(self, mut compare: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        extreme_set(self, |a, b| compare(b, a))
+    }
+
+    /// Every item whose key is the maximum key.
+    fn max_set_by_key<K, F>(self, mut key: F) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        extreme_set(self, |a, b| key(b).cmp(&key(a)))
+    }
+}
+
+impl<I: Iterator> ExtremesExt for I {}
+
+#[test]
+fn min_and_max_set_keep_every_tie() {
+    let v = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 9];
+    assert_eq!(v.iter().min_set(), [&1, &1]);
+    assert_eq!(v.iter().max_set(), [&9, &9]);
+    assert_eq!([7].into_iter().max_set(), [7]);
+    assert_eq!([2, 2, 2].into_iter().min_set(), [2, 2, 2]);
+}
+
+#[test]
+fn min_and_max_set_of_nothing_is_empty() {
+    assert!(core::iter::empty::<u8>().min_set().is_empty());
+    assert!(core::iter::empty::<u8>().max_set_by_key(|&n| n).is_empty());
+}
+
+#[test]
+fn extreme_sets_by_key_and_comparison() {
+    let words = ["kiwi", "fig", "pear", "plum", "apple", "yam"];
+    assert_eq!(words.iter().min_set_by_key(|w| w.len()), [&"fig", &"yam"]);
+    assert_eq!(words.iter().max_set_by_key(|w| w.len()), [&"apple"]);
+    // Ties keep their order.
+    assert_eq!(
+        words
+            .into_iter()
+            .max_set_by(|a, b| a.len().cmp(&b.len()).reverse()),
+        ["fig", "yam"]
+    );
+
+    let temps = [21.5, 19.0, 23.25, 19.0];
+    assert_eq!(
+        temps.into_iter().min_set_by(|a: &f64, b| a.total_cmp(b)),
+        [19.0, 19.0]
+    );
+}
@@ -0,0 +1,86 @@
+//! `partition_n(n, f)` is `partition` with `n` sides instead of two: `f`
+//! gives each item the index of its bucket, and the buckets come back as
+//! `n` vectors, each holding its items in the order they came.
+//!
+//! An index of `n` or more is a bug in `f`, so it panics the way indexing
+//! a slice out of bounds does, rather than losing the item or putting it
+//! somewhere it doesn't belong.
+
+use alloc::vec::Vec;
+
+pub trait PartitionExt: Iterator {
+    /// The items in `n` buckets, by the index `f` gives each of them.
+    ///
+    /// # Panics
+    ///
+    /// If `f` returns an index that is not less than `n`.
+    #[track_caller]
+    fn partition_n<F>(self, n: usize, mut f: F) -> Vec<Vec<Self::Item>>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item) -> usize,
+    {
+        let mut buckets: Vec<Vec<Self::Item>> = (0..n).map(|_| Vec::new()).collect();
+        for item in self {
+            let index = f(&item);
+            match buckets.get_mut(index) {
+                Some(bucket) => bucket.push(item),
+                None => panic!("partition_n: bucket {index} is out of range for {n} buckets"),
+            }
+        }
+        buckets
+    }
+}
+
+impl<I: Iterator> PartitionExt for I {}
+
+#[test]
+fn partition_n_fills_each_bucket() {
+    let by_rem = (0..10).partition_n(3, |n| n % 3);
+    assert_eq!(
+        by_rem,
+        [
+            alloc::vec![0, 3, 6, 9],
+            alloc::vec![1, 4, 7],
+            alloc::vec![2, 5, 8]
+        ]
+    );
+
+    // Buckets nothing goes into are still there, and empty.
+    let sparse = [5, 15].into_iter().partition_n(3, |n| n / 10);
+    assert_eq!(sparse, [alloc::vec![5], alloc::vec![15], alloc::vec![]]);
+    assert_eq!(core::iter::empty::<u8>().partition_n(2, |_| 0), [[], []]);
+
+    // With two buckets it is `partition`.
+    let (even, odd): (Vec<_>, Vec<_>) = (0..10).partition(|n| n % 2 == 0);
+    assert_eq!((0..10).partition_n(2, |n| (n % 2) as usize), [even, odd]);
+}
+
+#[test]
+fn partition_n_keeps_order_within_buckets() {
+    // Words by length: each bucket lists its words as they appeared.
+    let words = ["bb", "a", "cc", "d", "eee", "aa", "f"];
+    let by_len = words.into_iter().partition_n(4, |w| w.len());
+    assert_eq!(
+        by_len,
+        [
+            alloc::vec![],
+            alloc::vec!["a", "d", "f"],
+            alloc::vec!["bb", "cc", "aa"],
+            alloc::vec!["eee"],
+        ]
+    );
+
+    // Tagging items with their position shows none were reordered.
+    let buckets = (0..100).map(|n| (n * 7 % 5, n)).partition_n(5, |&(b, _)| b);
+    for bucket in &buckets {
+        assert!(bucket.windows(2).all(|w| w[0].1 < w[1].1));
+    }
+    assert_eq!(buckets.iter().map(Vec::len).sum::<usize>(), 100);
+}
+
+#[test]
+#[should_panic(expected = "partition_n: bucket 3 is out of range for 3 buckets")]
+fn partition_n_panics_on_an_index_out_of_range() {
+    (0..10).partition_n(3, |&n| n);
+}
@@ -0,0 +1,155 @@
+//! `choose_weighted(k, rng)` picks `k` items at random from `(item, weight)`
+//! pairs, without replacement, each one more likely to be picked the more
+//! it weighs. It is the A-Res algorithm of Efraimidis and Spirakis: every
+//! item draws a key `u^(1/weight)` for a uniform `u` in `(0, 1]`, and the
+//! `k` largest keys win. Only the best `k` so far are kept, in a min-heap,
+//! so it needs one pass and room for `k` items however many there are.
+//!
+//! The keys are compared by their logarithms, `ln(u) / weight`, which
+//! order the same way but don't round to 0 for tiny weights.
+
+use rand::Rng;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// An item with its key, ordered by the key alone.
+struct Keyed<T> {
+    key: f64,
+    item: T,
+}
+
+impl<T> PartialEq for Keyed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Keyed<T> {}
+
+impl<T> PartialOrd for Keyed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Keyed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.total_cmp(&other.key)
+    }
+}
+
+pub trait SampleExt: Iterator {
+    /// `k` of the items, chosen at random with the chance of each in
+    /// proportion to its weight, and in no particular order. Items with a
+    /// weight of 0 are never chosen, so there are fewer than `k` if fewer
+    /// than `k` items weigh anything.
+    ///
+    /// # Panics
+    ///
+    /// If a weight is negative, infinite or NaN.
+    #[track_caller]
+    fn choose_weighted<T, R>(self, k: usize, rng: &mut R) -> Vec<T>
+    where
+        Self: Sized + Iterator<Item = (T, f64)>,
+        R: Rng + ?Sized,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+        // The smallest key on top, to be the first to go. It grows as items
+        // come, as `k` is only an upper bound and can be far more than
+        // there are.
+        let mut chosen = BinaryHeap::new();
+        for (item, weight) in self {
+            assert!(
+                weight.is_finite() && weight >= 0.0,
+                "choose_weighted: {weight} is not a weight"
+            );
+            if weight == 0.0 {
+                continue;
+            }
+            let u = 1.0 - rng.gen::<f64>();
+            let key = u.ln() / weight;
+            if chosen.len() < k {
+                chosen.push(Reverse(Keyed { key, item }));
+            } else if chosen.peek().is_some_and(|Reverse(least)| key > least.key) {
+                chosen.pop();
+                chosen.push(Reverse(Keyed { key, item }));
+            }
+        }
+        chosen
+            .into_iter()
+            .map(|Reverse(keyed)| keyed.item)
+            .collect()
+    }
+}
+
+impl<I: Iterator> SampleExt for I {}
+
+#[cfg(test)]
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn choose_weighted_picks_by_weight() {
+    let mut rng = StdRng::seed_from_u64(1410);
+    let weights = [('a', 1.0), ('b', 2.0), ('c', 3.0), ('d', 4.0)];
+    let trials = 20_000;
+    let mut counts = [0usize; 4];
+    for _ in 0..trials {
+        let chosen = weights.into_iter().choose_weighted(1, &mut rng);
+        counts[(chosen[0] as u8 - b'a') as usize] += 1;
+    }
+    // Each is picked about weight / 10 of the time.
+    for (count, (_, weight)) in counts.iter().zip(weights) {
+        let share = *count as f64 / trials as f64;
+        assert!((share - weight / 10.0).abs() < 0.02, "{counts:?}");
+    }
+}
+
+#[test]
+fn choose_weighted_without_replacement() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let trials = 10_000;
+    // 'x' outweighs the rest together, so it is nearly always one of two.
+    let weights = [('x', 50.0), ('y', 1.0), ('z', 1.0), ('w', 1.0)];
+    let mut with_x = 0;
+    for _ in 0..trials {
+        let mut chosen = weights.into_iter().choose_weighted(2, &mut rng);
+        chosen.sort();
+        chosen.dedup();
+        assert_eq!(chosen.len(), 2);
+        with_x += chosen.contains(&'x') as usize;
+    }
+    assert!(with_x as f64 / trials as f64 > 0.98);
+}
+
+#[test]
+fn choose_weighted_with_few_or_weightless_items() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let items = [(1, 1.0), (2, 0.0), (3, 5.0)];
+
+    let mut all = items.into_iter().choose_weighted(10, &mut rng);
+    all.sort();
+    assert_eq!(all, [1, 3]);
+    // "As many as there are", without reserving room for them all up front.
+    let mut all = items.into_iter().choose_weighted(usize::MAX, &mut rng);
+    all.sort();
+    assert_eq!(all, [1, 3]);
+    assert!(items.into_iter().choose_weighted(0, &mut rng).is_empty());
+    assert!(std::iter::empty::<(u8, f64)>()
+        .choose_weighted(3, &mut rng)
+        .is_empty());
+
+    // Tiny weights still have keys to compare.
+    let tiny = [("a", 1e-300), ("b", 1e-300)];
+    assert_eq!(tiny.into_iter().choose_weighted(1, &mut rng).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "choose_weighted: -1 is not a weight")]
+fn choose_weighted_rejects_negative_weights() {
+    let mut rng = StdRng::seed_from_u64(0);
+    [(1, 1.0), (2, -1.0)]
+        .into_iter()
+        .choose_weighted(1, &mut rng);
+}
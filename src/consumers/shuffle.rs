@@ -0,0 +1,143 @@
+//! `shuffled(rng)` collects the items and puts them in a random order, with
+//! a Fisher-Yates shuffle. `partial_shuffle(k, rng)` is for when only the
+//! first `k` of that order are wanted: it also collects the items, but then
+//! draws them one at a time as they are asked for, so the work after
+//! collecting is in proportion to how many are taken, not to how many
+//! there are.
+//!
+//! Both take the random number generator from the caller, so a seeded one
+//! makes the order the same every time.
+
+use rand::Rng;
+use std::iter::FusedIterator;
+use std::vec;
+
+/// The first `k` items of a random order, drawn as they are asked for.
+#[derive(Debug, Clone)]
+pub struct PartialShuffle<T, R> {
+    // The items not drawn yet, in no particular order.
+    items: Vec<T>,
+    rng: R,
+    left: usize,
+}
+
+impl<T, R: Rng> Iterator for PartialShuffle<T, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.left == 0 || self.items.is_empty() {
+            return None;
+        }
+        self.left -= 1;
+        // Any of the items not drawn yet is as likely as any other, which
+        // is one step of Fisher-Yates; `swap_remove` keeps it O(1).
+        let index = self.rng.gen_range(0..self.items.len());
+        Some(self.items.swap_remove(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.left.min(self.items.len());
+        (len, Some(len))
+    }
+}
+
+impl<T, R: Rng> ExactSizeIterator for PartialShuffle<T, R> {}
+
+impl<T, R: Rng> FusedIterator for PartialShuffle<T, R> {}
+
+pub trait ShuffleExt: Iterator {
+    /// All the items, in a random order.
+    fn shuffled<R>(self, rng: &mut R) -> vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        R: Rng + ?Sized,
+    {
+        let mut items: Vec<_> = self.collect();
+        for i in (1..items.len()).rev() {
+            items.swap(i, rng.gen_range(0..=i));
+        }
+        items.into_iter()
+    }
+
+    /// The first `k` items of a random order (all of them, if there are
+    /// fewer than `k`).
+    fn partial_shuffle<R>(self, k: usize, rng: R) -> PartialShuffle<Self::Item, R>
+    where
+        Self: Sized,
+        R: Rng,
+    {
+        PartialShuffle {
+            items: self.collect(),
+            rng,
+            left: k,
+        }
+    }
+}
+
+impl<I: Iterator> ShuffleExt for I {}
+
+#[cfg(test)]
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn shuffled_is_a_seeded_permutation() {
+    let shuffle = |seed| {
+        (0..20)
+            .shuffled(&mut StdRng::seed_from_u64(seed))
+            .collect::<Vec<_>>()
+    };
+    let once = shuffle(1411);
+    assert_eq!(once, shuffle(1411));
+    assert_ne!(once, shuffle(1412));
+    assert_ne!(once, (0..20).collect::<Vec<_>>());
+
+    let mut sorted = once;
+    sorted.sort();
+    assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert_eq!(std::iter::empty::<u8>().shuffled(&mut rng).len(), 0);
+    assert_iter_eq!(std::iter::once('a').shuffled(&mut rng), ['a']);
+}
+
+#[test]
+fn shuffled_puts_each_item_anywhere() {
+    // Where the first of 4 items lands is about even over many shuffles.
+    let mut rng = StdRng::seed_from_u64(4);
+    let trials = 20_000;
+    let mut landed = [0usize; 4];
+    for _ in 0..trials {
+        let at = (0..4).shuffled(&mut rng).position(|n| n == 0).unwrap();
+        landed[at] += 1;
+    }
+    for count in landed {
+        assert!(
+            (count as f64 / trials as f64 - 0.25).abs() < 0.02,
+            "{landed:?}"
+        );
+    }
+}
+
+#[test]
+fn partial_shuffle_draws_k_distinct_items() {
+    let draw = |seed| {
+        (0..1000)
+            .partial_shuffle(5, StdRng::seed_from_u64(seed))
+            .collect::<Vec<_>>()
+    };
+    let drawn = draw(1411);
+    assert_eq!(drawn, draw(1411));
+    assert_eq!(drawn.len(), 5);
+    let mut distinct = drawn.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 5);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut it = (0..3).partial_shuffle(10, &mut rng);
+    assert_eq!(it.len(), 3);
+    it.next();
+    assert_eq!(it.len(), 2);
+    assert_eq!(it.count(), 2);
+    assert_eq!((0..3).partial_shuffle(0, &mut rng).next(), None);
+}
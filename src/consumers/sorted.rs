@@ -0,0 +1,123 @@
+//! `is_ordered()` checks that the items come in ascending order, and
+//! `is_partitioned_by(pred)` that every item matching `pred` comes before
+//! every item that doesn't. Each one looks at the items once and stops at
+//! the first that breaks the rule, which makes them cheap enough for
+//! `debug_assert!`s on the inputs of functions that need sorted data.
+//!
+//! The names are not `is_sorted` and `is_partitioned`, so as not to clash
+//! with `Iterator::is_sorted` (stable since Rust 1.82) and the unstable
+//! `Iterator::is_partitioned`; on toolchains without those, these are the
+//! only ones there are.
+
+use core::cmp::Ordering;
+
+// Like `Iterator::is_sorted`, these consume the iterator, whatever the
+// `is_` prefix suggests to clippy.
+#[allow(clippy::wrong_self_convention)]
+pub trait SortedExt: Iterator {
+    /// Whether each item is less than or equal to the next.
+    fn is_ordered(self) -> bool
+    where
+        Self: Sized,
+        Self::Item: PartialOrd,
+    {
+        // Incomparable items (such as NaN) count as out of order.
+        self.is_ordered_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Greater))
+    }
+
+    /// Whether `compare` finds no item `Greater` than the next.
+    fn is_ordered_by<F>(mut self, mut compare: F) -> bool
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        let Some(mut prev) = self.next() else {
+            return true;
+        };
+        for item in self {
+            if compare(&prev, &item) == Ordering::Greater {
+                return false;
+            }
+            prev = item;
+        }
+        true
+    }
+
+    /// Whether the items' keys are in ascending order.
+    fn is_ordered_by_key<K, F>(self, mut key: F) -> bool
+    where
+        Self: Sized,
+        K: PartialOrd,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.map(|item| key(&item)).is_ordered()
+    }
+
+    /// Whether all the items for which `pred` holds come before all those
+    /// for which it doesn't.
+    fn is_partitioned_by<P>(mut self, mut pred: P) -> bool
+    where
+        Self: Sized,
+        P: FnMut(Self::Item) -> bool,
+    {
+        // Skip the items that match, then make sure none of the rest do.
+        self.all(&mut pred) || !self.any(pred)
+    }
+}
+
+impl<I: Iterator> SortedExt for I {}
+
+// Counts how many items a consumer pulls, to show where it stopped.
+#[cfg(test)]
+fn counted<'a, I>(
+    iter: I,
+    pulled: &'a core::cell::Cell<usize>,
+) -> impl Iterator<Item = I::Item> + 'a
+where
+    I: Iterator + 'a,
+{
+    iter.inspect(move |_| pulled.set(pulled.get() + 1))
+}
+
+#[test]
+fn is_ordered_checks_ascending_order() {
+    assert!([1, 2, 2, 5].iter().is_ordered());
+    assert!(![1, 3, 2].iter().is_ordered());
+    assert!(core::iter::empty::<u8>().is_ordered());
+    assert!(core::iter::once(1).is_ordered());
+    assert!(![1.0, f64::NAN, 2.0].iter().is_ordered());
+
+    assert!([5, 3, 3, 0].iter().is_ordered_by(|a, b| b.cmp(a)));
+    assert!(["a", "bb", "cc", "ddd"]
+        .iter()
+        .is_ordered_by_key(|s| s.len()));
+    assert!(!["bb", "a"].iter().is_ordered_by_key(|s| s.len()));
+}
+
+#[test]
+fn is_ordered_stops_at_the_first_descent() {
+    let pulled = core::cell::Cell::new(0);
+    assert!(!counted([1, 2, 0, 3, 4, 5].into_iter(), &pulled).is_ordered());
+    assert_eq!(pulled.get(), 3);
+
+    // Even an endless iterator gives an answer, if it is out of order.
+    let pulled = core::cell::Cell::new(0);
+    assert!(!counted((0..).map(|n| n % 4), &pulled).is_ordered());
+    assert_eq!(pulled.get(), 5);
+}
+
+#[test]
+fn is_partitioned_by_checks_the_split() {
+    let is_even = |n: &i32| n % 2 == 0;
+    assert!([2, 4, 1, 3].iter().is_partitioned_by(is_even));
+    assert!([1, 3].iter().is_partitioned_by(is_even));
+    assert!([2, 4].iter().is_partitioned_by(is_even));
+    assert!(core::iter::empty::<&i32>().is_partitioned_by(is_even));
+    assert!(![2, 1, 4].iter().is_partitioned_by(is_even));
+
+    // It stops at the first match after a non-match.
+    let pulled = core::cell::Cell::new(0);
+    let answer = counted([0, 2, 1, 4, 5, 6].into_iter(), &pulled).is_partitioned_by(|n| n % 2 == 0);
+    assert!(!answer);
+    assert_eq!(pulled.get(), 4);
+}
@@ -0,0 +1,259 @@
+//! `Either<L, R>` holds one of two iterators with the same item type, and
+//! is an iterator itself. Each `impl Iterator` or adapter chain has a type
+//! of its own, so the two arms of an `if` can't otherwise return different
+//! ones without boxing them:
+//!
+//! ```
+//! use iterators::either::{Either, EitherExt};
+//!
+//! fn evens_or_all(v: &[u32], only_evens: bool) -> impl Iterator<Item = &u32> {
+//!     if only_evens {
+//!         v.iter().filter(|n| *n % 2 == 0).left()
+//!     } else {
+//!         v.iter().right()
+//!     }
+//! }
+//!
+//! assert_eq!(evens_or_all(&[1, 2, 3, 4], true).count(), 2);
+//! assert_eq!(evens_or_all(&[1, 2, 3, 4], false).count(), 4);
+//! ```
+//!
+//! `partition_map` goes the other way: a closure sorts each item into one
+//! of two collections by returning `Left` or `Right`.
+//!
+//! `EitherOrBoth<L, R>` adds a third case, both at once, for the results
+//! of joins such as `merge_join_by`.
+
+use core::iter::FusedIterator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    pub fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(self, Either::Right(_))
+    }
+}
+
+/// A value from the left, the right, or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EitherOrBoth<L, R> {
+    Left(L),
+    Right(R),
+    Both(L, R),
+}
+
+impl<L, R> EitherOrBoth<L, R> {
+    /// The left value, if there is one.
+    pub fn left(self) -> Option<L> {
+        match self {
+            EitherOrBoth::Left(l) | EitherOrBoth::Both(l, _) => Some(l),
+            EitherOrBoth::Right(_) => None,
+        }
+    }
+
+    /// The right value, if there is one.
+    pub fn right(self) -> Option<R> {
+        match self {
+            EitherOrBoth::Right(r) | EitherOrBoth::Both(_, r) => Some(r),
+            EitherOrBoth::Left(_) => None,
+        }
+    }
+
+    /// Both values, if there are both.
+    pub fn both(self) -> Option<(L, R)> {
+        match self {
+            EitherOrBoth::Both(l, r) => Some((l, r)),
+            _ => None,
+        }
+    }
+
+    /// Each side, if it is there.
+    pub fn into_options(self) -> (Option<L>, Option<R>) {
+        match self {
+            EitherOrBoth::Left(l) => (Some(l), None),
+            EitherOrBoth::Right(r) => (None, Some(r)),
+            EitherOrBoth::Both(l, r) => (Some(l), Some(r)),
+        }
+    }
+}
+
+// Every method calls the same one on whichever side is there.
+macro_rules! either {
+    ($value:expr, $side:pat => $result:expr) => {
+        match $value {
+            Either::Left($side) => $result,
+            Either::Right($side) => $result,
+        }
+    };
+}
+
+impl<L, R> Iterator for Either<L, R>
+where
+    L: Iterator,
+    R: Iterator<Item = L::Item>,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        either!(self, it => it.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        either!(self, it => it.size_hint())
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        either!(self, it => it.nth(n))
+    }
+
+    fn count(self) -> usize {
+        either!(self, it => it.count())
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        either!(self, it => it.last())
+    }
+
+    fn fold<Acc, G>(self, init: Acc, g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        either!(self, it => it.fold(init, g))
+    }
+}
+
+impl<L, R> DoubleEndedIterator for Either<L, R>
+where
+    L: DoubleEndedIterator,
+    R: DoubleEndedIterator<Item = L::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        either!(self, it => it.next_back())
+    }
+
+    fn rfold<Acc, G>(self, init: Acc, g: G) -> Acc
+    where
+        G: FnMut(Acc, Self::Item) -> Acc,
+    {
+        either!(self, it => it.rfold(init, g))
+    }
+}
+
+impl<L, R> ExactSizeIterator for Either<L, R>
+where
+    L: ExactSizeIterator,
+    R: ExactSizeIterator<Item = L::Item>,
+{
+}
+
+impl<L, R> FusedIterator for Either<L, R>
+where
+    L: FusedIterator,
+    R: FusedIterator<Item = L::Item>,
+{
+}
+
+pub trait EitherExt: Iterator {
+    /// This iterator as the left side of an `Either`.
+    fn left<R>(self) -> Either<Self, R>
+    where
+        Self: Sized,
+    {
+        Either::Left(self)
+    }
+
+    /// This iterator as the right side of an `Either`.
+    fn right<L>(self) -> Either<L, Self>
+    where
+        Self: Sized,
+    {
+        Either::Right(self)
+    }
+
+    /// Collects the `Left`s that `f` returns into one collection and the
+    /// `Right`s into another.
+    fn partition_map<A, B, F, L, R>(self, mut f: F) -> (A, B)
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> Either<L, R>,
+        A: Default + Extend<L>,
+        B: Default + Extend<R>,
+    {
+        let (mut lefts, mut rights) = (A::default(), B::default());
+        self.for_each(|item| match f(item) {
+            Either::Left(l) => lefts.extend(Some(l)),
+            Either::Right(r) => rights.extend(Some(r)),
+        });
+        (lefts, rights)
+    }
+}
+
+impl<I: Iterator> EitherExt for I {}
+
+#[cfg(test)]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(test)]
+fn countdown(
+    n: u32,
+    backwards: bool,
+) -> Either<core::iter::Rev<core::ops::Range<u32>>, core::ops::Range<u32>> {
+    if backwards {
+        (0..n).rev().left()
+    } else {
+        (0..n).right()
+    }
+}
+
+#[test]
+fn either_iterates_whichever_side_it_has() {
+    assert_iter_eq!(countdown(3, true), [2, 1, 0]);
+    assert_iter_eq!(countdown(3, false), [0, 1, 2]);
+    assert!(countdown(3, true).is_left() && countdown(3, false).is_right());
+
+    let mut it = countdown(5, false);
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.next_back(), Some(4));
+    assert_eq!(it.nth(1), Some(1));
+    assert_eq!(it.size_hint(), (2, Some(2)));
+    assert_eq!(it.clone().last(), Some(3));
+    assert_eq!(it.rfold(0, |acc, n| acc * 10 + n), 32);
+}
+
+#[test]
+fn either_sides_can_be_different_adapters() {
+    let words = ["apple", "kiwi", "banana"];
+    let pick = |long: bool| {
+        if long {
+            words.iter().filter(|w| w.len() > 4).copied().left()
+        } else {
+            words.iter().copied().right()
+        }
+    };
+    assert_eq!(pick(true).collect::<Vec<_>>(), ["apple", "banana"]);
+    assert_eq!(pick(false).count(), 3);
+}
+
+#[test]
+fn partition_map_splits_by_side() {
+    let (numbers, words): (Vec<i32>, Vec<String>) = ["1", "two", "3", "four"]
+        .into_iter()
+        .partition_map(|s| match s.parse::<i32>() {
+            Ok(n) => Either::Left(n),
+            Err(_) => Either::Right(String::from(s)),
+        });
+    assert_eq!(numbers, [1, 3]);
+    assert_eq!(words, ["two", "four"]);
+
+    let (none, all): (Vec<u8>, Vec<u8>) = (0..3).partition_map(Either::<u8, u8>::Right);
+    assert!(none.is_empty());
+    assert_eq!(all, [0, 1, 2]);
+}
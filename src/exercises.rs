@@ -0,0 +1,63 @@
+//! The lessons' exercises, each a small gap to fill in. Every exercise
+//! compiles as it is, with `unimplemented!()` where the answer goes, and
+//! a `stub;` at the top of its tests says so: while it is there, the tests
+//! expect the stub's panic.
+//!
+//! To do an exercise, replace its `unimplemented!()` with an answer and
+//! delete the `stub;`, so that the tests check the answer instead:
+//!
+//! ```text
+//! cargo test exercises                        # your code
+//! cargo test exercises --features solutions   # yours, and the answers
+//! ```
+//!
+//! The answers are in a `solution` module next to each stub, compiled only
+//! with the `solutions` feature, which runs the same tests against them as
+//! well. It never changes the code you write.
+
+/// Tests for an exercise, run on the code in the exercise's module and,
+/// with the `solutions` feature, on its `solution` module too. Starting
+/// with `stub;`, the tests on the exercise's own code have to hit the
+/// stub's `unimplemented!()`.
+macro_rules! exercise_tests {
+    (@solution $($(#[$meta:meta])* fn $name:ident() $body:block)*) => {
+        #[cfg(feature = "solutions")]
+        mod solution_tests {
+            #[allow(unused_imports)]
+            use super::solution::*;
+
+            $(
+                #[test]
+                $(#[$meta])*
+                fn $name() $body
+            )*
+        }
+    };
+    (stub; $($(#[$meta:meta])* fn $name:ident() $body:block)*) => {
+        $(
+            #[test]
+            #[should_panic(expected = "not implemented")]
+            $(#[$meta])*
+            fn $name() $body
+        )*
+
+        exercise_tests!(@solution $($(#[$meta])* fn $name() $body)*);
+    };
+    ($($(#[$meta:meta])* fn $name:ident() $body:block)*) => {
+        $(
+            #[test]
+            $(#[$meta])*
+            fn $name() $body
+        )*
+
+        exercise_tests!(@solution $($(#[$meta])* fn $name() $body)*);
+    };
+}
+
+mod counter;
+mod flatten;
+mod into_counter;
+mod into_iterator;
+mod map;
+mod mapping;
+mod unique;
@@ -0,0 +1,237 @@
+//! From `i5_custom_iterators`: implement `Iterator` for `Counter`, which
+//! counts from 0 up to, but not including, `max`.
+//!
+//! `next` is the only method an iterator has to have, but `nth`, `count`
+//! and `last` are here too: by default they call `next()` once per item,
+//! while a counter knows where it is and can answer each in constant
+//! time. `size_hint` can say exactly how many items are left.
+
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct Counter {
+    max: i32,
+    // `count` tracks the state of this iterator.
+    count: i32,
+}
+
+impl Counter {
+    pub fn new(max: i32) -> Counter {
+        Counter { count: -1, max }
+    }
+}
+
+impl Iterator for Counter {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unimplemented!()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        unimplemented!()
+    }
+
+    fn count(self) -> usize {
+        unimplemented!()
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+}
+
+// Once `next()` returns `None`, it has to keep returning `None`.
+impl FusedIterator for Counter {}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use std::iter::FusedIterator;
+
+    // How many times `next()` has run on this thread, so the tests can
+    // check that the fast paths don't lean on it.
+    #[cfg(test)]
+    thread_local! {
+        pub(super) static NEXT_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Counter {
+        pub(super) max: i32,
+        pub(super) count: i32,
+    }
+
+    impl Counter {
+        pub fn new(max: i32) -> Counter {
+            Counter { count: -1, max }
+        }
+    }
+
+    impl Iterator for Counter {
+        type Item = i32;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            #[cfg(test)]
+            NEXT_CALLS.set(NEXT_CALLS.get() + 1);
+
+            // Check before incrementing: bumping `count` on every call past
+            // the end would eventually overflow and start yielding again.
+            if self.count + 1 >= self.max {
+                return None;
+            }
+            self.count += 1;
+            Some(self.count)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let remaining = self.remaining();
+            (remaining, Some(remaining))
+        }
+
+        // The default `nth`, `count` and `last` call `next()` once per item.
+        // A counter knows exactly where it is, so each can be answered in
+        // constant time.
+        fn nth(&mut self, n: usize) -> Option<Self::Item> {
+            if n >= self.remaining() {
+                // Park `count` at the end, exactly where `next()` leaves it.
+                // `max` can be as low as `i32::MIN`, hence the saturation; `last`
+                // only subtracts once it knows there is an item left.
+                self.count = self.count.max(self.max.saturating_sub(1));
+                return None;
+            }
+            self.count += n as i32 + 1;
+            Some(self.count)
+        }
+
+        fn count(self) -> usize {
+            self.remaining()
+        }
+
+        fn last(self) -> Option<Self::Item> {
+            (self.remaining() > 0).then(|| self.max - 1)
+        }
+    }
+
+    impl Counter {
+        fn remaining(&self) -> usize {
+            (self.max as i64 - self.count as i64 - 1).max(0) as usize
+        }
+    }
+
+    // Once `next()` returns `None`, `count` stays put, so it keeps
+    // returning `None`.
+    impl FusedIterator for Counter {}
+}
+
+exercise_tests! {
+    stub;
+
+    fn counter_exercise() {
+        let counter = Counter::new(10);
+        for i in counter {
+            println!("{i}");
+        }
+    }
+
+    fn counter_yields_zero_to_max() {
+        let counter = Counter::new(5);
+        assert_eq!(counter.collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+
+        assert_eq!(Counter::new(0).next(), None);
+    }
+
+    fn counter_clone() {
+        let mut counter = Counter::new(4);
+        counter.next();
+
+        let copy = counter.clone();
+        assert_eq!(counter.next(), Some(1));
+        assert_eq!(copy.collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(counter.collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(
+            format!("{:?}", Counter::new(4)),
+            "Counter { max: 4, count: -1 }"
+        );
+    }
+
+    fn counter_fast_paths_match_next() {
+        // `from_fn` only knows `next()`, so it uses the default, linear
+        // implementations of `nth`, `count` and `last`.
+        fn slow(mut counter: Counter) -> impl Iterator<Item = i32> {
+            std::iter::from_fn(move || counter.next())
+        }
+
+        for max in [0, 1, 5] {
+            for n in 0..7 {
+                let mut fast = Counter::new(max);
+                let mut linear = slow(Counter::new(max));
+                assert_eq!(fast.nth(n), linear.nth(n), "max = {max}, n = {n}");
+                assert_eq!(fast.next(), linear.next(), "max = {max}, n = {n}");
+            }
+            assert_eq!(Counter::new(max).count(), slow(Counter::new(max)).count());
+            assert_eq!(Counter::new(max).last(), slow(Counter::new(max)).last());
+        }
+    }
+
+    fn counter_fast_paths_do_no_per_item_work() {
+        let counter = Counter::new(i32::MAX);
+        assert_eq!(counter.clone().count(), i32::MAX as usize);
+        assert_eq!(counter.clone().last(), Some(i32::MAX - 1));
+
+        let mut counter = counter;
+        assert_eq!(counter.nth(2_000_000_000), Some(2_000_000_000));
+        assert_eq!(counter.nth(usize::MAX), None);
+        assert_eq!(counter.next(), None);
+    }
+
+    fn counter_stays_exhausted() {
+        let mut counter = Counter::new(2);
+        assert_eq!(counter.by_ref().count(), 2);
+
+        assert_eq!(counter.next(), None);
+        assert_eq!(counter.next(), None);
+        assert_eq!(counter.count, 1);
+    }
+
+    fn counter_min_i32_does_not_overflow() {
+        let mut counter = Counter::new(i32::MIN);
+        assert_eq!(counter.nth(1), None);
+        assert_eq!(counter.next(), None);
+        assert_eq!(counter.clone().count(), 0);
+        assert_eq!(counter.last(), None);
+    }
+
+    fn counter_max_i32_does_not_overflow() {
+        let mut counter = Counter {
+            count: i32::MAX - 2,
+            max: i32::MAX,
+        };
+        assert_eq!(counter.next(), Some(i32::MAX - 1));
+        assert_eq!(counter.next(), None);
+        assert_eq!(counter.next(), None);
+    }
+}
+
+#[cfg(feature = "solutions")]
+#[test]
+fn counter_fast_paths_do_not_call_next() {
+    use solution::{Counter, NEXT_CALLS};
+
+    let mut counter = Counter::new(i32::MAX);
+    assert_eq!(
+        counter.size_hint(),
+        (i32::MAX as usize, Some(i32::MAX as usize))
+    );
+    assert_eq!(counter.clone().count(), i32::MAX as usize);
+    assert_eq!(counter.clone().last(), Some(i32::MAX - 1));
+    assert_eq!(counter.nth(2_000_000_000), Some(2_000_000_000));
+    assert_eq!(counter.nth(usize::MAX), None);
+    assert_eq!(NEXT_CALLS.get(), 0);
+
+    assert_eq!(counter.next(), None);
+    assert_eq!(NEXT_CALLS.get(), 1);
+}
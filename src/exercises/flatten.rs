@@ -0,0 +1,122 @@
+//! From `i6_iterator_adapters`: write a `Flatten` adapter, following the
+//! same steps as `Map` there. The struct, the extension trait and the
+//! blanket implementation are done; `next` is left.
+//!
+//! `orig` yields iterables. `inner` is the iterator over the one being
+//! worked through, if any: take items from it until it runs out, then move
+//! on to the next one from `orig`.
+
+// Step 1: Define a struct for the custom adapter.
+pub struct Flatten<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    orig: I,
+    inner: Option<<I::Item as IntoIterator>::IntoIter>,
+}
+
+// Step 2: Implement Iterator for the custom adapter.
+impl<I> Iterator for Flatten<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+}
+
+// Step 3: Define a new extension trait with the new operator to be
+//         added, as a sub-trait of Iterator.
+pub trait FlattenExt: Iterator {
+    // Named so as not to be mistaken for `Iterator::flatten`.
+    fn my_flatten(self) -> Flatten<Self>
+    where
+        Self: Sized,
+        Self::Item: IntoIterator;
+}
+
+// Step 4: Implement the trait for all types that implement Iterator.
+impl<I: Iterator> FlattenExt for I {
+    fn my_flatten(self) -> Flatten<Self>
+    where
+        Self::Item: IntoIterator,
+    {
+        Flatten {
+            orig: self,
+            inner: None,
+        }
+    }
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    pub struct Flatten<I>
+    where
+        I: Iterator,
+        I::Item: IntoIterator,
+    {
+        orig: I,
+        inner: Option<<I::Item as IntoIterator>::IntoIter>,
+    }
+
+    impl<I> Iterator for Flatten<I>
+    where
+        I: Iterator,
+        I::Item: IntoIterator,
+    {
+        type Item = <I::Item as IntoIterator>::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                if let Some(item) = self.inner.as_mut().and_then(Iterator::next) {
+                    return Some(item);
+                }
+                self.inner = Some(self.orig.next()?.into_iter());
+            }
+        }
+    }
+
+    pub trait FlattenExt: Iterator {
+        fn my_flatten(self) -> Flatten<Self>
+        where
+            Self: Sized,
+            Self::Item: IntoIterator;
+    }
+
+    impl<I: Iterator> FlattenExt for I {
+        fn my_flatten(self) -> Flatten<Self>
+        where
+            Self::Item: IntoIterator,
+        {
+            Flatten {
+                orig: self,
+                inner: None,
+            }
+        }
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn flatten_exercise() {
+        let vs = vec![vec![1, 2], vec![3, 4]];
+
+        let result: Vec<_> = vs.into_iter().my_flatten().collect();
+
+        assert_eq!(result, [1, 2, 3, 4]);
+    }
+
+    fn flatten_skips_empty_inner_iterators() {
+        let vs = vec![vec![], vec![1], vec![], vec![], vec![2, 3], vec![]];
+
+        let result: Vec<i32> = vs.into_iter().my_flatten().collect();
+
+        assert_eq!(result, [1, 2, 3]);
+        assert_eq!(Vec::<Vec<u8>>::new().into_iter().my_flatten().next(), None);
+    }
+}
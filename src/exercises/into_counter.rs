@@ -0,0 +1,134 @@
+//! From `i5_custom_iterators`: implement `IntoIterator` for `Counter`,
+//! which isn't an iterator itself but hands out one over `0..max`.
+//!
+//! Like `Vec<T>`, `&Vec<T>` and `&mut Vec<T>`, a `Counter` can also be
+//! iterated through a reference, which leaves it usable afterwards, so
+//! `&Counter` and `&mut Counter` get an implementation each too.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Counter {
+    max: i32,
+    // No need to track the state, because this isn't an iterator.
+}
+
+impl Counter {
+    pub fn new(max: i32) -> Counter {
+        Counter { max }
+    }
+}
+
+impl IntoIterator for Counter {
+    type Item = i32;
+    type IntoIter = Range<i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        unimplemented!()
+    }
+}
+
+impl IntoIterator for &Counter {
+    type Item = i32;
+    type IntoIter = Range<i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        unimplemented!()
+    }
+}
+
+impl IntoIterator for &mut Counter {
+    type Item = i32;
+    type IntoIter = Range<i32>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        unimplemented!()
+    }
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use std::ops::Range;
+
+    #[derive(Debug, Clone)]
+    pub struct Counter {
+        pub(super) max: i32,
+    }
+
+    impl Counter {
+        pub fn new(max: i32) -> Counter {
+            Counter { max }
+        }
+    }
+
+    impl IntoIterator for Counter {
+        type Item = i32;
+        type IntoIter = Range<i32>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            0..self.max
+        }
+    }
+
+    // `Counter` doesn't store its values, so there is nothing to hand out
+    // by reference: every form yields plain `i32`s.
+    impl IntoIterator for &Counter {
+        type Item = i32;
+        type IntoIter = Range<i32>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            0..self.max
+        }
+    }
+
+    impl IntoIterator for &mut Counter {
+        type Item = i32;
+        type IntoIter = Range<i32>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            0..self.max
+        }
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn into_counter_exercise() {
+        let counter = Counter::new(10);
+        for i in counter {
+            println!("{i}");
+        }
+    }
+
+    fn into_counter_by_reference() {
+        let counter = Counter::new(3);
+
+        let mut seen = Vec::new();
+        for i in &counter {
+            seen.push(i);
+        }
+        // `counter` wasn't moved, so it can be iterated again.
+        for i in &counter {
+            seen.push(i * 10);
+        }
+
+        assert_eq!(seen, [0, 1, 2, 0, 10, 20]);
+    }
+
+    fn into_counter_by_mutable_reference() {
+        let mut counter = Counter::new(3);
+
+        let mut sum = 0;
+        for i in &mut counter {
+            sum += i;
+        }
+        counter.max = 5;
+
+        assert_eq!(sum, 3);
+        assert_eq!(
+            (&mut counter).into_iter().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! From `i5_custom_iterators`: implement `IntoIterator` for
+//! `PasswordGenerator`, so that it can go straight into a `for` loop.
+//!
+//! A `PasswordGenerator` only holds the settings; the passwords come from
+//! `Passwords`, which is already written. What's missing is the step from
+//! one to the other.
+
+use rand::rngs::ThreadRng;
+use rand::Rng;
+
+#[derive(Debug, Clone)]
+pub struct PasswordGenerator {
+    length: usize,
+}
+
+impl PasswordGenerator {
+    pub fn new(length: usize) -> Self {
+        Self { length }
+    }
+}
+
+/// Endless passwords of `length` lowercase letters.
+#[derive(Debug, Clone)]
+pub struct Passwords {
+    length: usize,
+    rng: ThreadRng,
+}
+
+impl Iterator for Passwords {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let password = (0..self.length)
+            .map(|_| self.rng.gen_range(b'a'..=b'z') as char)
+            .collect();
+        Some(password)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl IntoIterator for PasswordGenerator {
+    type Item = String;
+    type IntoIter = Passwords;
+
+    fn into_iter(self) -> Self::IntoIter {
+        unimplemented!()
+    }
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use super::Passwords;
+
+    /// The same settings as the exercise's, for an `IntoIterator` of its own.
+    #[derive(Debug, Clone)]
+    pub struct PasswordGenerator(super::PasswordGenerator);
+
+    impl PasswordGenerator {
+        pub fn new(length: usize) -> Self {
+            Self(super::PasswordGenerator::new(length))
+        }
+    }
+
+    impl IntoIterator for PasswordGenerator {
+        type Item = String;
+        type IntoIter = Passwords;
+
+        fn into_iter(self) -> Self::IntoIter {
+            Passwords {
+                length: self.0.length,
+                rng: rand::thread_rng(),
+            }
+        }
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn into_iterator_exercise() {
+        let gen = PasswordGenerator::new(10);
+
+        let mut count = 0;
+        for password in gen.into_iter().take(10) {
+            assert_eq!(password.len(), 10);
+            assert!(password.bytes().all(|b| b.is_ascii_lowercase()));
+            count += 1;
+        }
+        assert_eq!(count, 10);
+    }
+
+    fn into_iterator_in_a_for_loop() {
+        // `for` calls `into_iter` itself.
+        let mut passwords = Vec::new();
+        for password in PasswordGenerator::new(4) {
+            passwords.push(password);
+            if passwords.len() == 3 {
+                break;
+            }
+        }
+        assert!(passwords.iter().all(|p| p.len() == 4));
+    }
+}
@@ -0,0 +1,263 @@
+//! From `i6_iterator_adapters`: write the `Map` adapter there, following
+//! its four steps. The struct and the extension trait are done; the
+//! `Iterator` impl is left, with `next` and a `size_hint` that says as much
+//! as the original iterator's does, and `next_back` so that it can run
+//! backwards too.
+//!
+//! One test also wants `fold` forwarded to the original iterator, since
+//! `sum`, `for_each` and `collect` are built on it; see
+//! `i6_iterator_adapters::fold_forwarding`.
+
+use std::fmt;
+use std::iter::FusedIterator;
+
+// Step 1: Define a struct for the custom adapter.
+#[derive(Clone)]
+pub struct Map<I, F> {
+    orig: I,
+    f: F,
+}
+
+// Closures don't implement `Debug`, so `f` is left out.
+impl<I: fmt::Debug, F> fmt::Debug for Map<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("orig", &self.orig)
+            .finish_non_exhaustive()
+    }
+}
+
+// Step 2: Implement `Iterator` for the custom adapter.
+impl<I, F, B> Iterator for Map<I, F>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unimplemented!()
+    }
+}
+
+impl<I, F, B> DoubleEndedIterator for Map<I, F>
+where
+    I: DoubleEndedIterator,
+    F: FnMut(I::Item) -> B,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+}
+
+impl<I, F, B> ExactSizeIterator for Map<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(I::Item) -> B,
+{
+}
+
+impl<I, F, B> FusedIterator for Map<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(I::Item) -> B,
+{
+}
+
+// Step 3: Define a new extension trait with the new operator to be
+//         added, as a sub-trait of `Iterator`.
+//
+// The operator is called `fmap` so it doesn't collide with `Iterator::map`.
+pub trait MapExt: Iterator {
+    fn fmap<B, F>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item) -> B;
+}
+
+// Step 4: Implement the trait for all types that implement `Iterator`.
+impl<I: Iterator> MapExt for I {
+    fn fmap<B, F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnMut(Self::Item) -> B,
+    {
+        Map { orig: self, f }
+    }
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use std::fmt;
+    use std::iter::FusedIterator;
+
+    #[derive(Clone)]
+    pub struct Map<I, F> {
+        orig: I,
+        f: F,
+    }
+
+    impl<I: fmt::Debug, F> fmt::Debug for Map<I, F> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Map")
+                .field("orig", &self.orig)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl<I, F, B> Iterator for Map<I, F>
+    where
+        I: Iterator,
+        F: FnMut(I::Item) -> B,
+    {
+        type Item = B;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.orig.next().map(&mut self.f)
+        }
+
+        // `map` yields exactly one item per input item, so the hint of the
+        // original iterator is also ours.
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.orig.size_hint()
+        }
+
+        // Consumers such as `sum`, `for_each` and `collect` are built on
+        // `fold`. Forwarding it lets the original iterator run its own
+        // (often much tighter) loop instead of being driven by `next()`.
+        fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+        where
+            G: FnMut(Acc, Self::Item) -> Acc,
+        {
+            let mut f = self.f;
+            self.orig.fold(init, move |acc, item| g(acc, f(item)))
+        }
+
+        // `try_fold` would deserve the same treatment, but its signature
+        // mentions the unstable `Try` trait, so it can't be overridden on
+        // stable Rust. Short-circuiting consumers (`find`, `any`, ...) fall
+        // back to `next()`.
+    }
+
+    // Like std's `map`, our `Map` can run backwards whenever the original
+    // iterator can, and knows its exact length whenever the original does.
+    impl<I, F, B> DoubleEndedIterator for Map<I, F>
+    where
+        I: DoubleEndedIterator,
+        F: FnMut(I::Item) -> B,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.orig.next_back().map(&mut self.f)
+        }
+    }
+
+    impl<I, F, B> ExactSizeIterator for Map<I, F>
+    where
+        I: ExactSizeIterator,
+        F: FnMut(I::Item) -> B,
+    {
+    }
+
+    impl<I, F, B> FusedIterator for Map<I, F>
+    where
+        I: FusedIterator,
+        F: FnMut(I::Item) -> B,
+    {
+    }
+
+    pub trait MapExt: Iterator {
+        fn fmap<B, F>(self, f: F) -> Map<Self, F>
+        where
+            Self: Sized,
+            F: FnMut(Self::Item) -> B;
+    }
+
+    impl<I: Iterator> MapExt for I {
+        fn fmap<B, F>(self, f: F) -> Map<Self, F>
+        where
+            F: FnMut(Self::Item) -> B,
+        {
+            Map { orig: self, f }
+        }
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn map_exercise() {
+        let vs = vec![1, 2, 3, 4, 5];
+
+        let result: Vec<_> = vs.into_iter().fmap(|x| x * 2).collect();
+
+        assert_eq!(result, [2, 4, 6, 8, 10]);
+    }
+
+    fn map_clone_and_debug() {
+        let mut mapped = (1..5).fmap(|x| x * 10);
+        mapped.next();
+
+        let copy = mapped.clone();
+        assert_eq!(mapped.next(), Some(20));
+        assert_eq!(copy.collect::<Vec<_>>(), [20, 30, 40]);
+        assert_eq!(mapped.collect::<Vec<_>>(), [30, 40]);
+
+        let mapped = (1..5).fmap(|x| x * 10);
+        assert_eq!(format!("{mapped:?}"), "Map { orig: 1..5, .. }");
+    }
+
+    fn map_stays_exhausted() {
+        let mut mapped = [1].into_iter().fmap(|x| x + 1);
+
+        assert_eq!(mapped.next(), Some(2));
+        assert_eq!(mapped.next(), None);
+        assert_eq!(mapped.next(), None);
+    }
+
+    fn map_rev() {
+        let vs = [
+            String::from("red"),
+            String::from("green"),
+            String::from("blue"),
+        ];
+
+        let rs: Vec<String> = vs.iter().fmap(|x| x.to_ascii_uppercase()).rev().collect();
+
+        assert_eq!(rs, ["BLUE", "GREEN", "RED"]);
+    }
+
+    fn map_from_both_ends() {
+        let mut mapped = (1..6).fmap(|x| x * 10);
+
+        assert_eq!(mapped.len(), 5);
+        assert_eq!(mapped.next(), Some(10));
+        assert_eq!(mapped.next_back(), Some(50));
+        assert_eq!(mapped.len(), 3);
+        assert_eq!(mapped.rev().collect::<Vec<_>>(), [40, 30, 20]);
+    }
+
+    fn map_size_hint() {
+        let mapped = [1, 2, 3].into_iter().fmap(|x| x * 2);
+        assert_eq!(mapped.size_hint(), (3, Some(3)));
+
+        let mapped = (0..10).filter(|x| x % 2 == 0).fmap(|x| x * 2);
+        assert_eq!(mapped.size_hint(), (0, Some(10)));
+
+        let mapped = (0..).fmap(|x: u64| x * 2);
+        assert_eq!(mapped.size_hint(), (usize::MAX, None));
+    }
+
+    fn map_forwards_fold() {
+        use crate::i6_iterator_adapters::fold_forwarding::source;
+
+        let (src, calls) = source(vec![1, 2, 3]);
+
+        let sum: i32 = src.fmap(|x| x * 10).sum();
+
+        assert_eq!(sum, 60);
+        assert_eq!((calls.next.get(), calls.fold.get()), (0, 1));
+    }
+}
@@ -0,0 +1,38 @@
+//! From `i2_std_iterators`: this used to be a test that didn't compile.
+//!
+//! ```ignore
+//! let players = player_scores
+//!     .map(|(player, _score)| player)
+//!     .collect::<Vec<_>>();
+//! ```
+//!
+//! An array has a `map` of its own, which makes another array rather than
+//! an iterator, so there's nothing to `collect`. Make the names into a
+//! `Vec` with an iterator instead.
+
+/// The players' names, in the same order.
+pub fn player_names(player_scores: [(&'static str, u32); 4]) -> Vec<&'static str> {
+    unimplemented!()
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    pub fn player_names(player_scores: [(&'static str, u32); 4]) -> Vec<&'static str> {
+        player_scores
+            .into_iter()
+            .map(|(player, _score)| player)
+            .collect()
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn mapping_exercise() {
+        let player_scores = [("Jack", 20), ("Jane", 23), ("Jill", 18), ("John", 19)];
+
+        let players = player_names(player_scores);
+
+        assert_eq!(players, ["Jack", "Jane", "Jill", "John"]);
+    }
+}
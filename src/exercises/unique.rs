@@ -0,0 +1,254 @@
+//! From `i6_iterator_adapters`: write the `Unique` adapter there, which
+//! yields each item the first time it turns up and skips it after that.
+//! The struct and the extension trait are done; `next` and `size_hint`
+//! are left. Remember that any of the remaining items could be one that
+//! has been seen already.
+//!
+//! As with `Map`, one test also wants `fold` forwarded to the original
+//! iterator.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+
+// Step 1: Define a struct for the custom adapter.
+pub struct Unique<I>
+where
+    I: Iterator,
+{
+    orig: I,
+    seen: HashSet<I::Item>,
+}
+
+// `#[derive]` would only require `I: Clone`, but cloning `seen` also
+// needs `I::Item: Clone`, so these impls are written by hand.
+impl<I> Clone for Unique<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Unique {
+            orig: self.orig.clone(),
+            seen: self.seen.clone(),
+        }
+    }
+}
+
+impl<I> fmt::Debug for Unique<I>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unique")
+            .field("orig", &self.orig)
+            .field("seen", &self.seen)
+            .finish()
+    }
+}
+
+// Step 2: Implement `Iterator` for the custom adapter.
+impl<I> Iterator for Unique<I>
+where
+    I: Iterator,
+    I::Item: Hash + Eq + Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unimplemented!()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unimplemented!()
+    }
+}
+
+impl<I> FusedIterator for Unique<I>
+where
+    I: FusedIterator,
+    I::Item: Hash + Eq + Clone,
+{
+}
+
+// Step 3: Define a new extension trait with the new operator to be
+//         added, as a sub-trait of Iterator.
+pub trait UniqueExt: Iterator {
+    fn unique(self) -> Unique<Self>
+    where
+        Self: Sized,
+        Self::Item: Hash + Eq + Clone;
+}
+
+// Step 4: Implement the trait for all types that implement `Iterator`.
+impl<I: Iterator> UniqueExt for I {
+    fn unique(self) -> Unique<Self>
+    where
+        Self::Item: Hash + Eq + Clone,
+    {
+        Unique {
+            orig: self,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+#[cfg(feature = "solutions")]
+mod solution {
+    use std::collections::HashSet;
+    use std::fmt;
+    use std::hash::Hash;
+    use std::iter::FusedIterator;
+
+    pub struct Unique<I>
+    where
+        I: Iterator,
+    {
+        orig: I,
+        seen: HashSet<I::Item>,
+    }
+
+    impl<I> Clone for Unique<I>
+    where
+        I: Iterator + Clone,
+        I::Item: Clone,
+    {
+        fn clone(&self) -> Self {
+            Unique {
+                orig: self.orig.clone(),
+                seen: self.seen.clone(),
+            }
+        }
+    }
+
+    impl<I> fmt::Debug for Unique<I>
+    where
+        I: Iterator + fmt::Debug,
+        I::Item: fmt::Debug,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Unique")
+                .field("orig", &self.orig)
+                .field("seen", &self.seen)
+                .finish()
+        }
+    }
+
+    impl<I> Iterator for Unique<I>
+    where
+        I: Iterator,
+        I::Item: Hash + Eq + Clone,
+    {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.orig
+                .by_ref()
+                .find(|item| self.seen.insert(item.clone()))
+        }
+
+        // Every remaining item may be a duplicate, so only the upper bound
+        // carries over from the original iterator.
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (0, self.orig.size_hint().1)
+        }
+
+        fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+        where
+            G: FnMut(Acc, Self::Item) -> Acc,
+        {
+            let mut seen = self.seen;
+            self.orig.fold(init, move |acc, item| {
+                if seen.insert(item.clone()) {
+                    g(acc, item)
+                } else {
+                    acc
+                }
+            })
+        }
+    }
+
+    impl<I> FusedIterator for Unique<I>
+    where
+        I: FusedIterator,
+        I::Item: Hash + Eq + Clone,
+    {
+    }
+
+    pub trait UniqueExt: Iterator {
+        fn unique(self) -> Unique<Self>
+        where
+            Self: Sized,
+            Self::Item: Hash + Eq + Clone;
+    }
+
+    impl<I: Iterator> UniqueExt for I {
+        fn unique(self) -> Unique<Self>
+        where
+            Self::Item: Hash + Eq + Clone,
+        {
+            Unique {
+                orig: self,
+                seen: HashSet::new(),
+            }
+        }
+    }
+}
+
+exercise_tests! {
+    stub;
+
+    fn unique_exercise() {
+        let vs = vec!["a", "b", "a", "cc", "cc", "d"];
+
+        let result: Vec<_> = vs.into_iter().unique().collect();
+
+        assert_eq!(result, ["a", "b", "cc", "d"]);
+    }
+
+    fn unique_clone_and_debug() {
+        let mut unique = vec![1, 2, 1, 3, 2, 4].into_iter().unique();
+        assert_eq!(unique.next(), Some(1));
+        assert_eq!(unique.next(), Some(2));
+
+        // The copy remembers what has been seen so far.
+        let copy = unique.clone();
+        assert_eq!(copy.collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(unique.collect::<Vec<_>>(), [3, 4]);
+
+        let unique = [7].into_iter().unique();
+        assert_eq!(
+            format!("{unique:?}"),
+            "Unique { orig: IntoIter([7]), seen: {} }"
+        );
+    }
+
+    fn unique_stays_exhausted() {
+        let mut unique = ["a", "a"].into_iter().unique();
+
+        assert_eq!(unique.next(), Some("a"));
+        assert_eq!(unique.next(), None);
+        assert_eq!(unique.next(), None);
+    }
+
+    fn unique_size_hint() {
+        let mut unique = vec!["a", "a", "b"].into_iter().unique();
+        assert_eq!(unique.size_hint(), (0, Some(3)));
+
+        unique.next();
+        assert_eq!(unique.size_hint(), (0, Some(2)));
+
+        assert_eq!((0..).unique().size_hint(), (0, None));
+    }
+
+    fn unique_forwards_fold() {
+        use crate::i6_iterator_adapters::fold_forwarding::source;
+
+        let (src, calls) = source(vec![1, 1, 2, 1, 3]);
+
+        assert_eq!(src.unique().count(), 3);
+        assert_eq!((calls.next.get(), calls.fold.get()), (0, 1));
+    }
+}
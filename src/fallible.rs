@@ -0,0 +1,286 @@
+//! Iterators whose every step can fail, in the style of the
+//! `fallible-iterator` crate.
+//!
+//! An `Iterator<Item = Result<T, E>>` can fail too, but nothing stops it
+//! from going on after an error, and each adapter has to unwrap the `Ok`
+//! side by hand. A `TryIterator` puts the error in the signature of
+//! `try_next` instead: `Ok(Some(x))` is an item, `Ok(None)` is the end, and
+//! `Err(e)` is a failure, after which the iterator is not used again. Its
+//! adapters only ever see the `Ok` items, and `?` works inside them.
+//!
+//! `convert` and `TryIterator::iter` go back and forth between the two.
+
+use core::iter::FusedIterator;
+
+pub trait TryIterator {
+    type Ok;
+    type Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Ok>, Self::Error>;
+
+    /// Transforms each item with `f`, which may fail too.
+    fn map<B, F>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Self::Ok) -> Result<B, Self::Error>,
+    {
+        Map { orig: self, f }
+    }
+
+    /// Keeps the items `pred` holds for.
+    fn filter<P>(self, pred: P) -> Filter<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Ok) -> bool,
+    {
+        Filter { orig: self, pred }
+    }
+
+    /// Collects every item, or returns the first error.
+    fn collect<C>(self) -> Result<C, Self::Error>
+    where
+        Self: Sized,
+        C: FromIterator<Self::Ok>,
+    {
+        self.iter().collect()
+    }
+
+    /// Turns this into an `Iterator` of `Result`s, which ends right after
+    /// the first `Err`.
+    fn iter(self) -> Iter<Self>
+    where
+        Self: Sized,
+    {
+        Iter {
+            orig: self,
+            done: false,
+        }
+    }
+}
+
+impl<I: TryIterator + ?Sized> TryIterator for &mut I {
+    type Ok = I::Ok;
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<Self::Ok>, Self::Error> {
+        (**self).try_next()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map<I, F> {
+    orig: I,
+    f: F,
+}
+
+impl<I, F, B> TryIterator for Map<I, F>
+where
+    I: TryIterator,
+    F: FnMut(I::Ok) -> Result<B, I::Error>,
+{
+    type Ok = B;
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<B>, I::Error> {
+        match self.orig.try_next()? {
+            Some(item) => (self.f)(item).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter<I, P> {
+    orig: I,
+    pred: P,
+}
+
+impl<I, P> TryIterator for Filter<I, P>
+where
+    I: TryIterator,
+    P: FnMut(&I::Ok) -> bool,
+{
+    type Ok = I::Ok;
+    type Error = I::Error;
+
+    fn try_next(&mut self) -> Result<Option<I::Ok>, I::Error> {
+        while let Some(item) = self.orig.try_next()? {
+            if (self.pred)(&item) {
+                return Ok(Some(item));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A `TryIterator` seen as an `Iterator` of `Result`s.
+#[derive(Debug, Clone)]
+pub struct Iter<I> {
+    orig: I,
+    // Set by the end or the first error, so `try_next` isn't called past
+    // either.
+    done: bool,
+}
+
+impl<I: TryIterator> Iterator for Iter<I> {
+    type Item = Result<I::Ok, I::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.orig.try_next().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+impl<I: TryIterator> FusedIterator for Iter<I> {}
+
+/// An `Iterator` of `Result`s seen as a `TryIterator`.
+#[derive(Debug, Clone)]
+pub struct Convert<I> {
+    orig: I,
+}
+
+pub fn convert<I, T, E>(iter: I) -> Convert<I::IntoIter>
+where
+    I: IntoIterator<Item = Result<T, E>>,
+{
+    Convert {
+        orig: iter.into_iter(),
+    }
+}
+
+impl<I, T, E> TryIterator for Convert<I>
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    type Ok = T;
+    type Error = E;
+
+    fn try_next(&mut self) -> Result<Option<T>, E> {
+        self.orig.next().transpose()
+    }
+}
+
+#[test]
+fn try_iterator_adapters() {
+    let numbers = convert(["1", "2", "3", "4"].map(Ok::<_, std::num::ParseIntError>));
+    let evens: Vec<i32> = numbers
+        .map(|s| s.parse::<i32>())
+        .filter(|n| n % 2 == 0)
+        .collect()
+        .unwrap();
+    assert_eq!(evens, [2, 4]);
+
+    let bad = convert(["1", "x", "3"].map(Ok::<_, std::num::ParseIntError>));
+    let parsed: Result<Vec<i32>, _> = bad.map(|s| s.parse::<i32>()).collect();
+    assert!(parsed.is_err());
+}
+
+#[test]
+fn try_iterator_stops_at_the_first_error() {
+    let items = [Ok(1), Ok(2), Err("boom"), Ok(3)];
+    let mut results = convert(items).iter();
+    assert_eq!(results.next(), Some(Ok(1)));
+    assert_eq!(results.next(), Some(Ok(2)));
+    assert_eq!(results.next(), Some(Err("boom")));
+    // Unlike the plain iterator, nothing comes after the error.
+    assert_eq!(results.next(), None);
+    assert_eq!(items.iter().count(), 4);
+
+    // Borrowed, a try-iterator can be resumed where it stopped.
+    let mut source = convert([Ok::<_, ()>(1), Ok(2), Ok(3)]);
+    assert_eq!((&mut source).filter(|&n| n > 1).try_next(), Ok(Some(2)));
+    assert_eq!(source.try_next(), Ok(Some(3)));
+    assert_eq!(source.try_next(), Ok(None));
+}
+
+// A worked example: reading `name,score` records, where any line can be
+// malformed. The reader is a `TryIterator` in its own right.
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct Score {
+    name: String,
+    score: u32,
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+enum CsvError {
+    MissingField { line: usize },
+    BadScore { line: usize, found: String },
+}
+
+#[cfg(test)]
+use crate::adapters::{EnumerateFromExt, EnumerateStep};
+
+#[cfg(test)]
+struct CsvScores<'a> {
+    lines: EnumerateStep<std::str::Lines<'a>, usize>,
+}
+
+#[cfg(test)]
+impl TryIterator for CsvScores<'_> {
+    type Ok = Score;
+    type Error = CsvError;
+
+    fn try_next(&mut self) -> Result<Option<Score>, CsvError> {
+        let Some((line_no, line)) = self.lines.next() else {
+            return Ok(None);
+        };
+        let (name, score) = line
+            .split_once(',')
+            .ok_or(CsvError::MissingField { line: line_no })?;
+        let score = score.trim().parse().map_err(|_| CsvError::BadScore {
+            line: line_no,
+            found: score.trim().to_string(),
+        })?;
+        Ok(Some(Score {
+            name: name.trim().to_string(),
+            score,
+        }))
+    }
+}
+
+#[cfg(test)]
+fn csv_scores(text: &str) -> CsvScores<'_> {
+    CsvScores {
+        lines: text.lines().enumerate_from(1),
+    }
+}
+
+#[test]
+fn try_iterator_reads_csv() {
+    let passed = |text| -> Result<Vec<String>, CsvError> {
+        csv_scores(text)
+            .filter(|s| s.score >= 50)
+            .map(|s| Ok(s.name))
+            .collect()
+    };
+    assert_eq!(
+        passed("ann, 72\nbob, 41\ncy, 50"),
+        Ok(vec!["ann".to_string(), "cy".to_string()])
+    );
+    assert_eq!(
+        passed("ann, 72\nbob\ncy, 50"),
+        Err(CsvError::MissingField { line: 2 })
+    );
+    assert_eq!(
+        passed("ann, 72\nbob, lots\ncy, x"),
+        Err(CsvError::BadScore {
+            line: 2,
+            found: "lots".to_string()
+        })
+    );
+
+    // The same records as an ordinary iterator, for `std` adapters.
+    let total: u32 = csv_scores("a, 1\nb, 2\nc, oops")
+        .iter()
+        .map_while(Result::ok)
+        .map(|s| s.score)
+        .sum();
+    assert_eq!(total, 3);
+}
@@ -30,17 +30,7 @@ mod adapters {
         println!("dest_with_map = {:?}", dest_with_map);
     }
 
-    #[cfg(feature = "skip")]
-    #[test]
-    fn mapping_exercise_make_this_compile() {
-        let player_scores = [("Jack", 20), ("Jane", 23), ("Jill", 18), ("John", 19)];
-
-        let players = player_scores
-            .map(|(player, _score)| player)
-            .collect::<Vec<_>>();
-
-        assert_eq!(players, ["Jack", "Jane", "Jill", "John"]);
-    }
+    // The exercise that was here is `exercises::mapping`.
 
     #[test]
     fn iter_mut_and_mapping() {
@@ -249,11 +239,14 @@ mod adapters {
 
     #[test]
     fn fibonacci_numbers() {
-        let fibo = std::iter::successors(Some((0, 1)), |&(a, b)| Some((b, a + b)))
-            .map(|(v, _)| v)
-            .take(10);
+        use crate::sources::successors_until;
+
+        // Unlike `std::iter::successors`, no `Some(...)` wrapping: the step
+        // always succeeds, and the stop condition lives in its own closure.
+        let fibo = successors_until((0, 1), |&(a, b)| (b, a + b), |&(a, _)| a > 50).map(|(v, _)| v);
 
-        println!("fibo = {:?}", fibo.collect::<Vec<_>>());
+        println!("fibo = {:?}", fibo.clone().collect::<Vec<_>>());
+        assert_eq!(fibo.collect::<Vec<_>>(), [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
     }
 }
 
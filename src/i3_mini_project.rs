@@ -1,49 +1,169 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+
+pub mod curves;
+mod game;
+mod generator;
+pub mod geometry;
+pub mod grid;
+mod log;
+pub mod neighborhood;
+mod parse;
+pub mod random_walk;
+mod render;
+#[cfg(feature = "serde")]
+mod save;
+mod search;
+
+pub use curves::{hilbert_index, hilbert_point, morton_index, morton_point, HilbertCurve, Morton};
+pub use game::simulation;
+pub use geometry::{line, points_within, spiral_from, Metric, Rect};
+pub use grid::Grid2D;
+pub use neighborhood::Neighborhood;
+pub use random_walk::{Boundary, RandomWalk};
 
 /**
  * Mini project
  */
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Worm {
-    position: Point2d,
+    // The cells the worm covers, head first. A worm starts as just its
+    // head and grows one segment per move until it is `length` long.
+    body: VecDeque<Point2d>,
     length: i32,
 }
 
+impl Worm {
+    fn new(position: Point2d, length: i32) -> Self {
+        Worm {
+            body: VecDeque::from([position]),
+            length,
+        }
+    }
+
+    fn head(&self) -> Point2d {
+        self.body[0]
+    }
+
+    /// The cells of the body, from the head to the tail.
+    fn segments(&self) -> std::collections::vec_deque::Iter<'_, Point2d> {
+        self.body.iter()
+    }
+
+    fn segments_mut(&mut self) -> std::collections::vec_deque::IterMut<'_, Point2d> {
+        self.body.iter_mut()
+    }
+
+    /// Moves the head to `target`. The rest of the body follows, and the
+    /// tail leaves its cell, unless the worm still has some growing to do.
+    /// Returns the cell the tail left, if it did.
+    fn move_to(&mut self, target: Point2d) -> Option<Point2d> {
+        self.body.push_front(target);
+        if self.body.len() > self.length.max(1) as usize {
+            self.body.pop_back()
+        } else {
+            None
+        }
+    }
+
+    /// Whether the head can move to `target` without running into the
+    /// body. The tail is no obstacle when it is about to move away.
+    fn can_move_to(&self, target: Point2d) -> bool {
+        let full_grown = self.body.len() >= self.length.max(1) as usize;
+        let blocking = if full_grown {
+            self.body.len() - 1
+        } else {
+            self.body.len()
+        };
+        !self.segments().take(blocking).any(|&p| p == target)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Map {
-    cells: Vec<Vec<MapCell>>,
+    cells: Grid2D<MapCell>,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum MapCell {
     Air,
     Dirt,
+    /// Can't be walked through or dug.
+    Rock,
+    /// Walkable, but slow going.
+    Water,
+    /// Walkable like air; what picking it up does is up to the game.
+    Powerup,
+}
+
+impl MapCell {
+    /// How many turns it takes a worm to get into this cell: one step for
+    /// open ground, two in water, and for dirt one turn to dig it and one
+    /// to move in. `None` means the worm can't get in at all.
+    fn cost(self) -> Option<u32> {
+        match self {
+            MapCell::Air | MapCell::Powerup => Some(1),
+            MapCell::Water | MapCell::Dirt => Some(2),
+            MapCell::Rock => None,
+        }
+    }
+
+    /// Whether a worm can move into this cell without digging first.
+    fn is_walkable(self) -> bool {
+        matches!(self, MapCell::Air | MapCell::Water | MapCell::Powerup)
+    }
 }
 
 impl Map {
     // fn at(&self, target: <Point2d as Add<Point2d>>::Output) -> Option<MapCell> {
     fn at(&self, target: Point2d) -> Option<MapCell> {
-        if target.x < 0 || target.y < 0 {
-            return None;
-        }
-        let x = target.x as usize;
-        let y = target.y as usize;
-        if x >= self.cells.len() || y >= self.cells[x].len() {
-            return None;
-        }
-        Some(self.cells[x][y])
+        self.cells.get(target).copied()
+    }
+
+    /// Every cell of the map with its position, row by row.
+    fn iter_cells(&self) -> impl Iterator<Item = (Point2d, MapCell)> + '_ {
+        self.cells.iter_with_coords().map(|(p, &cell)| (p, cell))
+    }
+
+    /// The cells around `point` in each of `Direction::all()`, leaving out the
+    /// ones that fall off the map.
+    fn neighbors(&self, point: Point2d) -> impl Iterator<Item = (Point2d, MapCell)> + '_ {
+        Direction::all().filter_map(move |dir| {
+            let target = point + Point2d::from(dir);
+            self.at(target).map(|cell| (target, cell))
+        })
     }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Point2d {
-    x: i32,
-    y: i32,
+pub struct Point2d {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point2d {
+    pub const fn new(x: i32, y: i32) -> Point2d {
+        Point2d { x, y }
+    }
+
+    /// The number of steps from `self` to `other` along the axes only.
+    pub fn manhattan(self, other: Point2d) -> i32 {
+        let d = other - self;
+        d.x.abs() + d.y.abs()
+    }
+
+    /// The number of steps from `self` to `other` when diagonal steps are
+    /// allowed, as they are for worms.
+    pub fn chebyshev(self, other: Point2d) -> i32 {
+        let d = other - self;
+        d.x.abs().max(d.y.abs())
+    }
 }
 
 impl std::ops::Add for Point2d {
     type Output = Point2d;
 
-    fn add(self, rhs: Self::Output) -> Self::Output {
+    fn add(self, rhs: Point2d) -> Self::Output {
         Point2d {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -51,13 +171,65 @@ impl std::ops::Add for Point2d {
     }
 }
 
-#[derive(Debug)]
+impl std::ops::Sub for Point2d {
+    type Output = Point2d;
+
+    fn sub(self, rhs: Point2d) -> Self::Output {
+        Point2d {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl std::ops::Neg for Point2d {
+    type Output = Point2d;
+
+    fn neg(self) -> Self::Output {
+        Point2d {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl std::ops::Mul<i32> for Point2d {
+    type Output = Point2d;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Point2d {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl std::ops::AddAssign for Point2d {
+    fn add_assign(&mut self, rhs: Point2d) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::SubAssign for Point2d {
+    fn sub_assign(&mut self, rhs: Point2d) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::iter::Sum for Point2d {
+    fn sum<I: Iterator<Item = Point2d>>(iter: I) -> Self {
+        iter.fold(Point2d::new(0, 0), |acc, p| acc + p)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Action {
     Move(Point2d),
     Dig(Point2d),
 }
 
-enum Direction {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
     North,
     South,
     East,
@@ -69,7 +241,7 @@ enum Direction {
 }
 
 impl Direction {
-    const ALL: [Direction; 8] = [
+    pub const ALL: [Direction; 8] = [
         Direction::North,
         Direction::South,
         Direction::East,
@@ -80,6 +252,51 @@ impl Direction {
         Direction::SouthWest,
     ];
 
+    // The directions in clockwise order, 45 degrees apart.
+    const CLOCKWISE: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// All eight directions, in the order of `Direction::ALL`.
+    pub fn all() -> std::array::IntoIter<Direction, 8> {
+        Self::ALL.into_iter()
+    }
+
+    /// North, east, south and west.
+    pub fn cardinal() -> impl Iterator<Item = Direction> {
+        Self::CLOCKWISE.into_iter().step_by(2)
+    }
+
+    /// North-east, south-east, south-west and north-west.
+    pub fn diagonal() -> impl Iterator<Item = Direction> {
+        Self::CLOCKWISE.into_iter().skip(1).step_by(2)
+    }
+
+    /// The next direction clockwise, 45 degrees away.
+    pub fn rotate_cw(self) -> Direction {
+        Self::CLOCKWISE[(self.clockwise_index() + 1) % 8]
+    }
+
+    /// The next direction counter-clockwise, 45 degrees away.
+    pub fn rotate_ccw(self) -> Direction {
+        Self::CLOCKWISE[(self.clockwise_index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Self::CLOCKWISE[(self.clockwise_index() + 4) % 8]
+    }
+
+    fn clockwise_index(self) -> usize {
+        Self::CLOCKWISE.iter().position(|&d| d == self).unwrap()
+    }
+
     fn as_vec(&self) -> Point2d {
         match self {
             Direction::North => Point2d { x: 0, y: -1 },
@@ -94,6 +311,37 @@ impl Direction {
     }
 }
 
+/// The one-cell step in that direction. `y` grows downwards, so north is
+/// `(0, -1)`.
+impl From<Direction> for Point2d {
+    fn from(dir: Direction) -> Point2d {
+        dir.as_vec()
+    }
+}
+
+// The end result of the case study below: the loop of
+// `valid_moves_for_worm_imperative`, one adapter per step.
+fn valid_moves_for_worm(worm: &Worm, map: &Map, occupied_cells: &HashSet<Point2d>) -> Vec<Action> {
+    // Each step of the loop becomes one adapter: walk the neighbors,
+    // drop the occupied ones and the ones the worm's own body is in, and
+    // turn each cell it can get into into an action. The cheapest actions
+    // come first; `sort_by_key` is stable, so ties keep their order.
+    let mut moves: Vec<(u32, Action)> = map
+        .neighbors(worm.head())
+        .filter(|(target, _)| !occupied_cells.contains(target))
+        .filter(|&(target, _)| worm.can_move_to(target))
+        .filter_map(|(target, cell)| {
+            let action = match cell {
+                MapCell::Dirt => Action::Dig(target),
+                _ => Action::Move(target),
+            };
+            cell.cost().map(|cost| (cost, action))
+        })
+        .collect();
+    moves.sort_by_key(|&(cost, _)| cost);
+    moves.into_iter().map(|(_, action)| action).collect()
+}
+
 #[test]
 fn case_study_imperative_style() {
     fn valid_moves_for_worm_imperative(
@@ -107,7 +355,7 @@ fn case_study_imperative_style() {
         // could walk, including diagonals.
         for dir in &Direction::ALL {
             // 1. Target is one cell in a direction from the worm
-            let target = worm.position + Direction::as_vec(dir);
+            let target = worm.head() + Direction::as_vec(dir);
             // 2. We check that the target is free
             if !occupied_cells.contains(&target) {
                 // 3. and 4. Air cells are moved to, Dirt cells are dug,
@@ -131,16 +379,13 @@ fn case_study_imperative_style() {
     }
 
     let map = Map {
-        cells: vec![
+        cells: Grid2D::from_rows(vec![
             vec![MapCell::Air, MapCell::Air, MapCell::Dirt],
             vec![MapCell::Air, MapCell::Dirt, MapCell::Air],
             vec![MapCell::Dirt, MapCell::Air, MapCell::Air],
-        ],
-    };
-    let worm = Worm {
-        position: Point2d { x: 0, y: 0 },
-        length: 3,
+        ]),
     };
+    let worm = Worm::new(Point2d { x: 0, y: 0 }, 3);
     let occupied_cells = HashSet::new();
 
     let actions = valid_moves_for_worm_imperative(&worm, &map, occupied_cells);
@@ -149,50 +394,288 @@ fn case_study_imperative_style() {
 
 #[test]
 fn case_study_transform_to_functional_style() {
-    fn valid_moves_for_worm(
-        worm: &Worm,
-        map: &Map,
-        occupied_cells: HashSet<Point2d>,
-    ) -> Vec<Action> {
-        let mut valid_moves = Vec::new();
-
-        // Direction::ALL is a constant list of all directions a worm
-        // could walk, including diagonals.
-        for dir in &Direction::ALL {
-            // 1. Target is one cell in a direction from the worm
-            let target = worm.position + Direction::as_vec(dir);
-            // 2. We check that the target is free
-            if !occupied_cells.contains(&target) {
-                // 3. and 4. Air cells are moved to, Dirt cells are dug,
-                // out of bounds cells do nothing.
-                match map.at(target) {
-                    Some(MapCell::Air) => {
-                        valid_moves.push(Action::Move(target));
-                    }
-                    Some(MapCell::Dirt) => {
-                        valid_moves.push(Action::Dig(target));
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-        valid_moves
-    }
-
     let map = Map {
-        cells: vec![
+        cells: Grid2D::from_rows(vec![
             vec![MapCell::Air, MapCell::Air, MapCell::Dirt],
             vec![MapCell::Air, MapCell::Dirt, MapCell::Air],
             vec![MapCell::Dirt, MapCell::Air, MapCell::Air],
-        ],
-    };
-    let worm = Worm {
-        position: Point2d { x: 0, y: 0 },
-        length: 3,
+        ]),
     };
+    let worm = Worm::new(Point2d { x: 0, y: 0 }, 3);
     let occupied_cells = HashSet::new();
 
-    let actions = valid_moves_for_worm(&worm, &map, occupied_cells);
+    let actions = valid_moves_for_worm(&worm, &map, &occupied_cells);
     println!("{:?}", actions);
+    assert_eq!(
+        actions,
+        [
+            Action::Move(Point2d::new(0, 1)),
+            Action::Move(Point2d::new(1, 0)),
+            Action::Dig(Point2d::new(1, 1)),
+        ]
+    );
+
+    let occupied_cells = HashSet::from([Point2d::new(1, 0)]);
+    let actions = valid_moves_for_worm(&worm, &map, &occupied_cells);
+    assert_eq!(
+        actions,
+        [
+            Action::Move(Point2d::new(0, 1)),
+            Action::Dig(Point2d::new(1, 1)),
+        ]
+    );
+}
+
+#[cfg(test)]
+fn sample_map() -> Map {
+    use MapCell::{Air, Dirt};
+    // . . #
+    // . # .
+    // # . .
+    // . . #
+    Map {
+        cells: Grid2D::from_rows(vec![
+            vec![Air, Air, Dirt],
+            vec![Air, Dirt, Air],
+            vec![Dirt, Air, Air],
+            vec![Air, Air, Dirt],
+        ]),
+    }
+}
+
+#[test]
+fn map_iter_cells() {
+    let map = sample_map();
+    assert_eq!(map.iter_cells().count(), 12);
+
+    let dirt: Vec<Point2d> = map
+        .iter_cells()
+        .filter(|&(_, cell)| cell == MapCell::Dirt)
+        .map(|(p, _)| p)
+        .collect();
+    assert_eq!(
+        dirt,
+        [
+            Point2d::new(2, 0),
+            Point2d::new(1, 1),
+            Point2d::new(0, 2),
+            Point2d::new(2, 3),
+        ]
+    );
+}
+
+#[test]
+fn map_neighbors_at_corners_edges_and_inside() {
+    let map = sample_map();
+    let neighbors = |x, y| -> Vec<Point2d> {
+        let mut points: Vec<Point2d> = map.neighbors(Point2d::new(x, y)).map(|(p, _)| p).collect();
+        points.sort_by_key(|p| (p.y, p.x));
+        points
+    };
+
+    // Corners have three neighbors.
+    assert_eq!(
+        neighbors(0, 0),
+        [Point2d::new(1, 0), Point2d::new(0, 1), Point2d::new(1, 1)]
+    );
+    assert_eq!(
+        neighbors(2, 3),
+        [Point2d::new(1, 2), Point2d::new(2, 2), Point2d::new(1, 3)]
+    );
+    // Edges have five.
+    assert_eq!(neighbors(0, 1).len(), 5);
+    assert_eq!(neighbors(1, 3).len(), 5);
+    assert_eq!(neighbors(2, 2).len(), 5);
+    // Inner cells have all eight.
+    assert_eq!(neighbors(1, 1).len(), 8);
+    assert_eq!(neighbors(1, 2).len(), 8);
+    // Off the map, only the cells touching it are in bounds.
+    assert_eq!(neighbors(-1, -1), [Point2d::new(0, 0)]);
+    assert!(neighbors(5, 5).is_empty());
+
+    let cells: Vec<MapCell> = map.neighbors(Point2d::new(0, 0)).map(|(_, c)| c).collect();
+    assert_eq!(cells.iter().filter(|&&c| c == MapCell::Dirt).count(), 1);
+}
+
+#[test]
+fn worm_body_follows_the_head() {
+    let mut worm = Worm::new(Point2d::new(0, 0), 3);
+    assert_eq!(worm.segments().len(), 1);
+
+    for x in 1..=4 {
+        worm.move_to(Point2d::new(x, 0));
+    }
+    // The body grew to its length, then started dropping its tail.
+    assert!(worm.segments().copied().eq([
+        Point2d::new(4, 0),
+        Point2d::new(3, 0),
+        Point2d::new(2, 0),
+    ]));
+
+    for segment in worm.segments_mut() {
+        segment.y += 1;
+    }
+    assert_eq!(worm.head(), Point2d::new(4, 1));
+}
+
+#[test]
+fn worm_cannot_move_into_itself() {
+    use MapCell::Air;
+    let map = Map {
+        cells: Grid2D::new(3, 3, Air),
+    };
+    // A worm curled around the center: head at (1, 0), tail at (0, 1).
+    let mut worm = Worm::new(Point2d::new(0, 1), 4);
+    for target in [(0, 0), (1, 0)] {
+        worm.move_to(Point2d::new(target.0, target.1));
+    }
+    let targets = |worm: &Worm| -> Vec<Point2d> {
+        valid_moves_for_worm(worm, &map, &HashSet::new())
+            .into_iter()
+            .map(|action| match action {
+                Action::Move(p) | Action::Dig(p) => p,
+            })
+            .collect()
+    };
+
+    // Still growing, so the tail stays put and blocks the head.
+    assert!(!targets(&worm).contains(&Point2d::new(0, 1)));
+    assert!(!targets(&worm).contains(&Point2d::new(0, 0)));
+    assert_eq!(targets(&worm).len(), 3);
+
+    // Fully grown, the tail moves away as the head moves in.
+    worm.move_to(Point2d::new(1, 1));
+    worm.move_to(Point2d::new(0, 1));
+    assert_eq!(worm.segments().len(), 4);
+    // Tail at (0, 0), head at (0, 1).
+    assert!(targets(&worm).contains(&Point2d::new(0, 0)));
+    assert!(!targets(&worm).contains(&Point2d::new(1, 0)));
+    assert!(!targets(&worm).contains(&Point2d::new(1, 1)));
+}
+
+#[test]
+fn direction_subsets() {
+    use Direction::*;
+    assert_eq!(Direction::all().len(), 8);
+    assert_eq!(
+        Direction::cardinal().collect::<Vec<_>>(),
+        [North, East, South, West]
+    );
+    assert_eq!(
+        Direction::diagonal().collect::<Vec<_>>(),
+        [NorthEast, SouthEast, SouthWest, NorthWest]
+    );
+
+    let mut both: Vec<Direction> = Direction::cardinal().chain(Direction::diagonal()).collect();
+    both.sort_by_key(|&d| Direction::ALL.iter().position(|&a| a == d));
+    assert_iter_eq!(both.into_iter(), Direction::all());
+
+    // A cardinal step moves along one axis, a diagonal step along both.
+    assert!(Direction::cardinal().all(|d| {
+        let p = Point2d::from(d);
+        p.x.abs() + p.y.abs() == 1
+    }));
+    assert!(Direction::diagonal().all(|d| {
+        let p = Point2d::from(d);
+        p.x.abs() == 1 && p.y.abs() == 1
+    }));
+}
+
+#[test]
+fn direction_rotation_laws() {
+    for dir in Direction::all() {
+        assert_eq!(dir.rotate_cw().rotate_ccw(), dir);
+        assert_eq!(dir.rotate_ccw().rotate_cw(), dir);
+        assert_eq!(dir.opposite().opposite(), dir);
+        assert_ne!(dir.opposite(), dir);
+
+        // Eight eighth-turns make a full turn, four make a half turn.
+        let turns: Vec<Direction> = std::iter::successors(Some(dir), |d| Some(d.rotate_cw()))
+            .take(9)
+            .collect();
+        assert_eq!(turns[8], dir);
+        assert_eq!(turns[4], dir.opposite());
+        assert_eq!(
+            dir.rotate_ccw().rotate_ccw().rotate_ccw().rotate_ccw(),
+            dir.opposite()
+        );
+
+        // Rotating swaps cardinal and diagonal directions.
+        let is_cardinal = |d: Direction| Direction::cardinal().any(|c| c == d);
+        assert_ne!(is_cardinal(dir), is_cardinal(dir.rotate_cw()));
+
+        // Opposite steps cancel out.
+        let (a, b) = (Point2d::from(dir), Point2d::from(dir.opposite()));
+        assert_eq!((a.x + b.x, a.y + b.y), (0, 0));
+    }
+    assert_eq!(Direction::North.rotate_cw(), Direction::NorthEast);
+    assert_eq!(Direction::West.rotate_ccw(), Direction::SouthWest);
+}
+
+#[test]
+fn valid_moves_depend_on_terrain() {
+    let map: Map = [
+        "%~.", //
+        "*.#", //
+        "...", //
+    ]
+    .into_iter()
+    .collect();
+    let worm = Worm::new(Point2d::new(1, 1), 1);
+    let moves = valid_moves_for_worm(&worm, &map, &HashSet::new());
+
+    // No way into the rock; water and dirt cost two turns, so come last.
+    assert_eq!(moves.len(), 7);
+    assert!(!moves.contains(&Action::Move(Point2d::new(0, 0))));
+    assert_eq!(
+        moves[5..],
+        [
+            Action::Move(Point2d::new(1, 0)),
+            Action::Dig(Point2d::new(2, 1))
+        ]
+    );
+    assert!(moves.contains(&Action::Move(Point2d::new(0, 1))));
+
+    assert_eq!(MapCell::Air.cost(), Some(1));
+    assert_eq!(MapCell::Water.cost(), Some(2));
+    assert_eq!(MapCell::Rock.cost(), None);
+}
+
+#[test]
+fn point_arithmetic() {
+    let a = Point2d::new(3, -2);
+    let b = Point2d::new(-1, 5);
+    assert_eq!(a + b, Point2d::new(2, 3));
+    assert_eq!(a - b, Point2d::new(4, -7));
+    assert_eq!(-a, Point2d::new(-3, 2));
+    assert_eq!(a * 3, Point2d::new(9, -6));
+    assert_eq!(a - b, a + -b);
+
+    let mut c = a;
+    c += b;
+    assert_eq!(c, a + b);
+    c -= b;
+    assert_eq!(c, a);
+
+    assert_eq!(a.manhattan(b), 11);
+    assert_eq!(a.chebyshev(b), 7);
+    assert_eq!(b.chebyshev(a), 7);
+    assert_eq!(a.manhattan(a), 0);
+}
+
+#[test]
+fn direction_vectors_sum_to_zero() {
+    let origin = Point2d::new(0, 0);
+    assert_eq!(Direction::all().map(Point2d::from).sum::<Point2d>(), origin);
+    assert_eq!(
+        Direction::cardinal().map(Point2d::from).sum::<Point2d>(),
+        origin
+    );
+    assert_eq!(std::iter::empty::<Point2d>().sum::<Point2d>(), origin);
+
+    // Every step is one Chebyshev step; only the cardinal ones are one
+    // Manhattan step.
+    assert!(Direction::all().all(|d| origin.chebyshev(d.into()) == 1));
+    assert!(Direction::cardinal().all(|d| origin.manhattan(d.into()) == 1));
+    assert!(Direction::diagonal().all(|d| origin.manhattan(d.into()) == 2));
 }
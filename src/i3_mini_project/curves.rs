@@ -0,0 +1,408 @@
+//! Space-filling curves: orders to visit every cell of a square grid in
+//! that keep cells that are close on the grid close in the order too, which
+//! is kinder to caches than going row by row.
+//!
+//! The grids are `2^order` cells on a side, with `(0, 0)` in the corner.
+//! The Z-order curve jumps now and then; the Hilbert curve never does, as
+//! each cell it visits is next to the one before.
+
+use super::{Grid2D, Point2d};
+use std::iter::FusedIterator;
+
+/// Spreads the bits of `v` out to the even positions: `abcd` becomes
+/// `0a0b0c0d`.
+fn spread_bits(v: u32) -> u64 {
+    let mut v = v as u64;
+    v = (v | (v << 16)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v << 8)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
+
+/// The inverse of `spread_bits`: gathers the even bits of `v`.
+fn gather_bits(v: u64) -> u32 {
+    let mut v = v & 0x5555_5555_5555_5555;
+    v = (v | (v >> 1)) & 0x3333_3333_3333_3333;
+    v = (v | (v >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    v = (v | (v >> 4)) & 0x00FF_00FF_00FF_00FF;
+    v = (v | (v >> 8)) & 0x0000_FFFF_0000_FFFF;
+    v = (v | (v >> 16)) & 0x0000_0000_FFFF_FFFF;
+    v as u32
+}
+
+/// The position of `point` along the Z-order curve: the bits of `x` and `y`
+/// interleaved, with `x` in the low bit of each pair.
+///
+/// # Panics
+///
+/// Panics if either coordinate is negative.
+pub fn morton_index(point: Point2d) -> u64 {
+    assert!(
+        point.x >= 0 && point.y >= 0,
+        "the Z-order curve has no negative coordinates"
+    );
+    spread_bits(point.x as u32) | (spread_bits(point.y as u32) << 1)
+}
+
+/// The point at position `index` along the Z-order curve.
+pub fn morton_point(index: u64) -> Point2d {
+    Point2d::new(gather_bits(index) as i32, gather_bits(index >> 1) as i32)
+}
+
+/// The cells of a `2^order` x `2^order` grid in Z-order: each quarter of
+/// the grid is finished before the next one starts, top left, top right,
+/// bottom left, bottom right, and so on down inside every quarter.
+#[derive(Debug, Clone)]
+pub struct Morton {
+    // Curve positions `front..back` are still to come.
+    front: u64,
+    back: u64,
+}
+
+impl Morton {
+    /// # Panics
+    ///
+    /// Panics if `order` is over 31, as the coordinates wouldn't fit in an
+    /// `i32`.
+    pub fn new(order: u32) -> Self {
+        assert!(order < 32, "order {order} is too big for a grid of i32s");
+        Morton {
+            front: 0,
+            back: 1 << (2 * order),
+        }
+    }
+}
+
+impl Iterator for Morton {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(morton_point(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Morton {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(morton_point(self.back))
+    }
+}
+
+impl ExactSizeIterator for Morton {}
+
+impl FusedIterator for Morton {}
+
+/// Flips and turns the `side` x `side` square so that a quarter of the
+/// Hilbert curve lines up with the quarter before it.
+fn hilbert_rotate(side: u64, x: &mut u64, y: &mut u64, rx: u64, ry: u64) {
+    if ry == 0 {
+        if rx == 1 {
+            *x = side - 1 - *x;
+            *y = side - 1 - *y;
+        }
+        std::mem::swap(x, y);
+    }
+}
+
+/// The position of `point` along the Hilbert curve of the given `order`.
+///
+/// # Panics
+///
+/// Panics if `point` isn't on the `2^order` x `2^order` grid.
+pub fn hilbert_index(order: u32, point: Point2d) -> u64 {
+    let side = 1u64 << order;
+    let on_grid = |v: i32| (0..side).contains(&(v as u64)) && v >= 0;
+    assert!(
+        on_grid(point.x) && on_grid(point.y),
+        "{point:?} isn't on a Hilbert curve of order {order}"
+    );
+    let (mut x, mut y) = (point.x as u64, point.y as u64);
+    let mut index = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        index += s * s * ((3 * rx) ^ ry);
+        hilbert_rotate(side, &mut x, &mut y, rx, ry);
+        s /= 2;
+    }
+    index
+}
+
+/// The point at position `index` along the Hilbert curve of the given
+/// `order`. Indices past the end of the curve wrap around.
+pub fn hilbert_point(order: u32, index: u64) -> Point2d {
+    let side = 1u64 << order;
+    let (mut x, mut y) = (0, 0);
+    let mut t = index;
+    let mut s = 1;
+    while s < side {
+        let rx = 1 & (t / 2);
+        let ry = 1 & (t ^ rx);
+        hilbert_rotate(s, &mut x, &mut y, rx, ry);
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+    Point2d::new(x as i32, y as i32)
+}
+
+/// The cells of a `2^order` x `2^order` grid along the Hilbert curve,
+/// starting at `(0, 0)` and ending at `(2^order - 1, 0)`.
+#[derive(Debug, Clone)]
+pub struct HilbertCurve {
+    order: u32,
+    // Curve positions `front..back` are still to come.
+    front: u64,
+    back: u64,
+}
+
+impl HilbertCurve {
+    /// # Panics
+    ///
+    /// Panics if `order` is over 31, as the coordinates wouldn't fit in an
+    /// `i32`.
+    pub fn new(order: u32) -> Self {
+        assert!(order < 32, "order {order} is too big for a grid of i32s");
+        HilbertCurve {
+            order,
+            front: 0,
+            back: 1 << (2 * order),
+        }
+    }
+}
+
+impl Iterator for HilbertCurve {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(hilbert_point(self.order, self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for HilbertCurve {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(hilbert_point(self.order, self.back))
+    }
+}
+
+impl ExactSizeIterator for HilbertCurve {}
+
+impl FusedIterator for HilbertCurve {}
+
+/// The smallest order whose curve covers a `width` x `height` grid.
+fn covering_order(width: usize, height: usize) -> u32 {
+    width.max(height).next_power_of_two().trailing_zeros()
+}
+
+/// The points of a `width` x `height` grid in the order of the smallest
+/// Z-order curve that covers it. Whole quarters of that curve's square that
+/// lie off the grid are jumped over rather than walked, so a long, thin
+/// grid costs about as much as its cells, not as much as the square.
+fn morton_within(width: usize, height: usize) -> impl Iterator<Item = Point2d> {
+    let order = covering_order(width, height);
+    let end = 1u64 << (2 * order);
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        while index < end {
+            let p = morton_point(index);
+            if (p.x as usize) < width && (p.y as usize) < height {
+                index += 1;
+                return Some(p);
+            }
+            // `index` starts a quarter (of a quarter, ...) of the square as
+            // big as its trailing zero bits allow, with `p` in its corner,
+            // so the whole quarter is off the grid too.
+            index += 1 << (index.trailing_zeros() & !1).min(2 * order);
+        }
+        None
+    })
+}
+
+impl<T> Grid2D<T> {
+    /// The cells of the grid in Z-order. A grid whose sides aren't a power
+    /// of two is walked along the curve of the smallest square that covers
+    /// it, skipping the parts that fall outside.
+    pub fn iter_z_order(&self) -> impl Iterator<Item = (Point2d, &T)> + '_ {
+        morton_within(self.width(), self.height()).filter_map(|p| self.get(p).map(|cell| (p, cell)))
+    }
+}
+
+#[cfg(test)]
+fn row_major(side: i32) -> Vec<Point2d> {
+    (0..side)
+        .flat_map(|y| (0..side).map(move |x| Point2d::new(x, y)))
+        .collect()
+}
+
+#[cfg(test)]
+fn sorted(mut points: Vec<Point2d>) -> Vec<Point2d> {
+    points.sort_by_key(|p| (p.y, p.x));
+    points
+}
+
+#[test]
+fn morton_order() {
+    let z: Vec<Point2d> = Morton::new(1).collect();
+    assert_eq!(
+        z,
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| Point2d::new(x, y))
+    );
+
+    let mut curve = Morton::new(2);
+    assert_eq!(curve.len(), 16);
+    // The whole top-left quarter comes before anything else.
+    let first_quarter: Vec<Point2d> = curve.by_ref().take(4).collect();
+    assert!(first_quarter.iter().all(|p| p.x < 2 && p.y < 2));
+    assert_eq!(curve.next(), Some(Point2d::new(2, 0)));
+    assert_eq!(curve.next_back(), Some(Point2d::new(3, 3)));
+    assert_eq!(curve.len(), 10);
+
+    assert_eq!(Morton::new(0).collect::<Vec<_>>(), [Point2d::new(0, 0)]);
+}
+
+#[test]
+fn morton_is_a_permutation_of_row_major() {
+    for order in 0..6 {
+        let side = 1 << order;
+        let z: Vec<Point2d> = Morton::new(order).collect();
+        assert_eq!(z.len(), (side * side) as usize);
+        assert_eq!(sorted(z.clone()), row_major(side));
+
+        // Index and point convert back and forth.
+        assert!(z
+            .iter()
+            .enumerate()
+            .all(|(i, &p)| morton_index(p) == i as u64 && morton_point(i as u64) == p));
+    }
+
+    let far = Point2d::new(i32::MAX, 12345);
+    assert_eq!(morton_point(morton_index(far)), far);
+}
+
+#[test]
+fn grid_iter_z_order() {
+    let grid: Grid2D<usize> = (0..15)
+        .map(|i| (Point2d::new(i % 5, i / 5), i as usize))
+        .collect();
+    let cells: Vec<usize> = grid.iter_z_order().map(|(_, &cell)| cell).collect();
+    assert_eq!(cells, [0, 1, 5, 6, 2, 3, 7, 8, 10, 11, 12, 13, 4, 9, 14]);
+
+    let mut sorted_cells = cells;
+    sorted_cells.sort();
+    assert_iter_eq!(sorted_cells.iter(), grid.iter());
+    assert_eq!(Grid2D::new(0, 0, ()).iter_z_order().count(), 0);
+}
+
+#[test]
+fn grid_iter_z_order_skips_the_square_around_a_thin_grid() {
+    // Walking the whole covering square would take 2^40 steps.
+    let row = Grid2D::new(1_000_000, 1, ());
+    let xs = row.iter_z_order().map(|(p, _)| p.x);
+    assert!(xs.eq(0..1_000_000));
+
+    let column = Grid2D::new(1, 1_000_000, ());
+    let ys = column.iter_z_order().map(|(p, _)| p.y);
+    assert!(ys.eq(0..1_000_000));
+
+    // The same order as filtering the covering curve.
+    for (width, height) in [(37, 5), (3, 64), (65, 65), (0, 9)] {
+        let inside = |p: &Point2d| (p.x as usize) < width && (p.y as usize) < height;
+        let filtered = Morton::new(covering_order(width, height)).filter(inside);
+        assert!(
+            morton_within(width, height).eq(filtered),
+            "{width} x {height}"
+        );
+    }
+}
+
+#[test]
+fn hilbert_curve_order_1_and_2() {
+    let points = |xy: &[(i32, i32)]| {
+        xy.iter()
+            .map(|&(x, y)| Point2d::new(x, y))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        HilbertCurve::new(1).collect::<Vec<_>>(),
+        points(&[(0, 0), (0, 1), (1, 1), (1, 0)])
+    );
+    assert_eq!(
+        HilbertCurve::new(2).collect::<Vec<_>>(),
+        points(&[
+            (0, 0),
+            (1, 0),
+            (1, 1),
+            (0, 1), //
+            (0, 2),
+            (0, 3),
+            (1, 3),
+            (1, 2), //
+            (2, 2),
+            (2, 3),
+            (3, 3),
+            (3, 2), //
+            (3, 1),
+            (2, 1),
+            (2, 0),
+            (3, 0), //
+        ])
+    );
+
+    let mut curve = HilbertCurve::new(3);
+    assert_eq!(curve.len(), 64);
+    assert_eq!(curve.next_back(), Some(Point2d::new(7, 0)));
+    assert_eq!(curve.next(), Some(Point2d::new(0, 0)));
+    assert_eq!(curve.len(), 62);
+}
+
+#[test]
+fn hilbert_curve_steps_to_neighbors_and_visits_every_cell_once() {
+    for order in 0..6 {
+        let side = 1 << order;
+        let curve: Vec<Point2d> = HilbertCurve::new(order).collect();
+        assert!(
+            curve.windows(2).all(|w| w[0].manhattan(w[1]) == 1),
+            "order {order}"
+        );
+        assert_eq!(sorted(curve.clone()), row_major(side), "order {order}");
+
+        assert!(curve.iter().enumerate().all(|(i, &p)| {
+            hilbert_index(order, p) == i as u64 && hilbert_point(order, i as u64) == p
+        }));
+    }
+}
+
+#[test]
+#[should_panic(expected = "isn't on a Hilbert curve of order 2")]
+fn hilbert_index_off_the_grid() {
+    hilbert_index(2, Point2d::new(4, 0));
+}
@@ -0,0 +1,436 @@
+//! The mini project as a running game: a map, the worms on it, and a turn
+//! counter. `ticks()` plays the game one turn at a time, and hands out the
+//! state after each turn, so a simulation is just an iterator to consume.
+
+use super::log::{ActionLog, LoggedAction};
+use super::{valid_moves_for_worm, Action, Map, MapCell, Point2d, Worm};
+use std::collections::HashSet;
+use std::fmt;
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct GameState {
+    pub(super) map: Map,
+    pub(super) worms: Vec<Worm>,
+    // The number of turns played so far.
+    pub(super) turn: usize,
+    // Every action `apply` has carried out, oldest first.
+    pub(super) log: ActionLog,
+}
+
+impl GameState {
+    pub(super) fn new(map: Map, worms: Vec<Worm>) -> Self {
+        GameState {
+            map,
+            worms,
+            turn: 0,
+            log: ActionLog::default(),
+        }
+    }
+
+    /// The cells covered by some worm's body.
+    fn occupied_cells(&self) -> HashSet<Point2d> {
+        self.worms
+            .iter()
+            .flat_map(Worm::segments)
+            .copied()
+            .collect()
+    }
+
+    /// What the worm at index `worm` could do this turn. The other worms
+    /// are in its way; its own body is handled by `valid_moves_for_worm`.
+    fn valid_moves(&self, worm: usize) -> Vec<Action> {
+        let others: HashSet<Point2d> = self
+            .worms
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != worm)
+            .flat_map(|(_, other)| other.segments())
+            .copied()
+            .collect();
+        valid_moves_for_worm(&self.worms[worm], &self.map, &others)
+    }
+
+    // Carries out `action` for the worm at index `worm`, without checking
+    // that it is one of its valid moves, or logging it. Digging feeds the
+    // worm, which then grows by one segment over its next move. Returns the
+    // cell the worm's tail left, if any.
+    fn perform(&mut self, worm: usize, action: Action) -> Option<Point2d> {
+        match action {
+            Action::Move(target) => self.worms[worm].move_to(target),
+            Action::Dig(target) => {
+                if let Some(cell) = self.map.cells.get_mut(target) {
+                    *cell = MapCell::Air;
+                }
+                self.worms[worm].length += 1;
+                None
+            }
+        }
+    }
+
+    /// Carries out `action` for the worm at index `worm`, if it is one of
+    /// that worm's valid moves. Otherwise nothing changes, and the error
+    /// says what is wrong with it.
+    pub(super) fn apply(&mut self, worm: usize, action: Action) -> Result<(), InvalidAction> {
+        if worm >= self.worms.len() {
+            return Err(InvalidAction::NoSuchWorm(worm));
+        }
+        if !self.valid_moves(worm).contains(&action) {
+            return Err(self.diagnose(worm, action));
+        }
+        let dropped_tail = self.perform(worm, action);
+        let logged = LoggedAction {
+            turn: self.turn,
+            worm,
+            action,
+        };
+        self.log.record(logged, dropped_tail);
+        Ok(())
+    }
+
+    /// Applies `actions` for the worm at index `worm`, in order. Stops at
+    /// the first invalid action; the ones before it stay applied.
+    fn apply_all<I>(&mut self, worm: usize, actions: I) -> Result<(), InvalidAction>
+    where
+        I: IntoIterator<Item = Action>,
+    {
+        actions
+            .into_iter()
+            .try_for_each(|action| self.apply(worm, action))
+    }
+
+    // Works out why `action` isn't a valid move for `worm`.
+    fn diagnose(&self, worm: usize, action: Action) -> InvalidAction {
+        let (Action::Move(target) | Action::Dig(target)) = action;
+        let head = self.worms[worm].head();
+        if self.map.at(target).is_none() {
+            InvalidAction::OutOfBounds(action)
+        } else if head.chebyshev(target) != 1 {
+            InvalidAction::NotAdjacent(action)
+        } else if self.occupied_cells().contains(&target) {
+            InvalidAction::Occupied(action)
+        } else {
+            InvalidAction::WrongCell(action)
+        }
+    }
+
+    /// Plays one turn: each worm in turn asks `policy` to pick one of its
+    /// valid moves, and the pick takes effect before the next worm chooses.
+    /// A worm with no valid moves, or whose policy returns `None` or an
+    /// invalid action, waits.
+    fn tick<P>(&mut self, policy: &mut P)
+    where
+        P: FnMut(&GameState, usize, &[Action]) -> Option<Action>,
+    {
+        for worm in 0..self.worms.len() {
+            let moves = self.valid_moves(worm);
+            if moves.is_empty() {
+                continue;
+            }
+            if let Some(action) = policy(self, worm, &moves) {
+                let _ = self.apply(worm, action);
+            }
+        }
+        self.turn += 1;
+    }
+
+    /// The states after turn 1, turn 2, and so on, forever. `policy` plays
+    /// every worm, as in `tick`.
+    pub(super) fn ticks<P>(self, policy: P) -> Ticks<P>
+    where
+        P: FnMut(&GameState, usize, &[Action]) -> Option<Action>,
+    {
+        Ticks {
+            state: self,
+            policy,
+        }
+    }
+}
+
+/// Why `GameState::apply` turned an action down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum InvalidAction {
+    NoSuchWorm(usize),
+    /// The target is off the map.
+    OutOfBounds(Action),
+    /// The target isn't next to the worm's head.
+    NotAdjacent(Action),
+    /// A worm, maybe the one acting, is in the way.
+    Occupied(Action),
+    /// A move into dirt or rock, or a dig into anything but dirt.
+    WrongCell(Action),
+}
+
+impl fmt::Display for InvalidAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidAction::NoSuchWorm(worm) => write!(f, "there is no worm {worm}"),
+            InvalidAction::OutOfBounds(action) => write!(f, "{action:?} is off the map"),
+            InvalidAction::NotAdjacent(action) => write!(f, "{action:?} is out of reach"),
+            InvalidAction::Occupied(action) => write!(f, "{action:?} is blocked by a worm"),
+            InvalidAction::WrongCell(action) => match action {
+                Action::Move(_) => write!(f, "{action:?} runs into solid ground"),
+                Action::Dig(_) => write!(f, "{action:?} has nothing to dig"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for InvalidAction {}
+
+pub(super) struct Ticks<P> {
+    state: GameState,
+    policy: P,
+}
+
+impl<P> Iterator for Ticks<P>
+where
+    P: FnMut(&GameState, usize, &[Action]) -> Option<Action>,
+{
+    type Item = GameState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state.tick(&mut self.policy);
+        Some(self.state.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<P> FusedIterator for Ticks<P> where P: FnMut(&GameState, usize, &[Action]) -> Option<Action> {}
+
+// Picks one of the valid moves at random; the same seed always picks the
+// same moves.
+pub(super) fn seeded_policy(
+    seed: u64,
+) -> impl FnMut(&GameState, usize, &[Action]) -> Option<Action> {
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    move |_, _, moves| moves.choose(&mut rng).copied()
+}
+
+/// The game played by three worms making random moves on a cave map, as
+/// pictures: the map after turn 1, turn 2, and so on, forever, each one a
+/// line of text per row. `seed` picks the map, where the worms start and
+/// their moves, so the same seed always plays the same game.
+pub fn simulation(seed: u64) -> impl Iterator<Item = String> {
+    use rand::rngs::StdRng;
+    use rand::seq::IteratorRandom;
+    use rand::SeedableRng;
+
+    let map = Map::generate(seed, 24, 12, 3);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let worms = map
+        .iter_cells()
+        .filter(|&(_, cell)| cell.is_walkable())
+        .choose_multiple(&mut rng, 3)
+        .into_iter()
+        .map(|(start, _)| Worm::new(start, 3))
+        .collect();
+    GameState::new(map, worms)
+        .ticks(seeded_policy(seed))
+        .map(|state| state.render().collect::<Vec<_>>().join("\n"))
+}
+
+#[cfg(test)]
+pub(super) fn sample_state() -> GameState {
+    let worm = |x, y| Worm::new(Point2d::new(x, y), 3);
+    GameState::new(
+        Map::generate(1358, 16, 12, 3),
+        vec![worm(2, 2), worm(13, 9), worm(8, 6)],
+    )
+}
+
+#[test]
+fn ticks_are_deterministic_for_a_seeded_policy() {
+    let a: Vec<GameState> = sample_state().ticks(seeded_policy(42)).take(20).collect();
+    let b: Vec<GameState> = sample_state().ticks(seeded_policy(42)).take(20).collect();
+    assert_eq!(a, b);
+    assert_iter_eq!(a.iter().map(|s| s.turn), 1..=20);
+
+    let c: Vec<GameState> = sample_state().ticks(seeded_policy(43)).take(20).collect();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn ticks_never_put_two_segments_in_one_cell() {
+    for seed in 0..10 {
+        for state in sample_state().ticks(seeded_policy(seed)).take(30) {
+            let segments: usize = state.worms.iter().map(|w| w.segments().len()).sum();
+            assert_eq!(state.occupied_cells().len(), segments);
+            assert!(state
+                .occupied_cells()
+                .iter()
+                .all(|&p| state.map.at(p).is_some()));
+        }
+    }
+}
+
+#[test]
+fn ticks_with_a_greedy_policy() {
+    // Always dig when possible, otherwise take the first move.
+    let greedy = |_: &GameState, _: usize, moves: &[Action]| {
+        let dig = moves.iter().find(|a| matches!(a, Action::Dig(_)));
+        dig.or(moves.first()).copied()
+    };
+    let start = sample_state();
+    let dirt = |state: &GameState| {
+        state
+            .map
+            .iter_cells()
+            .filter(|&(_, cell)| cell == MapCell::Dirt)
+            .count()
+    };
+
+    let states: Vec<GameState> = start.clone().ticks(greedy).take(10).collect();
+    // Every turn, every worm next to dirt digs some of it away.
+    assert!(states.windows(2).all(|w| dirt(&w[1]) <= dirt(&w[0])));
+    assert!(dirt(&states[9]) < dirt(&start));
+
+    // A policy that always waits leaves the game as it was.
+    let mut idle = start.clone().ticks(|_, _, _| None);
+    let state = idle.nth(4).unwrap();
+    assert_eq!(state.turn, 5);
+    assert_eq!((state.map, state.worms), (start.map, start.worms));
+}
+
+#[test]
+fn worms_grow_after_digging() {
+    use super::Grid2D;
+    use MapCell::{Air, Dirt};
+    // . . . #
+    let map = Map {
+        cells: Grid2D::from_rows(vec![vec![Air, Air, Air, Dirt]]),
+    };
+    let mut state = GameState::new(map, vec![Worm::new(Point2d::new(0, 0), 2)]);
+    for x in 1..=2 {
+        state.perform(0, Action::Move(Point2d::new(x, 0)));
+    }
+    assert_eq!(state.worms[0].segments().len(), 2);
+
+    state.perform(0, Action::Dig(Point2d::new(3, 0)));
+    assert_eq!(state.map.at(Point2d::new(3, 0)), Some(Air));
+    assert_eq!(state.worms[0].segments().len(), 2);
+
+    // The next move adds a segment instead of dropping the tail.
+    state.perform(0, Action::Move(Point2d::new(3, 0)));
+    assert!(state.worms[0].segments().copied().eq([
+        Point2d::new(3, 0),
+        Point2d::new(2, 0),
+        Point2d::new(1, 0),
+    ]));
+}
+
+#[cfg(test)]
+fn corridor() -> GameState {
+    use super::Grid2D;
+    use MapCell::{Air, Dirt};
+    // . . . #
+    // # # . .
+    let map = Map {
+        cells: Grid2D::from_rows(vec![vec![Air, Air, Air, Dirt], vec![Dirt, Dirt, Air, Air]]),
+    };
+    GameState::new(
+        map,
+        vec![
+            Worm::new(Point2d::new(0, 0), 2),
+            Worm::new(Point2d::new(3, 1), 2),
+        ],
+    )
+}
+
+#[test]
+fn apply_valid_actions() {
+    let mut state = corridor();
+    assert_eq!(state.apply(0, Action::Move(Point2d::new(1, 0))), Ok(()));
+    assert_eq!(state.apply(0, Action::Dig(Point2d::new(1, 1))), Ok(()));
+    assert_eq!(state.map.at(Point2d::new(1, 1)), Some(MapCell::Air));
+    assert_eq!(state.worms[0].head(), Point2d::new(1, 0));
+    assert_eq!(state.apply(0, Action::Move(Point2d::new(1, 1))), Ok(()));
+    assert_eq!(state.worms[0].head(), Point2d::new(1, 1));
+}
+
+#[test]
+fn apply_rejects_invalid_actions() {
+    let mut state = corridor();
+    let before = state.clone();
+    let at = Point2d::new;
+
+    assert_eq!(
+        state.apply(0, Action::Move(at(-1, 0))),
+        Err(InvalidAction::OutOfBounds(Action::Move(at(-1, 0))))
+    );
+    assert_eq!(
+        state.apply(0, Action::Move(at(2, 0))),
+        Err(InvalidAction::NotAdjacent(Action::Move(at(2, 0))))
+    );
+    assert_eq!(
+        state.apply(0, Action::Move(at(0, 1))),
+        Err(InvalidAction::WrongCell(Action::Move(at(0, 1))))
+    );
+    assert_eq!(
+        state.apply(0, Action::Dig(at(1, 0))),
+        Err(InvalidAction::WrongCell(Action::Dig(at(1, 0))))
+    );
+    assert_eq!(
+        state.apply(2, Action::Move(at(1, 0))),
+        Err(InvalidAction::NoSuchWorm(2))
+    );
+    assert_eq!(state, before);
+
+    // Worm 1 walks up next to worm 0, which can't step onto it.
+    state.apply(1, Action::Move(at(2, 1))).unwrap();
+    state.apply(1, Action::Move(at(1, 0))).unwrap();
+    let err = state.apply(0, Action::Move(at(1, 0))).unwrap_err();
+    assert_eq!(err, InvalidAction::Occupied(Action::Move(at(1, 0))));
+    assert_eq!(
+        err.to_string(),
+        "Move(Point2d { x: 1, y: 0 }) is blocked by a worm"
+    );
+}
+
+#[test]
+fn apply_all_stops_at_the_first_invalid_action() {
+    let at = Point2d::new;
+    let mut state = corridor();
+    let walk = [
+        Action::Move(at(1, 0)),
+        Action::Move(at(2, 0)),
+        Action::Dig(at(3, 0)),
+    ];
+    assert_eq!(state.apply_all(0, walk), Ok(()));
+    assert_eq!(state.worms[0].head(), at(2, 0));
+    assert_eq!(state.map.at(at(3, 0)), Some(MapCell::Air));
+
+    let mut state = corridor();
+    let result = state.apply_all(
+        0,
+        [
+            Action::Move(at(1, 0)),
+            Action::Move(at(3, 0)),
+            Action::Move(at(2, 0)),
+        ],
+    );
+    assert_eq!(
+        result,
+        Err(InvalidAction::NotAdjacent(Action::Move(at(3, 0))))
+    );
+    assert_eq!(state.worms[0].head(), at(1, 0));
+}
+
+#[test]
+fn simulation_draws_a_frame_per_turn() {
+    let frames: Vec<String> = simulation(1422).take(5).collect();
+    assert_eq!(frames, simulation(1422).take(5).collect::<Vec<_>>());
+    for frame in &frames {
+        assert_eq!(frame.lines().count(), 12);
+        assert!(frame.lines().all(|row| row.chars().count() == 24));
+        assert_eq!(frame.matches('@').count(), 3);
+    }
+    assert_ne!(frames[0], frames[4]);
+}
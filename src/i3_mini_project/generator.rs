@@ -0,0 +1,150 @@
+//! Cave maps grown with a cellular automaton. The first generation is random
+//! noise; every later one smooths the one before it, turning a cell into
+//! dirt when most of its neighbors are dirt and into air when most are air.
+//! After a few steps the noise settles into caves.
+
+use super::{Grid2D, Map, MapCell};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::iter::FusedIterator;
+
+/// The chance that a cell of the first generation is dirt.
+const DIRT_CHANCE: f64 = 0.45;
+
+/// Yields generation 0 (the noise), then one smoothing step per `next()`.
+/// Once the caves have settled, it keeps yielding the same map.
+#[derive(Debug, Clone)]
+pub(super) struct MapGenerator {
+    // The generation `next()` will yield.
+    map: Map,
+}
+
+impl MapGenerator {
+    pub(super) fn new(seed: u64, width: usize, height: usize) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rows = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        if rng.gen_bool(DIRT_CHANCE) {
+                            MapCell::Dirt
+                        } else {
+                            MapCell::Air
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        MapGenerator {
+            map: Map {
+                cells: Grid2D::from_rows(rows),
+            },
+        }
+    }
+
+    fn smooth(map: &Map) -> Map {
+        let mut cells = map.cells.clone();
+        for (point, cell) in map.iter_cells() {
+            // The edge of the map counts as dirt, so caves stay closed.
+            let neighbors = map.neighbors(point).collect::<Vec<_>>();
+            let dirt = 8 - neighbors.len()
+                + neighbors
+                    .iter()
+                    .filter(|&&(_, c)| c == MapCell::Dirt)
+                    .count();
+            let next = match dirt {
+                0..=3 => MapCell::Air,
+                4 => cell,
+                _ => MapCell::Dirt,
+            };
+            if let Some(target) = cells.get_mut(point) {
+                *target = next;
+            }
+        }
+        Map { cells }
+    }
+}
+
+impl Iterator for MapGenerator {
+    type Item = Map;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = Self::smooth(&self.map);
+        Some(std::mem::replace(&mut self.map, next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for MapGenerator {}
+
+impl Map {
+    /// A `width` x `height` cave map after `steps` smoothing steps of the
+    /// noise picked by `seed`. The same arguments always give the same map.
+    pub(super) fn generate(seed: u64, width: usize, height: usize, steps: usize) -> Map {
+        MapGenerator::new(seed, width, height)
+            .nth(steps)
+            .expect("the generator never ends")
+    }
+}
+
+#[cfg(test)]
+fn air_ratio(map: &Map) -> f64 {
+    let air = map
+        .iter_cells()
+        .filter(|&(_, cell)| cell == MapCell::Air)
+        .count();
+    air as f64 / (map.cells.width() * map.cells.height()) as f64
+}
+
+#[cfg(test)]
+fn changed_cells(a: &Map, b: &Map) -> usize {
+    a.cells
+        .iter()
+        .zip(b.cells.iter())
+        .filter(|(x, y)| x != y)
+        .count()
+}
+
+#[test]
+fn generator_is_reproducible() {
+    let a: Vec<Map> = MapGenerator::new(7, 30, 20).take(5).collect();
+    let b: Vec<Map> = MapGenerator::new(7, 30, 20).take(5).collect();
+    assert_eq!(a, b);
+    assert_eq!(Map::generate(7, 30, 20, 4), a[4]);
+
+    let other = Map::generate(8, 30, 20, 4);
+    assert_ne!(other, a[4]);
+    assert_eq!((other.cells.width(), other.cells.height()), (30, 20));
+}
+
+#[test]
+fn generator_starts_from_noise() {
+    let noise = MapGenerator::new(1357, 100, 100).next().unwrap();
+    // Roughly `DIRT_CHANCE` of the cells are dirt.
+    assert!((air_ratio(&noise) - (1.0 - DIRT_CHANCE)).abs() < 0.03);
+}
+
+#[test]
+fn generator_settles_into_equilibrium() {
+    for seed in 0..5 {
+        let generations: Vec<Map> = MapGenerator::new(seed, 60, 40).take(30).collect();
+        let changes: Vec<usize> = generations
+            .windows(2)
+            .map(|w| changed_cells(&w[0], &w[1]))
+            .collect();
+
+        // Each step changes fewer cells than the first one did, and the map
+        // stops changing altogether.
+        assert!(changes[1..].iter().all(|&c| c < changes[0]), "seed {seed}");
+        assert_eq!(changes.last(), Some(&0), "seed {seed}");
+
+        // The air ratio moves less and less between generations.
+        let ratios: Vec<f64> = generations.iter().map(air_ratio).collect();
+        let early = (ratios[1] - ratios[0]).abs();
+        let late = (ratios[29] - ratios[28]).abs();
+        assert!(late <= early, "seed {seed}");
+    }
+}
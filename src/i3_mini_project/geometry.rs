@@ -0,0 +1,505 @@
+//! Iterators over the grid cells that make up simple shapes.
+
+use super::{Map, MapCell, Point2d};
+use std::iter::FusedIterator;
+
+/// The cells on the segment from `from` to `to`, both included, as drawn by
+/// Bresenham's algorithm: each step moves one cell along the longer axis,
+/// and one along the shorter axis whenever the error term says so.
+pub fn line(from: Point2d, to: Point2d) -> Line {
+    let (dx, dy) = ((to.x - from.x).abs(), -(to.y - from.y).abs());
+    Line {
+        next: Some(from),
+        to,
+        dx,
+        dy,
+        step: Point2d::new((to.x - from.x).signum(), (to.y - from.y).signum()),
+        err: dx + dy,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Line {
+    next: Option<Point2d>,
+    to: Point2d,
+    // `dx` is the distance along x, `dy` minus the distance along y.
+    dx: i32,
+    dy: i32,
+    step: Point2d,
+    err: i32,
+}
+
+impl Iterator for Line {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.next?;
+        if point == self.to {
+            self.next = None;
+            return Some(point);
+        }
+        let (mut x, mut y) = (point.x, point.y);
+        let e2 = 2 * self.err;
+        if e2 >= self.dy {
+            self.err += self.dy;
+            x += self.step.x;
+        }
+        if e2 <= self.dx {
+            self.err += self.dx;
+            y += self.step.y;
+        }
+        self.next = Some(Point2d::new(x, y));
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every step moves one cell along the longer axis.
+        let len = self.next.map_or(0, |p| {
+            let remaining = p.chebyshev(self.to);
+            remaining as usize + 1
+        });
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Line {}
+
+impl FusedIterator for Line {}
+
+/// The cells around `center` in an outward square spiral: `center`, then
+/// the eight cells at distance 1, then the sixteen at distance 2, and so on.
+/// Each ring starts one step east of the cell the previous ring ended on and
+/// runs counter-clockwise (north first, since `y` grows downwards), so
+/// numbering the cells from 1 gives Ulam's spiral. The spiral never ends.
+pub fn spiral_from(center: Point2d) -> Spiral {
+    Spiral {
+        center,
+        ring: 0,
+        index: 0,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Spiral {
+    center: Point2d,
+    // The distance of the current ring from the center, and the position of
+    // the next cell along that ring.
+    ring: i32,
+    index: i32,
+}
+
+impl Iterator for Spiral {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let k = self.ring;
+        let offset = if k == 0 {
+            Point2d::new(0, 0)
+        } else {
+            // Four sides of `2 * k` cells each.
+            let j = self.index % (2 * k);
+            match self.index / (2 * k) {
+                0 => Point2d::new(k, k - 1 - j),
+                1 => Point2d::new(k - 1 - j, -k),
+                2 => Point2d::new(-k, -k + 1 + j),
+                _ => Point2d::new(-k + 1 + j, k),
+            }
+        };
+        self.index += 1;
+        if self.index >= (8 * k).max(1) {
+            self.ring += 1;
+            self.index = 0;
+        }
+        Some(self.center + offset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for Spiral {}
+
+/// A rectangle of cells, corners included.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point2d,
+    pub max: Point2d,
+}
+
+impl Rect {
+    /// The rectangle with `a` and `b` as opposite corners, in any order.
+    pub fn new(a: Point2d, b: Point2d) -> Rect {
+        Rect {
+            min: Point2d::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point2d::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y) as usize + 1
+    }
+
+    pub fn contains(&self, p: Point2d) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// The cells of the rectangle, row by row.
+    pub fn points(&self) -> RectPoints {
+        RectPoints {
+            rect: *self,
+            front: 0,
+            back: self.width() * self.height(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RectPoints {
+    rect: Rect,
+    // Row-major indices `front..back` are still to come.
+    front: usize,
+    back: usize,
+}
+
+impl RectPoints {
+    fn at(&self, index: usize) -> Point2d {
+        let width = self.rect.width();
+        Point2d::new(
+            self.rect.min.x + (index % width) as i32,
+            self.rect.min.y + (index / width) as i32,
+        )
+    }
+}
+
+impl Iterator for RectPoints {
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for RectPoints {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.at(self.back))
+    }
+}
+
+impl ExactSizeIterator for RectPoints {}
+
+impl FusedIterator for RectPoints {}
+
+/// Ways to measure how far apart two cells are.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    /// Steps along the axes only: a diamond.
+    Manhattan,
+    /// Steps in any of the eight directions, like a worm: a square.
+    Chebyshev,
+    /// Straight-line distance: a disc.
+    Euclidean,
+}
+
+impl Metric {
+    /// Whether `p` is at most `radius` away from `center`.
+    pub fn within(self, center: Point2d, p: Point2d, radius: i32) -> bool {
+        match self {
+            Metric::Manhattan => center.manhattan(p) <= radius,
+            Metric::Chebyshev => center.chebyshev(p) <= radius,
+            Metric::Euclidean => {
+                let d = p - center;
+                d.x * d.x + d.y * d.y <= radius * radius
+            }
+        }
+    }
+}
+
+/// The cells at most `radius` away from `center`, row by row. Every metric
+/// fits inside the Chebyshev square, so that square is all that is walked.
+pub fn points_within(
+    center: Point2d,
+    radius: i32,
+    metric: Metric,
+) -> impl Iterator<Item = Point2d> + Clone {
+    let r = radius.max(0);
+    let square = Rect::new(
+        Point2d::new(center.x - r, center.y - r),
+        Point2d::new(center.x + r, center.y + r),
+    );
+    let mut points = square.points();
+    if radius < 0 {
+        // Nothing is a negative distance away.
+        points.front = points.back;
+    }
+    points.filter(move |&p| metric.within(center, p, radius))
+}
+
+impl Map {
+    /// The cells of the map caught in an area of effect around `center`.
+    pub(super) fn cells_within(
+        &self,
+        center: Point2d,
+        radius: i32,
+        metric: Metric,
+    ) -> impl Iterator<Item = (Point2d, MapCell)> + '_ {
+        points_within(center, radius, metric).filter_map(|p| self.at(p).map(|cell| (p, cell)))
+    }
+
+    /// The cells of the map in a spiral around `center` (see `spiral_from`),
+    /// skipping those that fall off the map. Unlike the open spiral, this
+    /// one ends once it has covered the whole map.
+    pub(super) fn spiral_from(&self, center: Point2d) -> impl Iterator<Item = Point2d> + '_ {
+        let (right, bottom) = (
+            self.cells.width() as i32 - 1,
+            self.cells.height() as i32 - 1,
+        );
+        let rings = [center.x, right - center.x, center.y, bottom - center.y]
+            .into_iter()
+            .map(i32::abs)
+            .max()
+            .unwrap();
+        let side = 2 * rings as usize + 1;
+        spiral_from(center)
+            .take(side * side)
+            .filter(|&p| self.cells.contains(p))
+    }
+
+    /// Whether nothing blocks the view from `a` to `b`: every cell on the
+    /// line between them, ends included, is on the map and isn't dirt or
+    /// rock. The walk stops at the first cell that blocks it.
+    pub(super) fn has_line_of_sight(&self, a: Point2d, b: Point2d) -> bool {
+        line(a, b).all(
+            |p| matches!(self.at(p), Some(cell) if !matches!(cell, MapCell::Dirt | MapCell::Rock)),
+        )
+    }
+}
+
+#[cfg(test)]
+fn points(coords: &[(i32, i32)]) -> Vec<Point2d> {
+    coords.iter().map(|&(x, y)| Point2d::new(x, y)).collect()
+}
+
+#[test]
+fn line_horizontal_and_vertical() {
+    let east: Vec<_> = line(Point2d::new(1, 2), Point2d::new(4, 2)).collect();
+    assert_eq!(east, points(&[(1, 2), (2, 2), (3, 2), (4, 2)]));
+
+    let north: Vec<_> = line(Point2d::new(0, 3), Point2d::new(0, 0)).collect();
+    assert_eq!(north, points(&[(0, 3), (0, 2), (0, 1), (0, 0)]));
+
+    let single: Vec<_> = line(Point2d::new(5, 5), Point2d::new(5, 5)).collect();
+    assert_eq!(single, points(&[(5, 5)]));
+}
+
+#[test]
+fn line_diagonal() {
+    let down: Vec<_> = line(Point2d::new(0, 0), Point2d::new(3, 3)).collect();
+    assert_eq!(down, points(&[(0, 0), (1, 1), (2, 2), (3, 3)]));
+
+    let up: Vec<_> = line(Point2d::new(2, 0), Point2d::new(0, 2)).collect();
+    assert_eq!(up, points(&[(2, 0), (1, 1), (0, 2)]));
+}
+
+#[test]
+fn line_shallow_and_steep_slopes() {
+    let shallow: Vec<_> = line(Point2d::new(0, 0), Point2d::new(6, 2)).collect();
+    assert_eq!(
+        shallow,
+        points(&[(0, 0), (1, 0), (2, 1), (3, 1), (4, 1), (5, 2), (6, 2)])
+    );
+
+    let steep: Vec<_> = line(Point2d::new(0, 0), Point2d::new(-1, -4)).collect();
+    assert_eq!(steep.len(), 5);
+    assert_eq!(steep.first(), Some(&Point2d::new(0, 0)));
+    assert_eq!(steep.last(), Some(&Point2d::new(-1, -4)));
+    // Consecutive cells always touch, at least diagonally.
+    assert!(steep
+        .windows(2)
+        .all(|w| (w[1].x - w[0].x).abs() <= 1 && w[1].y - w[0].y == -1));
+}
+
+#[test]
+fn line_is_exact_size() {
+    let mut cells = line(Point2d::new(0, 0), Point2d::new(7, -3));
+    assert_eq!(cells.len(), 8);
+    cells.next();
+    cells.next();
+    assert_eq!(cells.len(), 6);
+    assert_eq!(cells.by_ref().count(), 6);
+    assert_eq!(cells.len(), 0);
+    assert_eq!(cells.next(), None);
+}
+
+#[test]
+fn map_line_of_sight() {
+    use super::Grid2D;
+    use MapCell::{Air, Dirt};
+    // . . . . .
+    // . . # . .
+    // . . . . .
+    let map = Map {
+        cells: Grid2D::from_rows(vec![
+            vec![Air, Air, Air, Air, Air],
+            vec![Air, Air, Dirt, Air, Air],
+            vec![Air, Air, Air, Air, Air],
+        ]),
+    };
+
+    assert!(map.has_line_of_sight(Point2d::new(0, 0), Point2d::new(4, 0)));
+    assert!(!map.has_line_of_sight(Point2d::new(0, 1), Point2d::new(4, 1)));
+    assert!(!map.has_line_of_sight(Point2d::new(2, 0), Point2d::new(2, 2)));
+    assert!(!map.has_line_of_sight(Point2d::new(1, 0), Point2d::new(3, 2)));
+    assert!(map.has_line_of_sight(Point2d::new(3, 0), Point2d::new(4, 2)));
+    assert!(!map.has_line_of_sight(Point2d::new(0, 2), Point2d::new(4, 0)));
+    // Off the map, nothing can be seen.
+    assert!(!map.has_line_of_sight(Point2d::new(0, 0), Point2d::new(5, 0)));
+}
+
+#[test]
+fn spiral_first_rings() {
+    let first: Vec<_> = spiral_from(Point2d::new(0, 0)).take(9).collect();
+    assert_eq!(
+        first,
+        points(&[
+            (0, 0),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ])
+    );
+
+    let second: Vec<_> = spiral_from(Point2d::new(10, 20)).skip(9).take(16).collect();
+    assert_eq!(second[0], Point2d::new(12, 21));
+    assert_eq!(second[3], Point2d::new(12, 18));
+    assert_eq!(second[15], Point2d::new(12, 22));
+    assert!(second
+        .iter()
+        .all(|p| (p.x - 10).abs().max((p.y - 20).abs()) == 2));
+}
+
+#[test]
+fn spiral_steps_one_cell_at_a_time() {
+    let cells: Vec<_> = spiral_from(Point2d::new(0, 0)).take(200).collect();
+    // Each cell shares a side with the one before it, even when the spiral
+    // moves out to the next ring.
+    assert!(cells.windows(2).all(|w| {
+        let (dx, dy) = ((w[1].x - w[0].x).abs(), (w[1].y - w[0].y).abs());
+        dx + dy == 1
+    }));
+
+    let mut unique = cells.clone();
+    unique.sort_by_key(|p| (p.x, p.y));
+    unique.dedup();
+    assert_eq!(unique.len(), 200);
+}
+
+#[test]
+fn map_spiral_is_clipped_to_the_map() {
+    use super::Grid2D;
+    let map = Map {
+        cells: Grid2D::new(4, 3, MapCell::Air),
+    };
+
+    let cells: Vec<_> = map.spiral_from(Point2d::new(0, 0)).collect();
+    assert_eq!(cells.len(), 12);
+    assert_eq!(cells[..4], points(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+    assert!(cells.iter().all(|&p| map.at(p).is_some()));
+
+    assert_eq!(map.spiral_from(Point2d::new(3, 1)).count(), 12);
+    assert_eq!(map.spiral_from(Point2d::new(-5, 9)).count(), 12);
+}
+
+#[test]
+fn rect_points_row_major() {
+    let rect = Rect::new(Point2d::new(3, 1), Point2d::new(1, 2));
+    assert_eq!((rect.width(), rect.height()), (3, 2));
+
+    let mut cells = rect.points();
+    assert_eq!(cells.len(), 6);
+    assert_eq!(
+        cells.clone().collect::<Vec<_>>(),
+        points(&[(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2)])
+    );
+    assert_eq!(cells.next_back(), Some(Point2d::new(3, 2)));
+    assert_eq!(cells.next(), Some(Point2d::new(1, 1)));
+    assert_eq!(cells.len(), 4);
+
+    assert!(rect.points().all(|p| rect.contains(p)));
+    assert!(!rect.contains(Point2d::new(0, 1)));
+    assert!(!rect.contains(Point2d::new(2, 3)));
+
+    let single = Rect::new(Point2d::new(-2, -2), Point2d::new(-2, -2));
+    assert_eq!(single.points().collect::<Vec<_>>(), points(&[(-2, -2)]));
+}
+
+#[test]
+fn points_within_each_metric() {
+    let center = Point2d::new(10, -4);
+    let count = |radius, metric| points_within(center, radius, metric).count();
+
+    for r in 0..6 {
+        // A diamond, a square, and (for r = 0..=5) the Gauss circle counts.
+        assert_eq!(
+            count(r, Metric::Manhattan),
+            (2 * r * r + 2 * r + 1) as usize
+        );
+        assert_eq!(
+            count(r, Metric::Chebyshev),
+            ((2 * r + 1) * (2 * r + 1)) as usize
+        );
+    }
+    let gauss: Vec<usize> = (0..6).map(|r| count(r, Metric::Euclidean)).collect();
+    assert_eq!(gauss, [1, 5, 13, 29, 49, 81]);
+    assert_eq!(count(-1, Metric::Chebyshev), 0);
+
+    let disc: Vec<Point2d> = points_within(center, 2, Metric::Euclidean).collect();
+    assert!(disc.contains(&Point2d::new(11, -3)));
+    assert!(disc.contains(&Point2d::new(10, -2)));
+    assert!(!disc.contains(&Point2d::new(12, -2)));
+    assert!(disc.iter().all(|&p| Metric::Euclidean.within(center, p, 2)));
+}
+
+#[test]
+fn map_area_of_effect() {
+    let map: Map = ["....", ".#..", "...."].into_iter().collect();
+    // Clipped to the map, and reporting what is in each cell.
+    let blast: Vec<(Point2d, MapCell)> = map
+        .cells_within(Point2d::new(0, 0), 1, Metric::Manhattan)
+        .collect();
+    assert_eq!(
+        blast,
+        [
+            (Point2d::new(0, 0), MapCell::Air),
+            (Point2d::new(1, 0), MapCell::Air),
+            (Point2d::new(0, 1), MapCell::Air),
+        ]
+    );
+    let dirt_hit = map
+        .cells_within(Point2d::new(2, 2), 1, Metric::Chebyshev)
+        .filter(|&(_, cell)| cell == MapCell::Dirt)
+        .count();
+    assert_eq!(dirt_hit, 1);
+}
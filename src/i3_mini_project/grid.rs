@@ -0,0 +1,737 @@
+//! `Grid2D<T>` stores a `width` x `height` grid in one flat `Vec<T>`, row by
+//! row, and hands out iterators over its rows, columns, and cells.
+
+use super::Point2d;
+use std::iter::FusedIterator;
+use std::slice;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    // Row-major: the cell at (x, y) lives at `y * width + x`.
+    cells: Vec<T>,
+}
+
+impl<T> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid2D {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds a grid from a list of rows, the first row being `y == 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows don't all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "all rows must have the same length"
+        );
+        Grid2D {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point2d) -> bool {
+        self.index(point).is_some()
+    }
+
+    pub fn get(&self, point: Point2d) -> Option<&T> {
+        self.index(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point2d) -> Option<&mut T> {
+        self.index(point).map(|i| &mut self.cells[i])
+    }
+
+    /// All cells in row-major order.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Each row as a slice, top to bottom.
+    pub fn rows(&self) -> Rows<'_, T> {
+        Rows {
+            cells: &self.cells,
+            width: self.width,
+            remaining: self.height,
+        }
+    }
+
+    /// Each column as an iterator, left to right. Columns aren't contiguous
+    /// in memory, so they're walked with a stride of `width`.
+    pub fn columns(&self) -> Columns<'_, T> {
+        Columns { grid: self, x: 0 }
+    }
+
+    /// Each top-left to bottom-right diagonal (constant `x - y`), starting
+    /// with the bottom-left corner and ending with the top-right corner.
+    /// Each diagonal is walked top to bottom with a stride of `width + 1`.
+    pub fn diagonals(&self) -> Diagonals<'_, T> {
+        Diagonals {
+            grid: self,
+            front: 0,
+            back: self.line_count(),
+        }
+    }
+
+    /// Each top-right to bottom-left diagonal (constant `x + y`), starting
+    /// with the top-left corner and ending with the bottom-right corner.
+    /// Each one is walked top to bottom with a stride of `width - 1`.
+    pub fn anti_diagonals(&self) -> AntiDiagonals<'_, T> {
+        AntiDiagonals {
+            grid: self,
+            front: 0,
+            back: self.line_count(),
+        }
+    }
+
+    /// A view of the grid with rows and columns swapped. Nothing is copied:
+    /// the view translates every access back into the original storage.
+    pub fn transposed(&self) -> Transposed<'_, T> {
+        Transposed { grid: self }
+    }
+
+    /// Every cell with its coordinates, in row-major order.
+    pub fn iter_with_coords(&self) -> impl Iterator<Item = (Point2d, &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point(i), cell))
+    }
+
+    // Number of diagonals (or anti-diagonals).
+    fn line_count(&self) -> usize {
+        if self.cells.is_empty() {
+            0
+        } else {
+            self.width + self.height - 1
+        }
+    }
+
+    // The `n`th diagonal, counting from the bottom-left corner.
+    fn diagonal(&self, n: usize) -> Strided<'_, T> {
+        let (x, y) = match n.checked_sub(self.height - 1) {
+            None => (0, self.height - 1 - n),
+            Some(x) => (x, 0),
+        };
+        let len = (self.width - x).min(self.height - y);
+        Strided::new(&self.cells, y * self.width + x, self.width + 1, len)
+    }
+
+    // The `n`th anti-diagonal, counting from the top-left corner.
+    fn anti_diagonal(&self, n: usize) -> Strided<'_, T> {
+        let y = n.saturating_sub(self.width - 1);
+        let x = n - y;
+        let len = (x + 1).min(self.height - y);
+        // A one-column grid only has one-cell anti-diagonals, so the stride
+        // of 0 is never actually taken.
+        Strided::new(&self.cells, y * self.width + x, self.width - 1, len)
+    }
+
+    fn index(&self, point: Point2d) -> Option<usize> {
+        let x = usize::try_from(point.x).ok()?;
+        let y = usize::try_from(point.y).ok()?;
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    fn point(&self, index: usize) -> Point2d {
+        Point2d::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+}
+
+/// Builds the smallest grid, anchored at the origin, that holds every
+/// point. Cells that aren't mentioned get `T::default()`; if a point is
+/// mentioned twice, the last value wins.
+///
+/// # Panics
+///
+/// Panics if a point has a negative coordinate.
+impl<T: Default> FromIterator<(Point2d, T)> for Grid2D<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2d, T)>>(iter: I) -> Self {
+        let entries: Vec<(Point2d, T)> = iter.into_iter().collect();
+        assert!(
+            entries.iter().all(|(p, _)| p.x >= 0 && p.y >= 0),
+            "grid coordinates must not be negative"
+        );
+
+        let width = entries.iter().map(|(p, _)| p.x as usize + 1).max();
+        let height = entries.iter().map(|(p, _)| p.y as usize + 1).max();
+        let (width, height) = (width.unwrap_or(0), height.unwrap_or(0));
+
+        let mut grid = Grid2D {
+            width,
+            height,
+            cells: std::iter::repeat_with(T::default)
+                .take(width * height)
+                .collect(),
+        };
+        for (point, value) in entries {
+            *grid.get_mut(point).unwrap() = value;
+        }
+        grid
+    }
+}
+
+/// A grid is written as its rows, top to bottom, each an array of cells.
+/// A grid with no rows has no width either, so reading back a `w` x `0`
+/// grid gives a `0` x `0` one.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Grid2D<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Grid2D<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().find(|row| row.len() != width) {
+            return Err(serde::de::Error::invalid_length(
+                row.len(),
+                &"rows as long as the first",
+            ));
+        }
+        Ok(Grid2D::from_rows(rows))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Grid2D<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Rows<'a, T> {
+    // The rows that haven't been yielded yet.
+    cells: &'a [T],
+    width: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (row, rest) = self.cells.split_at(self.width);
+        self.cells = rest;
+        self.remaining -= 1;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (rest, row) = self.cells.split_at(self.cells.len() - self.width);
+        self.cells = rest;
+        self.remaining -= 1;
+        Some(row)
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+impl<T> FusedIterator for Rows<'_, T> {}
+
+pub struct Columns<'a, T> {
+    grid: &'a Grid2D<T>,
+    x: usize,
+}
+
+impl<'a, T> Iterator for Columns<'a, T> {
+    type Item = Strided<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.x == self.grid.width {
+            return None;
+        }
+        self.x += 1;
+        let column = Strided::new(
+            &self.grid.cells,
+            self.x - 1,
+            self.grid.width,
+            self.grid.height,
+        );
+        Some(column)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.grid.width - self.x;
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Columns<'_, T> {}
+
+impl<T> FusedIterator for Columns<'_, T> {}
+
+pub struct Diagonals<'a, T> {
+    grid: &'a Grid2D<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for Diagonals<'a, T> {
+    type Item = Strided<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.grid.diagonal(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Diagonals<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.grid.diagonal(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Diagonals<'_, T> {}
+
+impl<T> FusedIterator for Diagonals<'_, T> {}
+
+pub struct AntiDiagonals<'a, T> {
+    grid: &'a Grid2D<T>,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iterator for AntiDiagonals<'a, T> {
+    type Item = Strided<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.grid.anti_diagonal(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for AntiDiagonals<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.grid.anti_diagonal(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for AntiDiagonals<'_, T> {}
+
+impl<T> FusedIterator for AntiDiagonals<'_, T> {}
+
+/// A transposed view of a `Grid2D`: the cell at `(x, y)` is the original's
+/// cell at `(y, x)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Transposed<'a, T> {
+    grid: &'a Grid2D<T>,
+}
+
+impl<'a, T> Transposed<'a, T> {
+    pub fn width(&self) -> usize {
+        self.grid.height()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn get(&self, point: Point2d) -> Option<&'a T> {
+        self.grid.get(Point2d::new(point.y, point.x))
+    }
+
+    /// The view's rows are the original's columns.
+    pub fn rows(&self) -> Columns<'a, T> {
+        self.grid.columns()
+    }
+
+    /// The view's columns are the original's rows.
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = Strided<'a, T>> + 'a {
+        let width = self.grid.width;
+        let cells = &self.grid.cells[..];
+        (0..self.grid.height).map(move |y| Strided::new(cells, y * width, 1, width))
+    }
+
+    /// All cells of the view in row-major order, which is column-major order
+    /// in the original storage.
+    pub fn iter(&self) -> TransposedIter<'a, T> {
+        TransposedIter {
+            grid: self.grid,
+            front: 0,
+            back: self.grid.cells.len(),
+        }
+    }
+
+    /// Copies the view into a grid of its own.
+    pub fn to_grid(&self) -> Grid2D<T>
+    where
+        T: Clone,
+    {
+        Grid2D {
+            width: self.width(),
+            height: self.height(),
+            cells: self.iter().cloned().collect(),
+        }
+    }
+}
+
+pub struct TransposedIter<'a, T> {
+    grid: &'a Grid2D<T>,
+    // Positions `front..back` of the view, in its row-major order.
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> TransposedIter<'a, T> {
+    fn at(&self, position: usize) -> &'a T {
+        // Position `i` of the view is at (x, y) = (i % h, i / h) in the
+        // view, i.e. at (i / h, i % h) in the original.
+        let h = self.grid.height;
+        &self.grid.cells[(position % h) * self.grid.width + position / h]
+    }
+}
+
+impl<'a, T> Iterator for TransposedIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for TransposedIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.at(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for TransposedIter<'_, T> {}
+
+impl<T> FusedIterator for TransposedIter<'_, T> {}
+
+/// Walks `len` items of a slice, starting at `start` and jumping `stride`
+/// items at a time.
+#[derive(Debug, Clone)]
+pub struct Strided<'a, T> {
+    data: &'a [T],
+    start: usize,
+    stride: usize,
+    // Steps `front..back` from `start` are still to be yielded.
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Strided<'a, T> {
+    pub(crate) fn new(data: &'a [T], start: usize, stride: usize, len: usize) -> Self {
+        Strided {
+            data,
+            start,
+            stride,
+            front: 0,
+            back: len,
+        }
+    }
+
+    fn at(&self, step: usize) -> &'a T {
+        &self.data[self.start + step * self.stride]
+    }
+}
+
+impl<'a, T> Iterator for Strided<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Strided<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.at(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Strided<'_, T> {}
+
+impl<T> FusedIterator for Strided<'_, T> {}
+
+#[cfg(test)]
+fn sample() -> Grid2D<u32> {
+    // 3 wide, 2 high.
+    Grid2D::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])
+}
+
+#[test]
+fn grid_get() {
+    let grid = sample();
+    assert_eq!(grid.get(Point2d::new(0, 0)), Some(&1));
+    assert_eq!(grid.get(Point2d::new(2, 1)), Some(&6));
+    assert_eq!(grid.get(Point2d::new(3, 0)), None);
+    assert_eq!(grid.get(Point2d::new(0, 2)), None);
+    assert_eq!(grid.get(Point2d::new(-1, 0)), None);
+}
+
+#[test]
+fn grid_rows() {
+    let grid = sample();
+    let rows: Vec<&[u32]> = grid.rows().collect();
+    assert_eq!(rows, [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(grid.rows().next_back(), Some(&[4, 5, 6][..]));
+    assert_eq!(grid.rows().len(), 2);
+}
+
+#[test]
+fn grid_columns() {
+    let grid = sample();
+    let columns: Vec<Vec<u32>> = grid.columns().map(|c| c.copied().collect()).collect();
+    assert_eq!(columns, [[1, 4], [2, 5], [3, 6]]);
+
+    let last_column: Vec<_> = grid.columns().last().unwrap().rev().collect();
+    assert_eq!(last_column, [&6, &3]);
+}
+
+#[test]
+fn grid_iter_with_coords() {
+    let grid = sample();
+    let cells: Vec<_> = grid
+        .iter_with_coords()
+        .map(|(p, &v)| (p.x, p.y, v))
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            (0, 0, 1),
+            (1, 0, 2),
+            (2, 0, 3),
+            (0, 1, 4),
+            (1, 1, 5),
+            (2, 1, 6)
+        ]
+    );
+}
+
+#[test]
+fn grid_from_iterator_of_points() {
+    let grid: Grid2D<char> = [(Point2d::new(2, 0), 'a'), (Point2d::new(0, 1), 'b')]
+        .into_iter()
+        .collect();
+
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(
+        grid.rows()
+            .map(|r| r.iter().collect::<String>())
+            .collect::<Vec<_>>(),
+        ["\0\0a", "b\0\0"]
+    );
+
+    // Round trip.
+    let copy: Grid2D<u32> = sample().iter_with_coords().map(|(p, &v)| (p, v)).collect();
+    assert_eq!(copy, sample());
+}
+
+#[test]
+fn grid_empty() {
+    let grid: Grid2D<u8> = std::iter::empty().collect();
+    assert_eq!(grid.rows().count(), 0);
+    assert_eq!(grid.columns().count(), 0);
+    assert_eq!(grid.iter_with_coords().count(), 0);
+
+    let zero_width = Grid2D::from_rows(vec![Vec::<u8>::new(), Vec::new()]);
+    assert_eq!(zero_width.rows().collect::<Vec<_>>(), [[], []]);
+    assert_eq!(zero_width.columns().count(), 0);
+}
+
+#[cfg(test)]
+fn collect_lines<'a>(lines: impl Iterator<Item = Strided<'a, u32>>) -> Vec<Vec<u32>> {
+    lines.map(|line| line.copied().collect()).collect()
+}
+
+#[test]
+fn grid_diagonals_on_wide_grid() {
+    // 1 2 3
+    // 4 5 6
+    let grid = sample();
+    assert_eq!(
+        collect_lines(grid.diagonals()),
+        [vec![4], vec![1, 5], vec![2, 6], vec![3]]
+    );
+    assert_eq!(
+        collect_lines(grid.anti_diagonals()),
+        [vec![1], vec![2, 4], vec![3, 5], vec![6]]
+    );
+}
+
+#[test]
+fn grid_diagonals_on_tall_grid() {
+    // 1 2
+    // 3 4
+    // 5 6
+    let grid = Grid2D::from_rows(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+    assert_eq!(
+        collect_lines(grid.diagonals()),
+        [vec![5], vec![3, 6], vec![1, 4], vec![2]]
+    );
+    assert_eq!(
+        collect_lines(grid.anti_diagonals().rev()),
+        [vec![6], vec![4, 5], vec![2, 3], vec![1]]
+    );
+}
+
+#[test]
+fn grid_diagonals_on_single_row_and_column() {
+    let row = Grid2D::from_rows(vec![vec![1, 2, 3]]);
+    assert_eq!(collect_lines(row.diagonals()), [[1], [2], [3]]);
+    assert_eq!(collect_lines(row.anti_diagonals()), [[1], [2], [3]]);
+
+    let column = Grid2D::from_rows(vec![vec![1], vec![2], vec![3]]);
+    assert_eq!(collect_lines(column.diagonals()), [[3], [2], [1]]);
+    assert_eq!(collect_lines(column.anti_diagonals()), [[1], [2], [3]]);
+
+    let empty: Grid2D<u32> = Grid2D::from_rows(vec![]);
+    assert_eq!(empty.diagonals().count(), 0);
+    assert_eq!(empty.anti_diagonals().count(), 0);
+}
+
+#[test]
+fn grid_diagonals_cover_every_cell_once() {
+    let grid: Grid2D<u32> = Grid2D::from_rows(
+        (0..4)
+            .map(|y| (0..7).map(|x| y * 7 + x).collect())
+            .collect(),
+    );
+
+    let mut cells: Vec<u32> = grid.diagonals().flatten().copied().collect();
+    cells.sort();
+    assert_iter_eq!(cells.into_iter(), 0..28);
+
+    let mut cells: Vec<u32> = grid.anti_diagonals().flatten().copied().collect();
+    cells.sort();
+    assert_iter_eq!(cells.into_iter(), 0..28);
+}
+
+#[test]
+fn grid_transposed_view() {
+    // 1 2 3      1 4
+    // 4 5 6  =>  2 5
+    //            3 6
+    let grid = sample();
+    let view = grid.transposed();
+
+    assert_eq!((view.width(), view.height()), (2, 3));
+    assert_eq!(view.get(Point2d::new(1, 0)), Some(&4));
+    assert_eq!(view.get(Point2d::new(0, 2)), Some(&3));
+    assert_eq!(view.get(Point2d::new(2, 0)), None);
+
+    assert_eq!(collect_lines(view.rows()), [[1, 4], [2, 5], [3, 6]]);
+    assert_eq!(collect_lines(view.columns()), [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(view.iter().copied().collect::<Vec<_>>(), [1, 4, 2, 5, 3, 6]);
+    assert_eq!(
+        view.iter().rev().copied().collect::<Vec<_>>(),
+        [6, 3, 5, 2, 4, 1]
+    );
+}
+
+#[test]
+fn grid_transposed_twice_is_the_original() {
+    let grid = sample();
+    assert_eq!(grid.transposed().to_grid().transposed().to_grid(), grid);
+    // The copy's rows are the original's columns.
+    let copy = grid.transposed().to_grid();
+    assert_eq!(
+        copy.rows().map(<[u32]>::to_vec).collect::<Vec<_>>(),
+        collect_lines(grid.columns())
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn grid_serde_round_trip() {
+    let grid = sample();
+    let json = crate::json::to_string(&grid).unwrap();
+    assert_eq!(crate::json::from_str::<Grid2D<u32>>(&json), Ok(grid));
+    assert_eq!(
+        crate::json::from_str::<Grid2D<u8>>("[[1, 2], [3, 4]]"),
+        Ok(Grid2D::from_rows(vec![vec![1, 2], vec![3, 4]]))
+    );
+    assert!(crate::json::from_str::<Grid2D<u8>>("[[1, 2], [3]]").is_err());
+}
@@ -0,0 +1,177 @@
+//! A record of everything `GameState::apply` has done. Walking it forwards
+//! replays a game from its start; walking it backwards undoes it.
+
+use super::game::{GameState, InvalidAction};
+use super::{Action, Map, MapCell, Point2d, Worm};
+
+/// An action as it was applied: by which worm, and on which turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct LoggedAction {
+    pub(super) turn: usize,
+    pub(super) worm: usize,
+    pub(super) action: Action,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct ActionLog {
+    // Each action, with the cell the worm's tail left when it moved. That
+    // is the one thing an action loses that undoing it needs back.
+    entries: Vec<(LoggedAction, Option<Point2d>)>,
+}
+
+impl ActionLog {
+    pub(super) fn record(&mut self, action: LoggedAction, dropped_tail: Option<Point2d>) {
+        self.entries.push((action, dropped_tail));
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl GameState {
+    /// The actions applied so far, oldest first.
+    pub(super) fn history(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = LoggedAction> + ExactSizeIterator + '_ {
+        self.log.entries.iter().map(|&(action, _)| action)
+    }
+
+    /// Takes back the most recent action, and rewinds the turn counter to
+    /// the turn it was taken on. Returns `None` when there is nothing left
+    /// to undo.
+    pub(super) fn undo(&mut self) -> Option<LoggedAction> {
+        let (logged, dropped_tail) = self.log.entries.pop()?;
+        let worm = &mut self.worms[logged.worm];
+        match logged.action {
+            Action::Move(_) => {
+                worm.body.pop_front();
+                worm.body.extend(dropped_tail);
+            }
+            Action::Dig(target) => {
+                if let Some(cell) = self.map.cells.get_mut(target) {
+                    *cell = MapCell::Dirt;
+                }
+                worm.length -= 1;
+            }
+        }
+        self.turn = logged.turn;
+        Some(logged)
+    }
+
+    /// Rebuilds a game by applying `actions`, each on its own turn, to a
+    /// fresh game on `map` with `worms`. Fails on the first action that
+    /// isn't valid at that point.
+    pub(super) fn replay<I>(
+        map: Map,
+        worms: Vec<Worm>,
+        actions: I,
+    ) -> Result<GameState, InvalidAction>
+    where
+        I: IntoIterator<Item = LoggedAction>,
+    {
+        let mut state = GameState::new(map, worms);
+        for logged in actions {
+            state.turn = logged.turn;
+            state.apply(logged.worm, logged.action)?;
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+fn start() -> GameState {
+    let map: Map = [
+        "....#", //
+        ".##.#", //
+        "....#", //
+    ]
+    .into_iter()
+    .collect();
+    GameState::new(
+        map,
+        vec![
+            Worm::new(Point2d::new(0, 0), 2),
+            Worm::new(Point2d::new(3, 2), 1),
+        ],
+    )
+}
+
+#[test]
+fn log_records_applied_actions() {
+    let at = Point2d::new;
+    let mut state = start();
+    state.apply(0, Action::Move(at(1, 0))).unwrap();
+    state.turn += 1;
+    state.apply(1, Action::Dig(at(4, 2))).unwrap();
+    // Rejected actions aren't logged.
+    assert!(state.apply(0, Action::Move(at(1, 1))).is_err());
+    state.apply(0, Action::Move(at(2, 0))).unwrap();
+
+    let history: Vec<LoggedAction> = state.history().collect();
+    assert_eq!(
+        history,
+        [
+            LoggedAction {
+                turn: 0,
+                worm: 0,
+                action: Action::Move(at(1, 0))
+            },
+            LoggedAction {
+                turn: 1,
+                worm: 1,
+                action: Action::Dig(at(4, 2))
+            },
+            LoggedAction {
+                turn: 1,
+                worm: 0,
+                action: Action::Move(at(2, 0))
+            },
+        ]
+    );
+    assert_eq!(state.history().next_back(), Some(history[2]));
+    assert_eq!(state.log.len(), 3);
+}
+
+#[test]
+fn undo_back_to_the_start() {
+    let start = start();
+    let policy = |_: &GameState, worm: usize, moves: &[Action]| moves.get(worm).copied();
+    let mut state = start.clone().ticks(policy).nth(5).unwrap();
+    assert!(!state.log.is_empty());
+    assert_ne!(state.worms, start.worms);
+
+    let undone = std::iter::from_fn(|| state.undo()).count();
+    assert_eq!(undone, 12);
+    assert_eq!(state.undo(), None);
+    assert_eq!(state.map, start.map);
+    assert_eq!(state.worms, start.worms);
+    assert_eq!(state.turn, 0);
+    assert!(state.log.is_empty());
+}
+
+#[test]
+fn replay_rebuilds_the_game() {
+    let start = start();
+    let policy = |_: &GameState, worm: usize, moves: &[Action]| moves.last().copied();
+    let played = start.clone().ticks(policy).nth(7).unwrap();
+
+    let replayed =
+        GameState::replay(start.map.clone(), start.worms.clone(), played.history()).unwrap();
+    assert_eq!(replayed.map, played.map);
+    assert_eq!(replayed.worms, played.worms);
+    assert_iter_eq!(replayed.history(), played.history());
+
+    // Replaying a tampered history stops at the first bad action.
+    let mut actions: Vec<LoggedAction> = played.history().collect();
+    actions[3].action = Action::Move(Point2d::new(9, 9));
+    let err = GameState::replay(start.map, start.worms, actions).unwrap_err();
+    assert_eq!(
+        err,
+        InvalidAction::OutOfBounds(Action::Move(Point2d::new(9, 9)))
+    );
+}
@@ -0,0 +1,209 @@
+//! Which cells count as a cell's neighbors is a choice. A `Neighborhood` is
+//! that choice written down as a list of offsets: the eight surrounding
+//! cells, the four that share an edge, the squares a chess knight can jump
+//! to, or anything else.
+
+use super::{Direction, Grid2D, Map, MapCell, Point2d};
+use std::iter::FusedIterator;
+use std::slice;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Neighborhood {
+    offsets: Vec<Point2d>,
+}
+
+impl Neighborhood {
+    /// A neighborhood from its offsets, in the order its neighbors will be
+    /// yielded. A repeated offset is only kept once, and `(0, 0)` is dropped,
+    /// as no cell is its own neighbor.
+    pub fn new<I: IntoIterator<Item = Point2d>>(offsets: I) -> Self {
+        let mut kept: Vec<Point2d> = Vec::new();
+        for offset in offsets {
+            if offset != Point2d::new(0, 0) && !kept.contains(&offset) {
+                kept.push(offset);
+            }
+        }
+        Neighborhood { offsets: kept }
+    }
+
+    /// The eight surrounding cells, in the order of `Direction::ALL`.
+    pub fn moore() -> Self {
+        Neighborhood::new(Direction::all().map(Point2d::from))
+    }
+
+    /// The four cells that share an edge.
+    pub fn von_neumann() -> Self {
+        Neighborhood::new(Direction::cardinal().map(Point2d::from))
+    }
+
+    /// Every cell up to `radius` steps away in any direction, row by row.
+    pub fn moore_radius(radius: i32) -> Self {
+        Neighborhood::new(
+            (-radius..=radius).flat_map(|y| (-radius..=radius).map(move |x| Point2d::new(x, y))),
+        )
+    }
+
+    /// The squares a chess knight can jump to.
+    pub fn knight() -> Self {
+        Neighborhood::new(
+            [
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ]
+            .map(|(x, y)| Point2d::new(x, y)),
+        )
+    }
+
+    pub fn offsets(&self) -> slice::Iter<'_, Point2d> {
+        self.offsets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// The neighbors of `point` that are on `grid`, with their cells.
+    pub fn around<'a, T>(&'a self, grid: &'a Grid2D<T>, point: Point2d) -> Neighbors<'a, T> {
+        Neighbors {
+            grid,
+            center: point,
+            offsets: self.offsets.iter(),
+        }
+    }
+}
+
+impl FromIterator<Point2d> for Neighborhood {
+    fn from_iter<I: IntoIterator<Item = Point2d>>(iter: I) -> Self {
+        Neighborhood::new(iter)
+    }
+}
+
+pub struct Neighbors<'a, T> {
+    grid: &'a Grid2D<T>,
+    center: Point2d,
+    offsets: slice::Iter<'a, Point2d>,
+}
+
+impl<'a, T> Neighbors<'a, T> {
+    fn visit(&self, offset: Point2d) -> Option<(Point2d, &'a T)> {
+        let point = self.center + offset;
+        self.grid.get(point).map(|cell| (point, cell))
+    }
+}
+
+impl<'a, T> Iterator for Neighbors<'a, T> {
+    type Item = (Point2d, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&offset) = self.offsets.next() {
+            if let Some(neighbor) = self.visit(offset) {
+                return Some(neighbor);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any of the offsets left may land off the grid.
+        (0, Some(self.offsets.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for Neighbors<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some(&offset) = self.offsets.next_back() {
+            if let Some(neighbor) = self.visit(offset) {
+                return Some(neighbor);
+            }
+        }
+        None
+    }
+}
+
+impl<T> FusedIterator for Neighbors<'_, T> {}
+
+impl Map {
+    /// The neighbors of `point` on the map, by the given neighborhood.
+    pub(super) fn neighbors_in<'a>(
+        &'a self,
+        hood: &'a Neighborhood,
+        point: Point2d,
+    ) -> impl Iterator<Item = (Point2d, MapCell)> + 'a {
+        hood.around(&self.cells, point).map(|(p, &cell)| (p, cell))
+    }
+}
+
+#[cfg(test)]
+fn neighbor_points(hood: &Neighborhood, grid: &Grid2D<u8>, x: i32, y: i32) -> Vec<(i32, i32)> {
+    hood.around(grid, Point2d::new(x, y))
+        .map(|(p, _)| (p.x, p.y))
+        .collect()
+}
+
+#[test]
+fn neighborhood_sizes() {
+    assert_eq!(Neighborhood::moore().len(), 8);
+    assert_eq!(Neighborhood::von_neumann().len(), 4);
+    assert_eq!(Neighborhood::knight().len(), 8);
+    assert_eq!(Neighborhood::moore_radius(2).len(), 24);
+    assert!(Neighborhood::moore_radius(0).is_empty());
+    assert_eq!(Neighborhood::moore_radius(1).len(), 8);
+
+    // The center and repeats are dropped.
+    let hood: Neighborhood = [(0, 0), (1, 0), (1, 0), (0, 1)]
+        .into_iter()
+        .map(|(x, y)| Point2d::new(x, y))
+        .collect();
+    assert_eq!(
+        hood.offsets().copied().collect::<Vec<_>>(),
+        [Point2d::new(1, 0), Point2d::new(0, 1)]
+    );
+}
+
+#[test]
+fn neighborhood_clips_to_the_grid() {
+    let grid = Grid2D::new(5, 4, 0u8);
+    let moore = Neighborhood::moore();
+    assert_eq!(neighbor_points(&moore, &grid, 0, 0).len(), 3);
+    assert_eq!(neighbor_points(&moore, &grid, 2, 0).len(), 5);
+    assert_eq!(neighbor_points(&moore, &grid, 2, 2).len(), 8);
+    assert_eq!(neighbor_points(&moore, &grid, 4, 3).len(), 3);
+
+    let knight = Neighborhood::knight();
+    assert_eq!(neighbor_points(&knight, &grid, 0, 0), [(1, 2), (2, 1)]);
+    assert_eq!(neighbor_points(&knight, &grid, 2, 2).len(), 6);
+    // Off the grid altogether, a knight can still jump back onto it.
+    assert_eq!(neighbor_points(&knight, &grid, -1, -1), [(0, 1), (1, 0)]);
+
+    let wide = Neighborhood::moore_radius(2);
+    assert_eq!(neighbor_points(&wide, &grid, 0, 0).len(), 8);
+    assert_eq!(neighbor_points(&wide, &grid, 2, 1).len(), 19);
+
+    let mut around = knight.around(&grid, Point2d::new(2, 2));
+    assert_eq!(around.size_hint(), (0, Some(8)));
+    assert_eq!(around.next_back().map(|(p, _)| p), Some(Point2d::new(0, 3)));
+}
+
+#[test]
+fn map_neighbors_in_matches_map_neighbors() {
+    let map: Map = ["..#", "#.%", "~.*"].into_iter().collect();
+    let moore = Neighborhood::moore();
+    for (point, _) in map.iter_cells() {
+        assert_iter_eq!(map.neighbors_in(&moore, point), map.neighbors(point));
+    }
+    let knight: Vec<MapCell> = map
+        .neighbors_in(&Neighborhood::knight(), Point2d::new(0, 0))
+        .map(|(_, cell)| cell)
+        .collect();
+    assert_eq!(knight, [MapCell::Air, MapCell::Rock]);
+}
@@ -0,0 +1,171 @@
+//! Maps drawn as ASCII art, the inverse of `render`: one line per row, with
+//! the same characters for each kind of cell.
+
+use super::{Grid2D, Map, MapCell};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum ParseMapError {
+    /// A character that isn't a cell. Lines and columns count from 1.
+    UnknownCell {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A row that isn't as long as the first one.
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for ParseMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMapError::UnknownCell {
+                line,
+                column,
+                found,
+            } => write!(f, "line {line}, column {column}: unknown cell {found:?}"),
+            ParseMapError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(f, "line {line}: expected {expected} cells, found {found}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseMapError {}
+
+impl MapCell {
+    fn from_glyph(glyph: char) -> Option<MapCell> {
+        match glyph {
+            '.' => Some(MapCell::Air),
+            '#' => Some(MapCell::Dirt),
+            '%' => Some(MapCell::Rock),
+            '~' => Some(MapCell::Water),
+            '*' => Some(MapCell::Powerup),
+            _ => None,
+        }
+    }
+}
+
+impl Map {
+    fn try_from_lines<'a, I>(lines: I) -> Result<Map, ParseMapError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let rows = lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        MapCell::from_glyph(c).ok_or(ParseMapError::UnknownCell {
+                            line: i + 1,
+                            column: j + 1,
+                            found: c,
+                        })
+                    })
+                    .collect::<Result<Vec<MapCell>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(first) = rows.first() {
+            let expected = first.len();
+            if let Some((i, row)) = rows.iter().enumerate().find(|(_, r)| r.len() != expected) {
+                return Err(ParseMapError::RaggedRow {
+                    line: i + 1,
+                    expected,
+                    found: row.len(),
+                });
+            }
+        }
+        Ok(Map {
+            cells: Grid2D::from_rows(rows),
+        })
+    }
+}
+
+impl FromStr for Map {
+    type Err = ParseMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Map::try_from_lines(s.lines())
+    }
+}
+
+/// Builds a map from its rows.
+///
+/// # Panics
+///
+/// Panics if the rows aren't a valid map; use `str::parse` to get the
+/// error instead.
+impl<'a> FromIterator<&'a str> for Map {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        Map::try_from_lines(iter).unwrap_or_else(|err| panic!("invalid map: {err}"))
+    }
+}
+
+#[test]
+fn parse_map() {
+    let map: Map = "..#\n#..\n".parse().unwrap();
+    assert_eq!((map.cells.width(), map.cells.height()), (3, 2));
+    assert_eq!(map.at(super::Point2d::new(2, 0)), Some(MapCell::Dirt));
+    assert_eq!(map.at(super::Point2d::new(2, 1)), Some(MapCell::Air));
+
+    let same: Map = ["..#", "#.."].into_iter().collect();
+    assert_eq!(same, map);
+
+    let empty: Map = "".parse().unwrap();
+    assert_eq!(empty.iter_cells().count(), 0);
+}
+
+#[test]
+fn parse_map_errors() {
+    assert_eq!(
+        "...\n.x.".parse::<Map>(),
+        Err(ParseMapError::UnknownCell {
+            line: 2,
+            column: 2,
+            found: 'x'
+        })
+    );
+
+    let err = "...\n..\n...".parse::<Map>().unwrap_err();
+    assert_eq!(
+        err,
+        ParseMapError::RaggedRow {
+            line: 2,
+            expected: 3,
+            found: 2
+        }
+    );
+    assert_eq!(err.to_string(), "line 2: expected 3 cells, found 2");
+}
+
+#[test]
+#[should_panic(expected = "invalid map: line 1, column 4: unknown cell '@'")]
+fn collect_invalid_map() {
+    let _: Map = ["...@"].into_iter().collect();
+}
+
+#[test]
+fn parse_round_trips_through_render() {
+    let art = "\
+        ..##....\n\
+        #..#.##.\n\
+        ###...#.\n";
+    let map: Map = art.parse().unwrap();
+    let rendered: String = map.render().map(|line| line + "\n").collect();
+    assert_eq!(rendered, art);
+
+    let generated = Map::generate(1362, 20, 10, 3);
+    let lines: Vec<String> = generated.render().collect();
+    let reparsed: Map = lines.iter().map(String::as_str).collect();
+    assert_eq!(reparsed, generated);
+}
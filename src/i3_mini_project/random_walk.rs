@@ -0,0 +1,129 @@
+//! A worm with no plan: each step goes one cell in a random direction. The
+//! walk can be kept inside a `width` x `height` area, in which case a step
+//! off the edge either bounces back or stays put at the edge.
+
+use super::{Direction, Point2d};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::iter::FusedIterator;
+
+/// What happens to a step that would leave the area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// Bounce off the edge: one step past it lands one step inside it.
+    Reflect,
+    /// Stop at the edge.
+    Clamp,
+}
+
+#[derive(Debug, Clone)]
+pub struct RandomWalk {
+    // The position `next()` will yield.
+    position: Point2d,
+    rng: StdRng,
+    bounds: Option<(i32, i32, Boundary)>,
+}
+
+impl RandomWalk {
+    /// An endless walk from `start`. The same `seed` always gives the same
+    /// walk.
+    pub fn new(start: Point2d, seed: u64) -> Self {
+        RandomWalk {
+            position: start,
+            rng: StdRng::seed_from_u64(seed),
+            bounds: None,
+        }
+    }
+
+    /// Keeps the walk inside `0..width` x `0..height`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the area is empty.
+    pub fn within(mut self, width: usize, height: usize, boundary: Boundary) -> Self {
+        assert!(width > 0 && height > 0, "a walk needs room to walk in");
+        self.bounds = Some((width as i32, height as i32, boundary));
+        self.position = self.constrain(self.position);
+        self
+    }
+
+    fn constrain(&self, p: Point2d) -> Point2d {
+        let Some((width, height, boundary)) = self.bounds else {
+            return p;
+        };
+        let fit = |v: i32, len: i32| match boundary {
+            Boundary::Reflect if v < 0 => (-v).min(len - 1),
+            Boundary::Reflect if v >= len => (2 * (len - 1) - v).max(0),
+            _ => v.clamp(0, len - 1),
+        };
+        Point2d::new(fit(p.x, width), fit(p.y, height))
+    }
+}
+
+impl Iterator for RandomWalk {
+    type Item = Point2d;
+
+    /// The start comes first, then one position per step.
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.position;
+        let dir = *Direction::ALL.choose(&mut self.rng).unwrap();
+        self.position = self.constrain(current + Point2d::from(dir));
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for RandomWalk {}
+
+#[test]
+fn random_walk_is_reproducible() {
+    let start = Point2d::new(3, 4);
+    let a: Vec<Point2d> = RandomWalk::new(start, 1366).take(50).collect();
+    let b: Vec<Point2d> = RandomWalk::new(start, 1366).take(50).collect();
+    let c: Vec<Point2d> = RandomWalk::new(start, 1367).take(50).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(a[0], start);
+
+    // Each step is one cell in one of the eight directions.
+    assert!(a.windows(2).all(|w| {
+        let (dx, dy) = (w[1].x - w[0].x, w[1].y - w[0].y);
+        dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0)
+    }));
+}
+
+#[test]
+fn random_walk_reflects_off_the_edges() {
+    let walk = RandomWalk::new(Point2d::new(0, 0), 7).within(3, 2, Boundary::Reflect);
+    let cells: Vec<Point2d> = walk.take(500).collect();
+    assert!(cells
+        .iter()
+        .all(|p| (0..3).contains(&p.x) && (0..2).contains(&p.y)));
+    // A bounce still moves, so the walk never stands still.
+    assert!(cells.windows(2).all(|w| w[0] != w[1]));
+    // Given long enough, it visits the whole area.
+    let mut seen = cells.clone();
+    seen.sort_by_key(|p| (p.x, p.y));
+    seen.dedup();
+    assert_eq!(seen.len(), 6);
+}
+
+#[test]
+fn random_walk_clamps_to_the_edges() {
+    let walk = RandomWalk::new(Point2d::new(-5, 9), 11).within(4, 4, Boundary::Clamp);
+    let cells: Vec<Point2d> = walk.take(500).collect();
+    assert_eq!(cells[0], Point2d::new(0, 3));
+    assert!(cells
+        .iter()
+        .all(|p| (0..4).contains(&p.x) && (0..4).contains(&p.y)));
+    // Walking into a corner leaves the walk where it was.
+    assert!(cells.windows(2).any(|w| w[0] == w[1]));
+
+    // In a single cell there is nowhere to go.
+    let stuck = RandomWalk::new(Point2d::new(0, 0), 1).within(1, 1, Boundary::Reflect);
+    assert!(stuck.take(20).all(|p| p == Point2d::new(0, 0)));
+}
@@ -0,0 +1,96 @@
+//! ASCII pictures of the map, handed out one row at a time: `.` is air, `#`
+//! dirt, `%` rock, `~` water and `*` a powerup. In a game, `@` is a worm's
+//! head and `o` the rest of it.
+
+use super::game::GameState;
+use super::{Map, MapCell, Point2d};
+use std::collections::HashMap;
+
+impl MapCell {
+    pub(super) fn glyph(self) -> char {
+        match self {
+            MapCell::Air => '.',
+            MapCell::Dirt => '#',
+            MapCell::Rock => '%',
+            MapCell::Water => '~',
+            MapCell::Powerup => '*',
+        }
+    }
+}
+
+impl Map {
+    /// The rows of the map as text, top row first.
+    pub(super) fn render(&self) -> impl Iterator<Item = String> + '_ {
+        self.cells
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.glyph()).collect())
+    }
+}
+
+impl GameState {
+    /// The rows of the map as text, top row first, with the worms drawn in.
+    pub(super) fn render(&self) -> impl Iterator<Item = String> + '_ {
+        let worms: HashMap<Point2d, char> = self
+            .worms
+            .iter()
+            .flat_map(|worm| {
+                let glyphs = std::iter::once('@').chain(std::iter::repeat('o'));
+                worm.segments().copied().zip(glyphs)
+            })
+            .collect();
+        self.map.cells.rows().enumerate().map(move |(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, cell)| {
+                    let point = Point2d::new(x as i32, y as i32);
+                    worms.get(&point).copied().unwrap_or(cell.glyph())
+                })
+                .collect()
+        })
+    }
+}
+
+#[test]
+fn render_map() {
+    use super::Grid2D;
+    use MapCell::{Air, Dirt};
+    let map = Map {
+        cells: Grid2D::from_rows(vec![
+            vec![Air, Air, Dirt, Dirt],
+            vec![Dirt, Air, Air, Air],
+            vec![Dirt, Dirt, Dirt, Air],
+        ]),
+    };
+
+    let lines: Vec<String> = map.render().collect();
+    assert_eq!(lines, ["..##", "#...", "###."]);
+
+    let picture: String = map.render().map(|line| line + "\n").collect();
+    assert_eq!(picture, "..##\n#...\n###.\n");
+}
+
+#[test]
+fn render_game_state() {
+    use super::{Grid2D, Worm};
+    let mut worm = Worm::new(Point2d::new(0, 0), 3);
+    worm.move_to(Point2d::new(1, 0));
+    worm.move_to(Point2d::new(2, 1));
+    let state = GameState::new(
+        Map {
+            cells: Grid2D::new(4, 3, MapCell::Air),
+        },
+        vec![worm, Worm::new(Point2d::new(3, 2), 1)],
+    );
+
+    let mut picture = String::new();
+    for line in state.render() {
+        picture.push_str(&line);
+        picture.push('\n');
+    }
+    assert_eq!(picture, "oo..\n..@.\n...@\n");
+
+    let empty = Map {
+        cells: Grid2D::new(0, 0, MapCell::Air),
+    };
+    assert_eq!(GameState::new(empty, vec![]).render().count(), 0);
+}
@@ -0,0 +1,161 @@
+//! Saving and loading games. Everything is written compactly, as tuples
+//! rather than structs:
+//!
+//! - a `Point2d` is `[x, y]`,
+//! - a `Map` is its rows as drawn by `render`, e.g. `["..#", "#.."]`,
+//! - a `Worm` is `[body, length]`, the body head first,
+//! - a `GameState` is `[map, worms, turn]`.
+//!
+//! The action log isn't saved, so a loaded game has nothing to undo.
+
+use super::game::GameState;
+use super::{Map, Point2d, Worm};
+use crate::json::{self, JsonError};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
+
+impl Serialize for Point2d {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.x, self.y).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Point2d {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (x, y) = Deserialize::deserialize(deserializer)?;
+        Ok(Point2d::new(x, y))
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.render())
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<String>::deserialize(deserializer)?;
+        rows.join("\n").parse().map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Worm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.body, self.length).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Worm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (body, length): (VecDeque<Point2d>, i32) = Deserialize::deserialize(deserializer)?;
+        // A worm is never longer than it is allowed to grow, and always has
+        // at least a head.
+        if body.is_empty() || body.len() > length.max(1) as usize {
+            return Err(D::Error::custom(format!(
+                "a worm of length {length} can't have {} segments",
+                body.len()
+            )));
+        }
+        Ok(Worm { body, length })
+    }
+}
+
+impl Serialize for GameState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.map, &self.worms, self.turn).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (map, worms, turn): (Map, Vec<Worm>, usize) = Deserialize::deserialize(deserializer)?;
+        if let Some(&p) = worms
+            .iter()
+            .flat_map(Worm::segments)
+            .find(|&&p| map.at(p).is_none())
+        {
+            return Err(D::Error::custom(format!(
+                "a worm is off the map at ({}, {})",
+                p.x, p.y
+            )));
+        }
+        let mut state = GameState::new(map, worms);
+        state.turn = turn;
+        Ok(state)
+    }
+}
+
+impl GameState {
+    /// The game as JSON, to hand to `load_json` later.
+    pub(super) fn save_json(&self) -> String {
+        json::to_string(self).expect("a game state is always valid JSON")
+    }
+
+    pub(super) fn load_json(s: &str) -> Result<GameState, JsonError> {
+        json::from_str(s)
+    }
+}
+
+#[test]
+fn point_serde_round_trip() {
+    let p = Point2d::new(-3, 7);
+    assert_eq!(json::to_string(&p).unwrap(), "[-3,7]");
+    assert_eq!(json::from_str::<Point2d>("[-3, 7]"), Ok(p));
+    assert!(json::from_str::<Point2d>("[1.5, 2]").is_err());
+}
+
+#[test]
+fn map_and_worm_serde_round_trip() {
+    let map: Map = "..#\n#~%\n".parse().unwrap();
+    let json = json::to_string(&map).unwrap();
+    assert_eq!(json, r##"["..#","#~%"]"##);
+    assert_eq!(json::from_str::<Map>(&json), Ok(map));
+    let err = json::from_str::<Map>(r#"["..", "x."]"#).unwrap_err();
+    assert_eq!(err.to_string(), "line 2, column 1: unknown cell 'x'");
+
+    let mut worm = Worm::new(Point2d::new(1, 1), 3);
+    worm.move_to(Point2d::new(2, 1));
+    let json = json::to_string(&worm).unwrap();
+    assert_eq!(json, "[[[2,1],[1,1]],3]");
+    assert_eq!(json::from_str::<Worm>(&json), Ok(worm));
+    assert!(json::from_str::<Worm>("[[], 3]").is_err());
+    assert!(json::from_str::<Worm>("[[[0,0],[0,1]], 1]").is_err());
+}
+
+#[test]
+fn game_state_save_and_load() {
+    let state = super::game::sample_state()
+        .ticks(super::game::seeded_policy(1381))
+        .nth(9)
+        .unwrap();
+    assert_eq!(state.turn, 10);
+    assert!(!state.log.is_empty());
+
+    let loaded = GameState::load_json(&state.save_json()).unwrap();
+    assert_eq!(loaded.map, state.map);
+    assert_eq!(loaded.worms, state.worms);
+    assert_eq!(loaded.turn, state.turn);
+    assert!(loaded.log.is_empty());
+    // Saving again gives the same text.
+    assert_eq!(loaded.save_json(), state.save_json());
+
+    // The loaded game plays on exactly like the original.
+    let next = |s: GameState| s.ticks(super::game::seeded_policy(7)).nth(4).unwrap();
+    let (a, b) = (next(state), next(loaded));
+    assert_eq!((a.map, a.worms, a.turn), (b.map, b.worms, b.turn));
+}
+
+#[test]
+fn load_json_rejects_bad_games() {
+    assert_eq!(
+        GameState::load_json(r#"[["..."], [[[[5, 0]], 1]], 0]"#),
+        Err(JsonError::Custom("a worm is off the map at (5, 0)".into()))
+    );
+    assert_eq!(
+        GameState::load_json(r#"[["..."], []"#),
+        Err(JsonError::UnexpectedEnd)
+    );
+    assert!(GameState::load_json(r#"[["..."], [], -1]"#).is_err());
+}
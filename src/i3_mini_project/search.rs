@@ -0,0 +1,329 @@
+//! Searches over the map that hand out their results one cell at a time.
+//! Each iterator owns its frontier and its visited set, so a search can be
+//! stopped as soon as the caller has seen enough.
+
+use super::{Grid2D, Map, MapCell, Point2d};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::iter::FusedIterator;
+
+impl Map {
+    /// The cells a worm at `start` could walk to without digging, nearest
+    /// first. `start` itself comes first, whatever it is made of.
+    pub(super) fn bfs_from(&self, start: Point2d) -> Bfs<'_, fn(MapCell) -> bool> {
+        self.bfs_from_with(start, MapCell::is_walkable)
+    }
+
+    /// Like `bfs_from`, but `passable` decides which cells can be entered.
+    pub(super) fn bfs_from_with<F>(&self, start: Point2d, passable: F) -> Bfs<'_, F>
+    where
+        F: FnMut(MapCell) -> bool,
+    {
+        let mut queue = VecDeque::new();
+        let mut visited = HashSet::new();
+        if self.at(start).is_some() {
+            queue.push_back(start);
+            visited.insert(start);
+        }
+        Bfs {
+            map: self,
+            queue,
+            visited,
+            passable,
+        }
+    }
+
+    /// The cells connected to `point` through cells of the same kind, with
+    /// `point` first. Like a worm, the fill can squeeze between diagonal
+    /// neighbors.
+    pub(super) fn region(&self, point: Point2d) -> Bfs<'_, impl FnMut(MapCell) -> bool> {
+        let kind = self.at(point);
+        self.bfs_from_with(point, move |cell| Some(cell) == kind)
+    }
+
+    /// The cheapest way for a worm to get from `from` to `to`, as its total
+    /// `MapCell::cost` and the cells along it, both ends included. `None`
+    /// if rock cuts `to` off from `from`.
+    ///
+    /// This is A*: cells are explored cheapest-looking first, where a cell
+    /// looks as cheap as the cost to reach it plus the fewest steps still
+    /// needed from there, each step costing at least 1.
+    pub(super) fn cheapest_path(&self, from: Point2d, to: Point2d) -> Option<(u32, Vec<Point2d>)> {
+        self.at(from)?;
+        let estimate = |p: Point2d| p.chebyshev(to) as u32;
+
+        let mut best: HashMap<Point2d, u32> = HashMap::from([(from, 0)]);
+        let mut came_from: HashMap<Point2d, Point2d> = HashMap::new();
+        // Ordered by estimated total cost; `Reverse` makes the heap a min-heap.
+        let mut open = BinaryHeap::from([Reverse((estimate(from), 0, from.x, from.y))]);
+
+        while let Some(Reverse((_, cost, x, y))) = open.pop() {
+            let point = Point2d::new(x, y);
+            if point == to {
+                let path = std::iter::successors(Some(to), |p| came_from.get(p).copied());
+                let mut path: Vec<Point2d> = path.collect();
+                path.reverse();
+                return Some((cost, path));
+            }
+            if cost > best[&point] {
+                // A cheaper way here was found after this one was queued.
+                continue;
+            }
+            for (next, cell) in self.neighbors(point) {
+                let Some(step) = cell.cost() else { continue };
+                let next_cost = cost + step;
+                if best.get(&next).is_none_or(|&known| next_cost < known) {
+                    best.insert(next, next_cost);
+                    came_from.insert(next, point);
+                    open.push(Reverse((
+                        next_cost + estimate(next),
+                        next_cost,
+                        next.x,
+                        next.y,
+                    )));
+                }
+            }
+        }
+        None
+    }
+
+    /// Every region of the map, each one as its kind and its cells. Regions
+    /// come in the order of their first cell in row-major order.
+    pub(super) fn regions(&self) -> Regions<'_> {
+        Regions {
+            map: self,
+            seen: Grid2D::new(self.cells.width(), self.cells.height(), false),
+            cursor: 0,
+        }
+    }
+}
+
+pub(super) struct Bfs<'a, F> {
+    map: &'a Map,
+    // Cells that have been reached but not yielded yet, nearest first.
+    queue: VecDeque<Point2d>,
+    // Every cell that has ever been queued, so none is queued twice.
+    visited: HashSet<Point2d>,
+    passable: F,
+}
+
+impl<F> Iterator for Bfs<'_, F>
+where
+    F: FnMut(MapCell) -> bool,
+{
+    type Item = Point2d;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.queue.pop_front()?;
+        for (neighbor, cell) in self.map.neighbors(point) {
+            if (self.passable)(cell) && self.visited.insert(neighbor) {
+                self.queue.push_back(neighbor);
+            }
+        }
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Everything queued will be yielded, and at most every cell that
+        // hasn't been reached yet can still join it.
+        let unvisited = self.map.cells.width() * self.map.cells.height() - self.visited.len();
+        (self.queue.len(), Some(self.queue.len() + unvisited))
+    }
+}
+
+impl<F> FusedIterator for Bfs<'_, F> where F: FnMut(MapCell) -> bool {}
+
+pub(super) struct Regions<'a> {
+    map: &'a Map,
+    // Cells that already belong to a region that has been yielded.
+    seen: Grid2D<bool>,
+    // The row-major index of the next cell that might start a new region.
+    cursor: usize,
+}
+
+impl Iterator for Regions<'_> {
+    type Item = (MapCell, Vec<Point2d>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let width = self.map.cells.width();
+        while self.cursor < width * self.map.cells.height() {
+            let start = Point2d::new((self.cursor % width) as i32, (self.cursor / width) as i32);
+            self.cursor += 1;
+            if self.seen.get(start) == Some(&true) {
+                continue;
+            }
+            let points: Vec<Point2d> = self.map.region(start).collect();
+            for &point in &points {
+                if let Some(seen) = self.seen.get_mut(point) {
+                    *seen = true;
+                }
+            }
+            return Some((self.map.at(start)?, points));
+        }
+        None
+    }
+}
+
+impl FusedIterator for Regions<'_> {}
+
+#[cfg(test)]
+fn parse(rows: &[&str]) -> Map {
+    rows.iter().copied().collect()
+}
+
+#[test]
+fn bfs_visits_nearest_cells_first() {
+    let map = parse(&["....", "....", "...."]);
+    let start = Point2d::new(1, 1);
+    let order: Vec<Point2d> = map.bfs_from(start).collect();
+    assert_eq!(order.len(), 12);
+    assert_eq!(order[0], start);
+
+    // A worm moves diagonally too, so distance is the larger of dx and dy.
+    let distance = |p: &Point2d| (p.x - start.x).abs().max((p.y - start.y).abs());
+    assert!(order.windows(2).all(|w| distance(&w[0]) <= distance(&w[1])));
+    assert_eq!(order.iter().filter(|p| distance(p) == 1).count(), 8);
+    assert_eq!(order.iter().filter(|p| distance(p) == 2).count(), 3);
+}
+
+#[test]
+fn bfs_stops_at_dirt_walls() {
+    let map = parse(&["..#..", "..#..", "..#.."]);
+    let mut left: Vec<Point2d> = map.bfs_from(Point2d::new(0, 0)).collect();
+    left.sort_by_key(|p| (p.y, p.x));
+    assert_eq!(left.len(), 6);
+    assert!(left.iter().all(|p| p.x < 2));
+
+    // With dirt made passable, the wall no longer separates the halves.
+    assert_eq!(map.bfs_from_with(Point2d::new(0, 0), |_| true).count(), 15);
+    // A diagonal gap is enough to slip through.
+    let map = parse(&["..#..", "...#.", "..#.."]);
+    assert_eq!(map.bfs_from(Point2d::new(0, 0)).count(), 12);
+}
+
+#[test]
+fn bfs_from_dirt_and_off_the_map() {
+    let map = parse(&[".###.", ".###.", ".###."]);
+    // The start is yielded, but the search can't leave it.
+    assert_eq!(
+        map.bfs_from(Point2d::new(2, 1)).collect::<Vec<_>>(),
+        [Point2d::new(2, 1)]
+    );
+    assert_eq!(map.bfs_from(Point2d::new(0, 0)).count(), 3);
+    assert_eq!(map.bfs_from(Point2d::new(-1, 0)).next(), None);
+}
+
+#[test]
+fn bfs_is_lazy() {
+    let map = parse(&["....", "....", "....", "...."]);
+    let mut bfs = map.bfs_from(Point2d::new(0, 0));
+    assert_eq!(bfs.size_hint(), (1, Some(16)));
+    bfs.next();
+    // Only the first ring has been discovered so far.
+    assert_eq!(bfs.visited.len(), 4);
+    assert_eq!(bfs.size_hint(), (3, Some(15)));
+    assert_eq!(bfs.count(), 15);
+}
+
+#[cfg(test)]
+fn caves() -> Map {
+    parse(&[
+        "..#....", //
+        "###.##.", //
+        ".#..#..", //
+        "##.####", //
+    ])
+}
+
+#[test]
+fn region_collects_connected_cells_of_one_kind() {
+    let map = caves();
+
+    let mut pocket: Vec<Point2d> = map.region(Point2d::new(1, 0)).collect();
+    pocket.sort_by_key(|p| (p.y, p.x));
+    assert_eq!(pocket, [Point2d::new(0, 0), Point2d::new(1, 0)]);
+
+    // (3, 1) touches the top-right air diagonally and the middle directly.
+    assert_eq!(map.region(Point2d::new(6, 0)).count(), 11);
+    assert_eq!(map.region(Point2d::new(0, 2)).count(), 1);
+    assert!(map
+        .region(Point2d::new(1, 1))
+        .all(|p| map.at(p) == Some(MapCell::Dirt)));
+    assert_eq!(map.region(Point2d::new(9, 9)).next(), None);
+}
+
+#[test]
+fn regions_split_the_map() {
+    let map = caves();
+    let regions: Vec<(MapCell, Vec<Point2d>)> = map.regions().collect();
+
+    let pockets: Vec<usize> = regions
+        .iter()
+        .filter(|(cell, _)| *cell == MapCell::Air)
+        .map(|(_, points)| points.len())
+        .collect();
+    assert_eq!(pockets, [2, 11, 1]);
+
+    // Every cell is in exactly one region.
+    let mut all: Vec<Point2d> = regions.into_iter().flat_map(|(_, p)| p).collect();
+    all.sort_by_key(|p| (p.y, p.x));
+    assert_iter_eq!(all.iter().copied(), map.iter_cells().map(|(p, _)| p));
+}
+
+#[test]
+fn cheapest_path_routes_around_water() {
+    let map = parse(&[
+        ".....", //
+        ".~~~.", //
+        ".....", //
+    ]);
+    // Straight across is 4 steps but costs 7; around the pond it's 4 steps
+    // of open ground.
+    let (cost, path) = map
+        .cheapest_path(Point2d::new(0, 1), Point2d::new(4, 1))
+        .unwrap();
+    assert_eq!(cost, 4);
+    assert_eq!(path.len(), 5);
+    assert_eq!(path.first(), Some(&Point2d::new(0, 1)));
+    assert_eq!(path.last(), Some(&Point2d::new(4, 1)));
+    assert!(path.iter().all(|&p| map.at(p) == Some(MapCell::Air)));
+    assert!(path
+        .windows(2)
+        .all(|w| map.neighbors(w[0]).any(|(p, _)| p == w[1])));
+}
+
+#[test]
+fn cheapest_path_costs() {
+    // With rock all around, wading through the water is the only way.
+    let map = parse(&[
+        "%%%%%", //
+        ".~~~.", //
+        "%%%%%", //
+    ]);
+    let (cost, path) = map
+        .cheapest_path(Point2d::new(0, 1), Point2d::new(4, 1))
+        .unwrap();
+    assert_eq!(cost, 2 + 2 + 2 + 1);
+    assert_eq!(path.len(), 5);
+
+    // Digging through one dirt cell beats a long detour.
+    let map = parse(&[
+        "..#..", //
+        "%%#%%", //
+        "%...%", //
+    ]);
+    let (cost, _) = map
+        .cheapest_path(Point2d::new(0, 0), Point2d::new(4, 0))
+        .unwrap();
+    assert_eq!(cost, 1 + 2 + 1 + 1);
+
+    let walled = parse(&[".%.", ".%.", ".%."]);
+    assert_eq!(
+        walled.cheapest_path(Point2d::new(0, 0), Point2d::new(2, 2)),
+        None
+    );
+    assert_eq!(
+        walled.cheapest_path(Point2d::new(0, 0), Point2d::new(0, 0)),
+        Some((0, vec![Point2d::new(0, 0)]))
+    );
+}
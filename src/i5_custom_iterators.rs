@@ -17,14 +17,8 @@ mod Iterator_for_Counter {
      * Implement `Iterator` for `Counter`.
      */
 
-    #[cfg(feature = "skip")]
-    #[test]
-    fn test1() {
-        let counter = Counter::new(10);
-        for i in counter {
-            println!("{i}");
-        }
-    }
+    // The tests for this are in `exercises::counter`, where the gap is a
+    // stub that compiles.
 }
 
 mod IntoIterator_for_Counter {
@@ -43,14 +37,8 @@ mod IntoIterator_for_Counter {
      * Implement `IntoIterator` for `Counter`.
      */
 
-    #[cfg(feature = "skip")]
-    #[test]
-    fn test() {
-        let counter = Counter::new(10);
-        for i in counter {
-            println!("{i}");
-        }
-    }
+    // The tests for this are in `exercises::into_counter`, where the gap is
+    // a stub that compiles.
 }
 
 /**
@@ -60,6 +48,7 @@ mod IntoIterator_for_Counter {
 mod Iterator_for_PasswordGenerator {
     use rand::Rng;
 
+    #[derive(Debug, Clone)]
     struct PasswordGenerator {
         length: usize,
     }
@@ -85,44 +74,25 @@ mod Iterator_for_PasswordGenerator {
             }
             Some(password)
         }
-    }
 
-    // #[cfg(feature = "skip")]
-    #[test]
-    fn test() {
-        let gen = PasswordGenerator::new(10);
-
-        // `IntoIterator` is automatically generated for Iterator via blanket impl.
-        for password in gen.take(10) {
-            println!("{password:?}");
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (usize::MAX, None)
         }
     }
-}
 
-mod IntoIterator_for_PasswordGenerator {
-    use rand::Rng;
+    // An endless source never returns `None`, so it is trivially fused.
+    impl std::iter::FusedIterator for PasswordGenerator {}
 
-    struct PasswordGenerator {
-        length: usize,
-    }
-
-    impl PasswordGenerator {
-        fn new(length: usize) -> Self {
-            Self { length }
-        }
-    }
-
-    /*
-     * Implement `IntoIterator` for `PasswordGenerator`.
-     */
-
-    #[cfg(feature = "skip")]
     #[test]
     fn test() {
         let gen = PasswordGenerator::new(10);
 
-        for password in gen.into_iter().take(10) {
+        // `IntoIterator` is automatically generated for Iterator via blanket impl.
+        for password in gen.take(10) {
             println!("{password:?}");
         }
     }
 }
+
+// Implementing `IntoIterator` for `PasswordGenerator` is an exercise:
+// see `exercises::into_iterator`.
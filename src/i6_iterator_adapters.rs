@@ -48,6 +48,9 @@ mod iterator_adapter_Map {
 
         assert_eq!(result, [2, 4, 6, 8, 10]);
     }
+
+    // The tests for `Map` itself are in `exercises::map`, where the gaps
+    // are stubs that compile.
 }
 
 mod iterator_adapter_Unique {
@@ -82,56 +85,292 @@ mod iterator_adapter_Unique {
      * TODO
      */
 
-    #[cfg(feature = "skip")]
+    // The test for this is in `exercises::unique`, where the gaps are
+    // stubs that compile.
+}
+
+mod iterator_adapter_Filter {
+    // Step 1: Define a struct for the custom adapter.
+    #[derive(Clone)]
+    pub struct Filter<I, P> {
+        orig: I,
+        pred: P,
+    }
+
+    impl<I: std::fmt::Debug, P> std::fmt::Debug for Filter<I, P> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Filter")
+                .field("orig", &self.orig)
+                .finish_non_exhaustive()
+        }
+    }
+
+    // Step 2: Implement `Iterator` for the custom adapter.
+    impl<I, P> Iterator for Filter<I, P>
+    where
+        I: Iterator,
+        P: FnMut(&I::Item) -> bool,
+    {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.orig.by_ref().find(|item| (self.pred)(item))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (0, self.orig.size_hint().1)
+        }
+
+        fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+        where
+            G: FnMut(Acc, Self::Item) -> Acc,
+        {
+            let mut pred = self.pred;
+            self.orig.fold(
+                init,
+                move |acc, item| {
+                    if pred(&item) {
+                        g(acc, item)
+                    } else {
+                        acc
+                    }
+                },
+            )
+        }
+    }
+
+    // Step 3: Define a new extension trait with the new operator to be
+    //         added, as a sub-trait of `Iterator`.
+    pub trait FilterExt: Iterator {
+        fn ffilter<P>(self, pred: P) -> Filter<Self, P>
+        where
+            Self: Sized,
+            P: FnMut(&Self::Item) -> bool;
+    }
+
+    // Step 4: Implement the trait for all types that implement `Iterator`.
+    impl<I: Iterator> FilterExt for I {
+        fn ffilter<P>(self, pred: P) -> Filter<Self, P>
+        where
+            P: FnMut(&Self::Item) -> bool,
+        {
+            Filter { orig: self, pred }
+        }
+    }
+
     #[test]
     fn test() {
-        let vs = vec!["a", "b", "a", "cc", "cc", "d"];
+        let evens: Vec<_> = (1..=10).ffilter(|x| x % 2 == 0).collect();
 
-        let result: Vec<_> = vs.into_iter().unique().collect();
-
-        assert_eq!(result, ["a", "b", "cc", "d"]);
+        assert_eq!(evens, [2, 4, 6, 8, 10]);
     }
 }
 
-// #[cfg(feature = "skip")]
-mod iterator_adapter_Flatten {
+mod iterator_adapter_Chain {
+    // Step 1: Define a struct for the custom adapter.
+    //
+    // Each half is dropped once it runs out, so neither is asked for more
+    // items after returning `None`.
+    #[derive(Clone, Debug)]
+    pub struct Chain<A, B> {
+        a: Option<A>,
+        b: Option<B>,
+    }
 
-    struct Flatten<I>
+    // Step 2: Implement `Iterator` for the custom adapter.
+    impl<A, B> Iterator for Chain<A, B>
     where
-        I: Iterator,
+        A: Iterator,
+        B: Iterator<Item = A::Item>,
     {
-        orig: I,
-    }
+        type Item = A::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if let Some(a) = &mut self.a {
+                match a.next() {
+                    None => self.a = None,
+                    item => return item,
+                }
+            }
+            self.b.as_mut()?.next()
+        }
 
-    // Step 2: Implement Iterator for the custom adapter.
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let hint = |it: Option<(usize, Option<usize>)>| it.unwrap_or((0, Some(0)));
+            let (a_lower, a_upper) = hint(self.a.as_ref().map(Iterator::size_hint));
+            let (b_lower, b_upper) = hint(self.b.as_ref().map(Iterator::size_hint));
+
+            let lower = a_lower.saturating_add(b_lower);
+            let upper = match (a_upper, b_upper) {
+                (Some(a), Some(b)) => a.checked_add(b),
+                _ => None,
+            };
+            (lower, upper)
+        }
 
-    /*
-     * TODO
-     */
+        fn fold<Acc, G>(self, init: Acc, mut g: G) -> Acc
+        where
+            G: FnMut(Acc, Self::Item) -> Acc,
+        {
+            let mut acc = init;
+            if let Some(a) = self.a {
+                acc = a.fold(acc, &mut g);
+            }
+            if let Some(b) = self.b {
+                acc = b.fold(acc, &mut g);
+            }
+            acc
+        }
+    }
 
     // Step 3: Define a new extension trait with the new operator to be
-    //         added, as a sub-trait of Iterator.
+    //         added, as a sub-trait of `Iterator`.
+    pub trait ChainExt: Iterator {
+        fn fchain<B>(self, other: B) -> Chain<Self, B::IntoIter>
+        where
+            Self: Sized,
+            B: IntoIterator<Item = Self::Item>;
+    }
 
-    /*
-     * TODO
-     */
+    // Step 4: Implement the trait for all types that implement `Iterator`.
+    impl<I: Iterator> ChainExt for I {
+        fn fchain<B>(self, other: B) -> Chain<Self, B::IntoIter>
+        where
+            B: IntoIterator<Item = Self::Item>,
+        {
+            Chain {
+                a: Some(self),
+                b: Some(other.into_iter()),
+            }
+        }
+    }
 
-    // Step 4: Implement the trait for all types that implement Iterator.
+    #[test]
+    fn test() {
+        let chained: Vec<_> = vec![1, 2, 3].into_iter().fchain([4, 5, 6]).collect();
 
-    /*
-     * TODO
-     */
+        assert_eq!(chained, [1, 2, 3, 4, 5, 6]);
+    }
 
     #[test]
-    fn test() {
-        let vs = vec![vec![1, 2], vec![3, 4]];
+    fn test_clone_for_cartesian_product() {
+        // A nested loop over a cloned adapter is how `cartesian_product`
+        // style adapters work: the inner iterator restarts for every outer item.
+        let letters = ['a'].into_iter().fchain(['b']);
+
+        let pairs: Vec<_> = (1..=2)
+            .flat_map(|n| letters.clone().map(move |c| (n, c)))
+            .collect();
+
+        assert_eq!(pairs, [(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]);
+        assert_eq!(
+            format!("{letters:?}"),
+            "Chain { a: Some(IntoIter(['a'])), b: Some(IntoIter(['b'])) }"
+        );
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut chained = (0..3).fchain(10..12);
+        assert_eq!(chained.size_hint(), (5, Some(5)));
+
+        chained.by_ref().take(4).for_each(drop);
+        assert_eq!(chained.size_hint(), (1, Some(1)));
+
+        assert_eq!((0..3).fchain(0..).size_hint(), (usize::MAX, None));
+    }
+}
+
+/// `fold` is the engine behind `sum`, `count`, `for_each`, `collect` and
+/// friends. These tests use a source that counts how it is driven, to show
+/// that folding over our adapters calls the source's own `fold` exactly once
+/// instead of pulling items through `next()`. The `Map` and `Unique`
+/// exercises check their answers with the same source.
+pub(crate) mod fold_forwarding {
+    use super::iterator_adapter_Chain::ChainExt;
+    use super::iterator_adapter_Filter::FilterExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    pub(crate) struct Calls {
+        pub(crate) next: Cell<usize>,
+        pub(crate) fold: Cell<usize>,
+    }
+
+    pub(crate) struct Source {
+        items: std::vec::IntoIter<i32>,
+        calls: Rc<Calls>,
+    }
+
+    pub(crate) fn source(items: Vec<i32>) -> (Source, Rc<Calls>) {
+        let calls = Rc::new(Calls::default());
+        let source = Source {
+            items: items.into_iter(),
+            calls: Rc::clone(&calls),
+        };
+        (source, calls)
+    }
+
+    impl Iterator for Source {
+        type Item = i32;
+
+        fn next(&mut self) -> Option<i32> {
+            self.calls.next.set(self.calls.next.get() + 1);
+            self.items.next()
+        }
+
+        fn fold<Acc, G>(self, init: Acc, g: G) -> Acc
+        where
+            G: FnMut(Acc, i32) -> Acc,
+        {
+            self.calls.fold.set(self.calls.fold.get() + 1);
+            self.items.fold(init, g)
+        }
+    }
+
+    #[test]
+    fn filter_forwards_fold() {
+        let (src, calls) = source(vec![1, 2, 3, 4]);
 
-        let result: Vec<_> = vs.into_iter().flatten().collect();
+        let evens: Vec<_> = src.ffilter(|x| x % 2 == 0).fold(Vec::new(), |mut v, x| {
+            v.push(x);
+            v
+        });
+
+        assert_eq!(evens, [2, 4]);
+        assert_eq!((calls.next.get(), calls.fold.get()), (0, 1));
+    }
+
+    #[test]
+    fn chain_forwards_fold_to_both_halves() {
+        let (a, a_calls) = source(vec![1, 2]);
+        let (b, b_calls) = source(vec![3]);
+        let mut visited = 0;
+
+        a.fchain(b)
+            .ffilter(|x| x % 2 == 1)
+            .for_each(|_| visited += 1);
+
+        assert_eq!(visited, 2);
+        assert_eq!((a_calls.next.get(), a_calls.fold.get()), (0, 1));
+        assert_eq!((b_calls.next.get(), b_calls.fold.get()), (0, 1));
+    }
+
+    #[test]
+    fn short_circuiting_uses_next() {
+        // `find` is built on `try_fold`, which can't be overridden on stable
+        // Rust, so it pulls items one at a time, and stops as soon as it can.
+        let (src, calls) = source(vec![1, 2, 3, 4]);
 
-        assert_eq!(result, [1, 2, 3, 4]);
+        assert_eq!(src.ffilter(|x| x % 2 == 0).find(|&x| x == 2), Some(2));
+        assert_eq!((calls.next.get(), calls.fold.get()), (2, 0));
     }
 }
 
+// Writing a `Flatten` adapter the same way is an exercise: see
+// `exercises::flatten`.
+
 /// The `FromIterator` trait allows for a collection to be built from an iterator.
 /// By implementing `FromIterator` for a type, you define how it will be created
 /// from an iterator. This is common for types which describe a collection of some kind.
@@ -146,60 +385,40 @@ mod from_iterator_in_std {
 
 #[test]
 fn from_iter_demo() {
-    // A sample collection, that's just a wrapper over `Vec<T>`
-    #[derive(Debug)]
-    struct MyCollection(Vec<i32>);
-
-    // Let's give it some methods so we can create one and add things to it.
-    impl MyCollection {
-        fn new() -> MyCollection {
-            MyCollection(Vec::new())
-        }
-
-        fn add(&mut self, elem: i32) {
-            self.0.push(elem);
-        }
-    }
-
-    // and we'll implement FromIterator
-    impl FromIterator<i32> for MyCollection {
-        fn from_iter<I: IntoIterator<Item = i32>>(iter: I) -> Self {
-            let mut my_collection = MyCollection::new();
-
-            for i in iter {
-                my_collection.add(i);
-            }
-            my_collection
-        }
-    }
+    // A sample collection, that's just a wrapper over `Vec<T>`. It started
+    // out here as `MyCollection(Vec<i32>)`; see `crate::collections::my_vec`
+    // for its methods (`new`, `add`, ...) and the `FromIterator` impl:
+    //
+    //     impl<T> FromIterator<T> for MyVec<T> {
+    //         fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    //             let mut my_vec = MyVec::new();
+    //             my_vec.extend(iter);
+    //             my_vec
+    //         }
+    //     }
+    use crate::collections::MyVec;
 
     // Now we can make a new iterator...
     let iter = 0..5;
 
-    // ... and make a MyCollection out of it
-    let collection = MyCollection::from_iter(iter);
+    // ... and make a MyVec out of it
+    let collection = MyVec::from_iter(iter);
 
-    assert_eq!(collection.0, vec![0, 1, 2, 3, 4]);
+    assert_eq!(collection.as_slice(), [0, 1, 2, 3, 4]);
 
     // collect works too!
 
     let iter = 0..5;
-    let c: MyCollection = iter.collect();
+    let c: MyVec<i32> = iter.collect();
 
-    assert_eq!(c.0, vec![0, 1, 2, 3, 4]);
+    assert_eq!(c.as_slice(), [0, 1, 2, 3, 4]);
 }
 
 #[test]
 fn from_iter_exercise() {
-    #[derive(Debug)]
-    enum List {
-        Cons(i32, Box<List>),
-        Nil,
-    }
+    // The finished cons list lives in `crate::collections::list`.
+    use crate::collections::List;
 
-    /*
-     * TODO: Implement `FromIterator` for `List`.
-     */
     let src = vec![1, 2, 3];
 
     // Cannot directly collect to array.
@@ -212,6 +431,8 @@ fn from_iter_exercise() {
     println!("list = {:?}", list);
 
     // Having implemented `FromIterator`, we can collect into a `List`.
-    // let list: List = src.iter().map(|item: &i32| item * 2).rev().collect();
+    let list: List<i32> = src.iter().map(|item: &i32| item * 2).collect();
     println!("list = {:?}", list);
+
+    assert_eq!(list.into_iter().collect::<Vec<_>>(), [2, 4, 6]);
 }
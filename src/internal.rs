@@ -0,0 +1,306 @@
+//! Internal (push-based) iteration: instead of the consumer calling `next()`
+//! for each item, the iterator calls the consumer for each item, and the
+//! consumer says whether to keep going with a `ControlFlow`.
+//!
+//! An external iterator has to stop after each item and save where it was,
+//! so that `next()` can pick up from there. For a deep stack of adapters,
+//! such as nested `flat_map`s, each `next()` walks down the whole stack to
+//! find the place again. An internal iterator never stops in the middle: a
+//! nested `flat_map` is just nested loops, and the compiler sees them as
+//! such.
+//!
+//! That is also why std's own adapters override `fold` and `try_fold`
+//! (see `fold_forwarding` in `i6_iterator_adapters`): `for_each`, `sum`,
+//! `find` and friends are built on them, so an adapter that forwards them
+//! turns a pull-based pipeline into a push-based one for the whole run.
+//!
+//! Every `Iterator`, including the ones in `sources`, is an
+//! `InternalIterator` through `try_for_each`, which drives it with
+//! `try_fold`. The adapter methods carry an `_internal` suffix so that they
+//! don't clash with `Iterator`'s.
+
+use core::ops::ControlFlow;
+
+pub trait InternalIterator: Sized {
+    type Item;
+
+    /// Calls `f` on each item until it returns `Break`, and returns that
+    /// `Break`, or `Continue(())` if every item was seen.
+    fn try_for_each_internal<B, F>(self, f: F) -> ControlFlow<B>
+    where
+        F: FnMut(Self::Item) -> ControlFlow<B>;
+
+    /// Calls `f` on every item. The default goes through
+    /// `try_for_each_internal`, but an iterator that never needs to stop
+    /// early can do better, so the adapters below override it.
+    fn for_each_internal<F>(self, mut f: F)
+    where
+        F: FnMut(Self::Item),
+    {
+        let _ = self.try_for_each_internal(|item| {
+            f(item);
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    fn map_internal<B, F>(self, f: F) -> Map<Self, F>
+    where
+        F: FnMut(Self::Item) -> B,
+    {
+        Map { orig: self, f }
+    }
+
+    fn filter_internal<P>(self, pred: P) -> Filter<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        Filter { orig: self, pred }
+    }
+
+    fn flat_map_internal<U, F>(self, f: F) -> FlatMap<Self, F>
+    where
+        U: InternalIterator,
+        F: FnMut(Self::Item) -> U,
+    {
+        FlatMap { orig: self, f }
+    }
+
+    fn find_internal<P>(self, mut pred: P) -> Option<Self::Item>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        match self.try_for_each_internal(|item| {
+            if pred(&item) {
+                ControlFlow::Break(item)
+            } else {
+                ControlFlow::Continue(())
+            }
+        }) {
+            ControlFlow::Break(item) => Some(item),
+            ControlFlow::Continue(()) => None,
+        }
+    }
+
+    fn sum_internal<S>(self) -> S
+    where
+        S: core::ops::AddAssign<Self::Item> + Default,
+    {
+        let mut total = S::default();
+        self.for_each_internal(|item| total += item);
+        total
+    }
+}
+
+impl<I: Iterator> InternalIterator for I {
+    type Item = I::Item;
+
+    fn try_for_each_internal<B, F>(mut self, f: F) -> ControlFlow<B>
+    where
+        F: FnMut(Self::Item) -> ControlFlow<B>,
+    {
+        self.try_for_each(f)
+    }
+
+    fn for_each_internal<F>(self, f: F)
+    where
+        F: FnMut(Self::Item),
+    {
+        self.for_each(f)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map<I, F> {
+    orig: I,
+    f: F,
+}
+
+impl<I, F, B> InternalIterator for Map<I, F>
+where
+    I: InternalIterator,
+    F: FnMut(I::Item) -> B,
+{
+    type Item = B;
+
+    fn try_for_each_internal<C, G>(self, mut g: G) -> ControlFlow<C>
+    where
+        G: FnMut(B) -> ControlFlow<C>,
+    {
+        let mut f = self.f;
+        self.orig.try_for_each_internal(|item| g(f(item)))
+    }
+
+    fn for_each_internal<G>(self, mut g: G)
+    where
+        G: FnMut(B),
+    {
+        let mut f = self.f;
+        self.orig.for_each_internal(|item| g(f(item)))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter<I, P> {
+    orig: I,
+    pred: P,
+}
+
+impl<I, P> InternalIterator for Filter<I, P>
+where
+    I: InternalIterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn try_for_each_internal<C, G>(self, mut g: G) -> ControlFlow<C>
+    where
+        G: FnMut(I::Item) -> ControlFlow<C>,
+    {
+        let mut pred = self.pred;
+        self.orig.try_for_each_internal(|item| {
+            if pred(&item) {
+                g(item)
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+    }
+
+    fn for_each_internal<G>(self, mut g: G)
+    where
+        G: FnMut(I::Item),
+    {
+        let mut pred = self.pred;
+        self.orig.for_each_internal(|item| {
+            if pred(&item) {
+                g(item)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlatMap<I, F> {
+    orig: I,
+    f: F,
+}
+
+impl<I, F, U> InternalIterator for FlatMap<I, F>
+where
+    I: InternalIterator,
+    U: InternalIterator,
+    F: FnMut(I::Item) -> U,
+{
+    type Item = U::Item;
+
+    fn try_for_each_internal<C, G>(self, mut g: G) -> ControlFlow<C>
+    where
+        G: FnMut(U::Item) -> ControlFlow<C>,
+    {
+        // No saved inner iterator: each one runs to the end (or the break)
+        // inside the outer loop.
+        let mut f = self.f;
+        self.orig
+            .try_for_each_internal(|item| f(item).try_for_each_internal(&mut g))
+    }
+
+    fn for_each_internal<G>(self, mut g: G)
+    where
+        G: FnMut(U::Item),
+    {
+        let mut f = self.f;
+        self.orig
+            .for_each_internal(|item| f(item).for_each_internal(&mut g))
+    }
+}
+
+#[cfg(test)]
+use crate::sources::{iterate, Linspace, StepCounter};
+
+#[test]
+fn internal_adapters() {
+    let mut seen = Vec::new();
+    (1..=10)
+        .filter_internal(|x| x % 3 != 0)
+        .map_internal(|x| x * 10)
+        .for_each_internal(|x| seen.push(x));
+    assert_eq!(seen, [10, 20, 40, 50, 70, 80, 100]);
+
+    let pairs = (0..3).flat_map_internal(|i| (0..i).map_internal(move |j| (i, j)));
+    let mut all = Vec::new();
+    pairs.for_each_internal(|p| all.push(p));
+    assert_eq!(all, [(1, 0), (2, 0), (2, 1)]);
+}
+
+#[test]
+fn internal_iteration_breaks_early() {
+    let mut visited = 0;
+    let found = (0..)
+        .flat_map_internal(|i| (0..i))
+        .map_internal(|x| {
+            visited += 1;
+            x
+        })
+        .find_internal(|&x| x == 3);
+    assert_eq!(found, Some(3));
+    // 0, 0 1, 0 1 2, 0 1 2 3.
+    assert_eq!(visited, 10);
+}
+
+#[test]
+fn internal_iteration_over_sources() {
+    assert_eq!(StepCounter::new(0, 10, 3).sum_internal::<i64>(), 18);
+    assert_eq!(Linspace::new(0.0, 1.0, 5).sum_internal::<f64>(), 2.5);
+    assert_eq!(
+        iterate(1u64, |x| x * 2).find_internal(|&x| x > 1000),
+        Some(1024)
+    );
+}
+
+/// The same four-deep nest of `flat_map`s, pulled item by item with
+/// `next()`, and pushed through with internal iteration: both visit every
+/// item once, in the same order, and both stop at the same item when told
+/// to. (Which is faster is for `cargo bench`, not for a test.)
+#[test]
+fn deep_nesting_pull_vs_push() {
+    use core::cell::Cell;
+
+    const N: u64 = 12;
+    let visited = Cell::new(0u64);
+    let visit = |a: u64, b: u64, c: u64, d: u64| {
+        visited.set(visited.get() + 1);
+        ((a * N + b) * N + c) * N + d
+    };
+
+    let pulled = (0..N).flat_map(|a| {
+        (0..N).flat_map(move |b| (0..N).flat_map(move |c| (0..N).map(move |d| visit(a, b, c, d))))
+    });
+    let mut pull_order = Vec::new();
+    for x in pulled {
+        pull_order.push(x);
+    }
+    assert_eq!(visited.replace(0), N.pow(4));
+    assert!(pull_order.iter().copied().eq(0..N.pow(4)));
+
+    let nested = || {
+        (0..N).flat_map_internal(|a| {
+            (0..N).flat_map_internal(move |b| {
+                (0..N).flat_map_internal(move |c| (0..N).map_internal(move |d| visit(a, b, c, d)))
+            })
+        })
+    };
+    let mut push_order = Vec::new();
+    nested().for_each_internal(|x| push_order.push(x));
+    assert_eq!(visited.replace(0), N.pow(4));
+    assert_eq!(push_order, pull_order);
+
+    // Stopping partway: neither reads past the item it stops at.
+    let target = N.pow(3) + 5;
+    assert_eq!(nested().find_internal(|&x| x == target), Some(target));
+    assert_eq!(visited.replace(0), target + 1);
+    let mut pulled = (0..N).flat_map(|a| {
+        (0..N).flat_map(move |b| (0..N).flat_map(move |c| (0..N).map(move |d| visit(a, b, c, d))))
+    });
+    assert_eq!(pulled.find(|&x| x == target), Some(target));
+    assert_eq!(visited.get(), target + 1);
+}
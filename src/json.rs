@@ -0,0 +1,503 @@
+//! Just enough JSON for the crate's own use: a `JsonValue` tree, a parser
+//! for it (`str::parse`), and a writer (`Display`). With the `serde`
+//! feature, `to_string` and `from_str` go between JSON text and any type
+//! implementing `Serialize` / `Deserialize`, by way of a `JsonValue`. With
+//! `std`, `JsonArrayIter` reads a huge top-level array one element at a time.
+//!
+//! Integers are kept exactly, as `i128`s, so that a `u64` or an `i64`
+//! survives the trip however big it is; any other number is an `f64`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "std")]
+mod stream;
+
+#[cfg(feature = "serde")]
+pub use de::{from_str, from_value};
+#[cfg(feature = "serde")]
+pub use ser::{to_string, to_value};
+#[cfg(feature = "std")]
+pub use stream::JsonArrayIter;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    /// A number written without a fraction or an exponent.
+    Int(i128),
+    /// Any other number, or an integer too big for an `i128`.
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// The members in the order they were written. A repeated key is kept
+    /// twice; `get` finds the first.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// The member called `key`, if this is an object that has one.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            JsonValue::Int(n) => Some(n as f64),
+            JsonValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonError {
+    /// The input isn't JSON. `offset` counts bytes from the start.
+    Syntax {
+        offset: usize,
+        message: &'static str,
+    },
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// The input is JSON, but not the JSON the type being read expects.
+    Custom(String),
+    /// Reading the input failed.
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Syntax { offset, message } => write!(f, "byte {offset}: {message}"),
+            JsonError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            JsonError::Custom(message) => f.write_str(message),
+            #[cfg(feature = "std")]
+            JsonError::Io(kind) => write!(f, "read error: {kind}"),
+        }
+    }
+}
+
+impl core::error::Error for JsonError {}
+
+/// How many arrays and objects deep a value may be. Each level is a
+/// recursive call, so without a limit a long run of `[` would overflow the
+/// stack.
+const MAX_DEPTH: usize = 128;
+
+/// A recursive-descent parser over a stream of bytes. It only ever looks
+/// one byte ahead, so the bytes can come from anywhere, a bit at a time.
+pub(crate) struct Parser<I> {
+    bytes: I,
+    peeked: Option<u8>,
+    // The offset of the next byte `bump` will return.
+    offset: usize,
+    // How many arrays and objects the parser is inside.
+    depth: usize,
+}
+
+impl<I> Parser<I>
+where
+    I: Iterator<Item = Result<u8, JsonError>>,
+{
+    pub(crate) fn new(bytes: I) -> Self {
+        Parser {
+            bytes,
+            peeked: None,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    pub(crate) fn peek(&mut self) -> Result<Option<u8>, JsonError> {
+        if self.peeked.is_none() {
+            self.peeked = self.bytes.next().transpose()?;
+        }
+        Ok(self.peeked)
+    }
+
+    pub(crate) fn bump(&mut self) -> Result<Option<u8>, JsonError> {
+        let byte = self.peek()?;
+        self.peeked = None;
+        if byte.is_some() {
+            self.offset += 1;
+        }
+        Ok(byte)
+    }
+
+    /// An error at the byte `peek` would return.
+    pub(crate) fn error(&self, message: &'static str) -> JsonError {
+        JsonError::Syntax {
+            offset: self.offset,
+            message,
+        }
+    }
+
+    // An error at the byte `bump` just returned.
+    fn error_at_last(&self, message: &'static str) -> JsonError {
+        JsonError::Syntax {
+            offset: self.offset - 1,
+            message,
+        }
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) -> Result<(), JsonError> {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek()? {
+            self.bump()?;
+        }
+        Ok(())
+    }
+
+    // Consumes `byte`, or fails with `message` if something else is next.
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), JsonError> {
+        match self.peek()? {
+            Some(b) if b == byte => {
+                self.bump()?;
+                Ok(())
+            }
+            Some(_) => Err(self.error(message)),
+            None => Err(JsonError::UnexpectedEnd),
+        }
+    }
+
+    /// Parses one value, skipping whitespace before it but not after.
+    pub(crate) fn parse_value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace()?;
+        match self.peek()? {
+            None => Err(JsonError::UnexpectedEnd),
+            Some(b'n') => self.parse_word("null", JsonValue::Null),
+            Some(b't') => self.parse_word("true", JsonValue::Bool(true)),
+            Some(b'f') => self.parse_word("false", JsonValue::Bool(false)),
+            Some(b'"') => self.parse_string().map(JsonValue::String),
+            Some(b'[' | b'{') if self.depth == MAX_DEPTH => Err(self.error("nesting too deep")),
+            Some(b'[') => self.nested(Self::parse_array),
+            Some(b'{') => self.nested(Self::parse_object),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("expected a value")),
+        }
+    }
+
+    // Parses an array or object one level further in.
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<JsonValue, JsonError>,
+    ) -> Result<JsonValue, JsonError> {
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_word(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in word.bytes() {
+            self.expect(expected, "unknown literal")?;
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.offset;
+        let mut text = String::new();
+        while let Some(b @ (b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')) = self.peek()? {
+            text.push(b as char);
+            self.bump()?;
+        }
+        let invalid = JsonError::Syntax {
+            offset: start,
+            message: "invalid number",
+        };
+        if !is_json_number(text.as_bytes()) {
+            return Err(invalid);
+        }
+        if !text.contains(['.', 'e', 'E']) {
+            if let Ok(n) = text.parse() {
+                return Ok(JsonValue::Int(n));
+            }
+        }
+        text.parse().map(JsonValue::Number).map_err(|_| invalid)
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"', "expected a string")?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.bump()?.ok_or(JsonError::UnexpectedEnd)? {
+                b'"' => break,
+                b'\\' => {
+                    let c = self.parse_escape()?;
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                0..=0x1f => return Err(self.error_at_last("control character in string")),
+                b => bytes.push(b),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    // The character a backslash escape stands for; the backslash is gone.
+    fn parse_escape(&mut self) -> Result<char, JsonError> {
+        let c = match self.bump()?.ok_or(JsonError::UnexpectedEnd)? {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let unit = self.parse_hex4()?;
+                let code = if (0xD800..0xDC00).contains(&unit) {
+                    // A high surrogate only makes sense with a low one
+                    // right after it.
+                    self.expect(b'\\', "unpaired surrogate")?;
+                    self.expect(b'u', "unpaired surrogate")?;
+                    let low = self.parse_hex4()?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(self.error("unpaired surrogate"));
+                    }
+                    0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    unit
+                };
+                return char::from_u32(code).ok_or(self.error("unpaired surrogate"));
+            }
+            _ => return Err(self.error_at_last("unknown escape")),
+        };
+        Ok(c)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let b = self.bump()?.ok_or(JsonError::UnexpectedEnd)?;
+            let digit = (b as char)
+                .to_digit(16)
+                .ok_or(self.error_at_last("invalid \\u escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect(b'[', "expected an array")?;
+        let mut items = Vec::new();
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b']') {
+            self.bump()?;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace()?;
+            match self.bump()?.ok_or(JsonError::UnexpectedEnd)? {
+                b',' => continue,
+                b']' => return Ok(JsonValue::Array(items)),
+                _ => return Err(self.error_at_last("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect(b'{', "expected an object")?;
+        let mut members = Vec::new();
+        self.skip_whitespace()?;
+        if self.peek()? == Some(b'}') {
+            self.bump()?;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace()?;
+            let key = self.parse_string()?;
+            self.skip_whitespace()?;
+            self.expect(b':', "expected ':'")?;
+            members.push((key, self.parse_value()?));
+            self.skip_whitespace()?;
+            match self.bump()?.ok_or(JsonError::UnexpectedEnd)? {
+                b',' => continue,
+                b'}' => return Ok(JsonValue::Object(members)),
+                _ => return Err(self.error_at_last("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+/// Whether `s` follows JSON's number grammar:
+/// `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`.
+fn is_json_number(s: &[u8]) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+    let mut i = usize::from(s.first() == Some(&b'-'));
+    match digits(&s[i..]) {
+        0 => return false,
+        n if n > 1 && s[i] == b'0' => return false,
+        n => i += n,
+    }
+    if s.get(i) == Some(&b'.') {
+        match digits(&s[i + 1..]) {
+            0 => return false,
+            n => i += 1 + n,
+        }
+    }
+    if let Some(b'e' | b'E') = s.get(i) {
+        i += 1;
+        if let Some(b'+' | b'-') = s.get(i) {
+            i += 1;
+        }
+        match digits(&s[i..]) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+    i == s.len()
+}
+
+impl FromStr for JsonValue {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s.bytes().map(Ok));
+        let value = parser.parse_value()?;
+        parser.skip_whitespace()?;
+        match parser.peek()? {
+            None => Ok(value),
+            Some(_) => Err(parser.error("trailing characters")),
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Writes compact JSON: no whitespace between tokens.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => f.write_str("null"),
+            JsonValue::Bool(b) => write!(f, "{b}"),
+            JsonValue::Int(n) => write!(f, "{n}"),
+            // JSON has no infinities or NaN.
+            JsonValue::Number(n) if !n.is_finite() => f.write_str("null"),
+            // Whole numbers without the `.0`.
+            JsonValue::Number(n) if (-1e15..1e15).contains(n) && *n as i64 as f64 == *n => {
+                write!(f, "{}", *n as i64)
+            }
+            JsonValue::Number(n) => write!(f, "{n}"),
+            JsonValue::String(s) => write_string(f, s),
+            JsonValue::Array(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_char(']')
+            }
+            JsonValue::Object(members) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_json_values() {
+    use JsonValue::*;
+    let value: JsonValue = r#" {"a": [1, -2.5, 3e2], "b": {"c": null}, "d": "x\ny", "e": true} "#
+        .parse()
+        .unwrap();
+    assert_eq!(
+        value.get("a"),
+        Some(&Array(vec![Int(1), Number(-2.5), Number(300.0)]))
+    );
+    assert_eq!(value.get("b").and_then(|b| b.get("c")), Some(&Null));
+    assert_eq!(value.get("d").and_then(JsonValue::as_str), Some("x\ny"));
+    assert_eq!(value.get("e"), Some(&Bool(true)));
+    assert_eq!(value.get("f"), None);
+
+    let escaped: JsonValue = r#""\u00e9\ud83d\ude00\"\\\/""#.parse().unwrap();
+    assert_eq!(escaped.as_str(), Some("é😀\"\\/"));
+    assert_eq!("[]".parse(), Ok(Array(vec![])));
+    assert_eq!("{}".parse(), Ok(Object(vec![])));
+}
+
+#[test]
+fn parse_json_errors() {
+    let err = |s: &str| s.parse::<JsonValue>().unwrap_err();
+    let syntax = |offset, message| JsonError::Syntax { offset, message };
+    assert_eq!(err("[1, 2"), JsonError::UnexpectedEnd);
+    assert_eq!(err("[1 2]"), syntax(3, "expected ',' or ']'"));
+    assert_eq!(err("{\"a\" 1}"), syntax(5, "expected ':'"));
+    assert_eq!(err("nul"), JsonError::UnexpectedEnd);
+    assert_eq!(err("nulL"), syntax(3, "unknown literal"));
+    assert_eq!(err("01"), syntax(0, "invalid number"));
+    assert_eq!(err("1."), syntax(0, "invalid number"));
+    assert_eq!(err("[1] x"), syntax(4, "trailing characters"));
+    assert_eq!(err("\"\\ud800\""), syntax(7, "unpaired surrogate"));
+    assert_eq!(err("?"), syntax(0, "expected a value"));
+    assert_eq!(err("[1 2]").to_string(), "byte 3: expected ',' or ']'");
+
+    // Deep enough to overflow the stack, if the parser let it recurse.
+    assert_eq!(err(&"[".repeat(200_000)), syntax(128, "nesting too deep"));
+    let objects = format!("{}{}", "[{\"a\":".repeat(65), "}]".repeat(65));
+    assert_eq!(err(&objects), syntax(384, "nesting too deep"));
+    let allowed = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    assert!(allowed.parse::<JsonValue>().is_ok());
+}
+
+#[test]
+fn write_json_round_trips() {
+    let text = r#"{"name":"a \"b\"\n","n":[0,-1,2.5,1e300],"ok":false,"none":null}"#;
+    let value: JsonValue = text.parse().unwrap();
+    let written = value.to_string();
+    assert_eq!(written.parse::<JsonValue>(), Ok(value));
+    assert!(written.starts_with(r#"{"name":"a \"b\"\n","n":[0,-1,2.5,"#));
+    assert_eq!(JsonValue::Number(f64::NAN).to_string(), "null");
+    assert_eq!(JsonValue::String("\u{1}".into()).to_string(), "\"\\u0001\"");
+}
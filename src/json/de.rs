@@ -0,0 +1,192 @@
+//! `Deserialize` from a `JsonValue`: the value is its own `Deserializer`,
+//! telling the visitor what it holds (`deserialize_any`), and serde's
+//! primitive visitors take it from there.
+
+use super::{JsonError, JsonValue};
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+impl de::Error for JsonError {
+    fn custom<T: Display>(msg: T) -> Self {
+        JsonError::Custom(msg.to_string())
+    }
+}
+
+pub fn from_value<T: DeserializeOwned>(value: JsonValue) -> Result<T, JsonError> {
+    T::deserialize(value)
+}
+
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, JsonError> {
+    from_value(s.parse()?)
+}
+
+impl<'de> IntoDeserializer<'de, JsonError> for JsonValue {
+    type Deserializer = JsonValue;
+
+    fn into_deserializer(self) -> JsonValue {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for JsonValue {
+    type Error = JsonError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsonError> {
+        match self {
+            JsonValue::Null => visitor.visit_unit(),
+            JsonValue::Bool(b) => visitor.visit_bool(b),
+            // The integer visitors check that the number fits the type
+            // being read.
+            JsonValue::Int(n) => match (u64::try_from(n), i64::try_from(n)) {
+                (Ok(n), _) => visitor.visit_u64(n),
+                (_, Ok(n)) => visitor.visit_i64(n),
+                _ => visitor.visit_i128(n),
+            },
+            // So do whole numbers written with an exponent, like `3e2`.
+            JsonValue::Number(n) if (0.0..1.8e19).contains(&n) && n as u64 as f64 == n => {
+                visitor.visit_u64(n as u64)
+            }
+            JsonValue::Number(n) if (-9.2e18..0.0).contains(&n) && n as i64 as f64 == n => {
+                visitor.visit_i64(n as i64)
+            }
+            JsonValue::Number(n) => visitor.visit_f64(n),
+            JsonValue::String(s) => visitor.visit_string(s),
+            JsonValue::Array(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            JsonValue::Object(members) => {
+                let mut map = MapDeserializer::new(members.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, JsonError> {
+        match self {
+            JsonValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, JsonError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// A unit variant is its name; any other variant is a one-member
+    /// object, `{name: data}`.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, JsonError> {
+        match self {
+            JsonValue::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            JsonValue::Object(members) if members.len() == 1 => {
+                let (variant, value) = members.into_iter().next().unwrap();
+                visitor.visit_enum(Variant { variant, value })
+            }
+            _ => Err(JsonError::Custom(
+                "expected a string or a one-member object for an enum".into(),
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct Variant {
+    variant: String,
+    value: JsonValue,
+}
+
+impl<'de> de::EnumAccess<'de> for Variant {
+    type Error = JsonError;
+    type Variant = JsonValue;
+
+    fn variant_seed<S>(self, seed: S) -> Result<(S::Value, JsonValue), JsonError>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for JsonValue {
+    type Error = JsonError;
+
+    fn unit_variant(self) -> Result<(), JsonError> {
+        match self {
+            JsonValue::Null => Ok(()),
+            _ => Err(JsonError::Custom("expected null for a unit variant".into())),
+        }
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value, JsonError>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, JsonError> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, JsonError> {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+#[test]
+fn deserialize_std_types() {
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    assert_eq!(
+        from_str::<(u8, String, Vec<f64>)>(r#"[1, "two", [3.5]]"#),
+        Ok((1, "two".into(), vec![3.5]))
+    );
+    assert_eq!(from_str::<Option<i32>>("null"), Ok(None));
+    assert_eq!(from_str::<Option<i32>>("-7"), Ok(Some(-7)));
+
+    let map: BTreeMap<String, bool> = from_str(r#"{"a": true, "b": false}"#).unwrap();
+    assert_eq!(map.len(), 2);
+
+    // Integers past 2^53 are read back exactly.
+    assert_eq!(from_str::<u64>("18446744073709551615"), Ok(u64::MAX));
+    assert_eq!(from_str::<i64>("-9007199254740993"), Ok(-(1 << 53) - 1));
+    assert_eq!(
+        from_str::<i128>("-170141183460469231731687303715884105728"),
+        Ok(i128::MIN)
+    );
+
+    // Out of range, not whole, or the wrong length.
+    assert!(from_str::<u8>("256").is_err());
+    assert!(from_str::<i32>("1.5").is_err());
+    assert!(from_str::<(u8, u8)>("[1, 2, 3]").is_err());
+}
@@ -0,0 +1,390 @@
+//! `Serialize` into a `JsonValue`. Structs and maps become objects,
+//! sequences and tuples become arrays, and an enum variant with data
+//! becomes a one-member object keyed by the variant's name, as `serde_json`
+//! does it.
+
+use super::{JsonError, JsonValue};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use serde::ser::{self, Serialize};
+
+impl ser::Error for JsonError {
+    fn custom<T: Display>(msg: T) -> Self {
+        JsonError::Custom(msg.to_string())
+    }
+}
+
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<JsonValue, JsonError> {
+    value.serialize(ValueSerializer)
+}
+
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, JsonError> {
+    to_value(value).map(|json| json.to_string())
+}
+
+struct ValueSerializer;
+
+fn tagged(variant: &str, value: JsonValue) -> JsonValue {
+    JsonValue::Object(vec![(variant.to_string(), value)])
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = JsonValue;
+    type Error = JsonError;
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<JsonValue, JsonError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Int(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<JsonValue, JsonError> {
+        i128::try_from(v)
+            .map(JsonValue::Int)
+            .map_err(|_| JsonError::Custom(format!("{v} is too big for a JSON integer")))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<JsonValue, JsonError> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<JsonValue, JsonError> {
+        let bytes = v.iter().map(|&b| JsonValue::Int(b.into())).collect();
+        Ok(JsonValue::Array(bytes))
+    }
+
+    fn serialize_none(self) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsonValue, JsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsonValue, JsonError> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<JsonValue, JsonError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, JsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, JsonError> {
+        Ok(tagged(variant, to_value(value)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, JsonError> {
+        Ok(SerializeArray {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, JsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, JsonError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, JsonError> {
+        Ok(SerializeArray {
+            items: Vec::with_capacity(len),
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject, JsonError> {
+        Ok(SerializeObject {
+            members: Vec::new(),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, JsonError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<SerializeObject, JsonError> {
+        Ok(SerializeObject {
+            members: Vec::new(),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+struct SerializeArray {
+    items: Vec<JsonValue>,
+    // Set for a tuple variant, whose array is wrapped in `{variant: ...}`.
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.items.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<JsonValue, JsonError> {
+        let array = JsonValue::Array(self.items);
+        Ok(match self.variant {
+            Some(variant) => tagged(variant, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+struct SerializeObject {
+    members: Vec<(String, JsonValue)>,
+    // A map's key, waiting for its value.
+    key: Option<String>,
+    // Set for a struct variant, whose object is wrapped in `{variant: ...}`.
+    variant: Option<&'static str>,
+}
+
+impl SerializeObject {
+    fn finish(self) -> Result<JsonValue, JsonError> {
+        let object = JsonValue::Object(self.members);
+        Ok(match self.variant {
+            Some(variant) => tagged(variant, object),
+            None => object,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    /// JSON keys are strings, so only keys that serialize to a string or a
+    /// number are allowed; numbers are written out as text.
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), JsonError> {
+        let key = match to_value(key)? {
+            JsonValue::String(s) => s,
+            number @ (JsonValue::Int(_) | JsonValue::Number(_)) => number.to_string(),
+            _ => return Err(JsonError::Custom("map keys must be strings".into())),
+        };
+        self.key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        let key = self.key.take().expect("serialize_key comes first");
+        self.members.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), JsonError> {
+        self.members.push((key.to_string(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = JsonValue;
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), JsonError> {
+        self.members.push((key.to_string(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsonValue, JsonError> {
+        self.finish()
+    }
+}
+
+#[test]
+fn serialize_std_types() {
+    use alloc::collections::BTreeMap;
+    assert_eq!(to_string(&(1, "two", [3.5])).unwrap(), r#"[1,"two",[3.5]]"#);
+    assert_eq!(to_string(&Some(true)).unwrap(), "true");
+    assert_eq!(to_string(&None::<i32>).unwrap(), "null");
+
+    let map: BTreeMap<u8, Vec<char>> = [(1, vec!['a']), (2, vec![])].into_iter().collect();
+    assert_eq!(to_string(&map).unwrap(), r#"{"1":["a"],"2":[]}"#);
+
+    // Integers are written exactly, even past 2^53.
+    assert_eq!(to_string(&u64::MAX).unwrap(), "18446744073709551615");
+    assert_eq!(to_string(&[i64::MIN]).unwrap(), "[-9223372036854775808]");
+    assert_eq!(to_string(&((1u64 << 53) + 1)).unwrap(), "9007199254740993");
+    assert!(to_string(&u128::MAX).is_err());
+
+    let bad_keys: BTreeMap<(u8, u8), u8> = [((1, 2), 3)].into_iter().collect();
+    assert!(to_string(&bad_keys).is_err());
+}
@@ -0,0 +1,195 @@
+//! `JsonArrayIter` parses a top-level JSON array lazily: each `next()`
+//! reads just far enough to return the next element, so only one element is
+//! ever held in memory, however long the array is.
+
+use super::{JsonError, JsonValue, Parser};
+use std::io::{self, BufReader, Read};
+use std::iter::FusedIterator;
+
+type Bytes<R> =
+    std::iter::Map<io::Bytes<BufReader<R>>, fn(io::Result<u8>) -> Result<u8, JsonError>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // Before the `[`.
+    Start,
+    // After an element, before the `,` or `]` that follows it.
+    Rest,
+    // After the `]`, or after an error.
+    Done,
+}
+
+pub struct JsonArrayIter<R> {
+    parser: Parser<Bytes<R>>,
+    state: State,
+}
+
+impl<R: Read> JsonArrayIter<R> {
+    /// Reads the array from `reader`, which is buffered internally.
+    pub fn new(reader: R) -> Self {
+        let to_json: fn(io::Result<u8>) -> Result<u8, JsonError> =
+            |byte| byte.map_err(|err| JsonError::Io(err.kind()));
+        JsonArrayIter {
+            parser: Parser::new(BufReader::new(reader).bytes().map(to_json)),
+            state: State::Start,
+        }
+    }
+
+    // Moves past the separator after the previous element (or the `[`),
+    // and reports whether there is another element to read.
+    fn advance(&mut self) -> Result<bool, JsonError> {
+        let parser = &mut self.parser;
+        parser.skip_whitespace()?;
+        match self.state {
+            State::Start => {
+                parser.expect(b'[', "expected an array")?;
+                parser.skip_whitespace()?;
+                if parser.peek()? == Some(b']') {
+                    parser.bump()?;
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            State::Rest => match parser.bump()?.ok_or(JsonError::UnexpectedEnd)? {
+                b',' => Ok(true),
+                b']' => Ok(false),
+                _ => Err(parser.error_at_last("expected ',' or ']'")),
+            },
+            State::Done => Ok(false),
+        }
+    }
+
+    // After the `]` there may only be whitespace.
+    fn finish(&mut self) -> Result<(), JsonError> {
+        self.parser.skip_whitespace()?;
+        match self.parser.peek()? {
+            None => Ok(()),
+            Some(_) => Err(self.parser.error("trailing characters")),
+        }
+    }
+}
+
+impl<R: Read> Iterator for JsonArrayIter<R> {
+    type Item = Result<JsonValue, JsonError>;
+
+    /// The next element. After the first error, or after the closing `]`,
+    /// the iterator is done: a broken array can't be picked up again.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+        let element = match self.advance() {
+            Ok(true) => self.parser.parse_value(),
+            Ok(false) => {
+                self.state = State::Done;
+                return self.finish().err().map(Err);
+            }
+            Err(err) => Err(err),
+        };
+        self.state = if element.is_ok() {
+            State::Rest
+        } else {
+            State::Done
+        };
+        Some(element)
+    }
+}
+
+impl<R: Read> FusedIterator for JsonArrayIter<R> {}
+
+#[cfg(test)]
+fn read_all(json: &str) -> Vec<Result<JsonValue, JsonError>> {
+    JsonArrayIter::new(json.as_bytes()).collect()
+}
+
+#[test]
+fn json_array_iter_yields_each_element() {
+    use JsonValue::*;
+    let items = read_all(r#" [1, [2, [3]], {"a": {"b": []}}, "x", null] "#);
+    assert_eq!(
+        items,
+        [
+            Ok(Int(1)),
+            Ok(Array(vec![Int(2), Array(vec![Int(3)])])),
+            Ok(r#"{"a": {"b": []}}"#.parse().unwrap()),
+            Ok(String("x".into())),
+            Ok(Null),
+        ]
+    );
+    assert_eq!(read_all("[]"), []);
+    assert_eq!(read_all(" [ \n ] \n"), []);
+}
+
+#[test]
+fn json_array_iter_stops_at_the_first_error() {
+    let syntax = |offset, message| Err(JsonError::Syntax { offset, message });
+    assert_eq!(read_all("{}"), [syntax(0, "expected an array")]);
+    assert_eq!(read_all(""), [Err(JsonError::UnexpectedEnd)]);
+    assert_eq!(
+        read_all("[1 2, 3]"),
+        [Ok(JsonValue::Int(1)), syntax(3, "expected ',' or ']'")]
+    );
+    assert_eq!(
+        read_all("[1, [2, 3"),
+        [Ok(JsonValue::Int(1)), Err(JsonError::UnexpectedEnd)]
+    );
+    assert_eq!(
+        read_all("[1,]"),
+        [Ok(JsonValue::Int(1)), syntax(3, "expected a value")]
+    );
+    assert_eq!(
+        read_all("[1] [2]"),
+        [Ok(JsonValue::Int(1)), syntax(4, "trailing characters")]
+    );
+
+    let mut it = JsonArrayIter::new("[tru]".as_bytes());
+    assert_eq!(it.next(), Some(syntax(4, "unknown literal")));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn json_array_iter_reports_read_errors() {
+    struct Broken;
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::ConnectionReset.into())
+        }
+    }
+    let mut it = JsonArrayIter::new(b"[1, ".chain(Broken));
+    assert_eq!(it.next(), Some(Ok(JsonValue::Int(1))));
+    let err = it.next().unwrap().unwrap_err();
+    assert_eq!(err, JsonError::Io(io::ErrorKind::ConnectionReset));
+    assert_eq!(err.to_string(), "read error: connection reset");
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn json_array_iter_reads_lazily() {
+    // An array that never ends: `[0, 1, 2, ...`, made up as it is read.
+    struct Counting {
+        next: u64,
+        pending: Vec<u8>,
+    }
+    impl Read for Counting {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() {
+                self.pending = format!("{}, ", self.next).into_bytes();
+                self.next += 1;
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+    let endless = b"[".chain(Counting {
+        next: 0,
+        pending: Vec::new(),
+    });
+    let numbers: Vec<f64> = JsonArrayIter::new(endless)
+        .map(|item| item.unwrap().as_f64().unwrap())
+        .skip(1000)
+        .take(3)
+        .collect();
+    assert_eq!(numbers, [1000.0, 1001.0, 1002.0]);
+}
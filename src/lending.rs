@@ -0,0 +1,123 @@
+//! A lending iterator hands out items that borrow from the iterator itself,
+//! so each item has to be given back (dropped) before the next one can be
+//! asked for.
+//!
+//! `Iterator` can't express that: `type Item` is one type for the whole
+//! iteration, with no way to mention the borrow of `&mut self` in `next`.
+//! That is why std has `slice::windows` but no `windows_mut`; two
+//! overlapping `&mut [T]` windows alive at the same time would alias. With a
+//! generic associated type (GAT), `Item<'a>` can borrow from `self` for
+//! `'a`, and the borrow checker makes sure a window is dropped before the
+//! next one exists.
+//!
+//! A `for` loop needs `IntoIterator`, so lending iterators are driven with
+//! `while let Some(item) = iter.next()`. A provided `for_each` would need
+//! `F: for<'a> FnMut(Self::Item<'a>)`, and the `Self: 'a` on the GAT turns
+//! that into `Self: 'static`, a current limitation of the type system. So
+//! `for_each` is written per iterator instead, where the item type is known.
+
+pub trait LendingIterator {
+    type Item<'a>
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>>;
+
+    /// The number of items left.
+    fn count(mut self) -> usize
+    where
+        Self: Sized,
+    {
+        let mut n = 0;
+        while self.next().is_some() {
+            n += 1;
+        }
+        n
+    }
+}
+
+/// Overlapping mutable windows of `size` elements over a slice, moving one
+/// element at a time.
+#[derive(Debug)]
+pub struct WindowsMut<'s, T> {
+    slice: &'s mut [T],
+    size: usize,
+    // The start of the next window.
+    start: usize,
+}
+
+/// # Panics
+///
+/// Panics if `size` is 0.
+pub fn windows_mut<T>(slice: &mut [T], size: usize) -> WindowsMut<'_, T> {
+    assert!(size > 0, "window size must be positive");
+    WindowsMut {
+        slice,
+        size,
+        start: 0,
+    }
+}
+
+impl<'s, T> LendingIterator for WindowsMut<'s, T> {
+    type Item<'a>
+        = &'a mut [T]
+    where
+        Self: 'a;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let window = self.slice.get_mut(self.start..self.start + self.size)?;
+        self.start += 1;
+        Some(window)
+    }
+}
+
+impl<T> WindowsMut<'_, T> {
+    /// Calls `f` on each window in turn.
+    pub fn for_each<F>(mut self, mut f: F)
+    where
+        F: FnMut(&mut [T]),
+    {
+        while let Some(window) = self.next() {
+            f(window);
+        }
+    }
+}
+
+#[test]
+fn windows_mut_running_sum() {
+    // Each window sees the update the one before it made.
+    let mut v = [1, 2, 3, 4, 5];
+    windows_mut(&mut v, 2).for_each(|w| w[1] += w[0]);
+    assert_eq!(v, [1, 3, 6, 10, 15]);
+}
+
+#[test]
+fn windows_mut_overlap() {
+    let mut v: Vec<i32> = (0..6).collect();
+    let mut windows = windows_mut(&mut v, 3);
+    let first = windows.next().unwrap();
+    assert_eq!(first, [0, 1, 2]);
+    first[2] = 20;
+    // `first` can't be used from here on: the next window borrows the
+    // same elements.
+    let second = windows.next().unwrap();
+    assert_eq!(second, [1, 20, 3]);
+    second.reverse();
+    assert_eq!(windows.count(), 2);
+    assert_eq!(v, [0, 3, 20, 1, 4, 5]);
+}
+
+#[test]
+fn windows_mut_counts() {
+    let mut v = [0u8; 5];
+    assert_eq!(windows_mut(&mut v, 1).count(), 5);
+    assert_eq!(windows_mut(&mut v, 5).count(), 1);
+    assert_eq!(windows_mut(&mut v, 6).count(), 0);
+    assert_eq!(windows_mut(&mut [0u8; 0], 1).count(), 0);
+
+    // Smoothing in place: each interior element becomes the average of its
+    // neighbors, using the already-smoothed value on the left.
+    let mut signal = [0.0, 8.0, 0.0, 8.0, 0.0];
+    windows_mut(&mut signal, 3).for_each(|w| w[1] = (w[0] + w[2]) / 2.0);
+    assert_eq!(signal, [0.0, 0.0, 4.0, 2.0, 0.0]);
+}
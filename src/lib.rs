@@ -1,18 +1,71 @@
+// Without the `std` feature, the crate is `no_std` and only needs `alloc`.
+// Unit tests always run with `std`, for the test harness.
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 #![allow(unused)]
 
+extern crate alloc;
+
+// First, so that the macros can be used in every module below.
+#[macro_use]
+pub mod macros;
+
+// The lessons print as they go, and the mini project uses `rand` and
+// `HashSet`, so they all need `std`.
+//
+// The lessons also deliberately spell out the imperative forms that the
+// iterator versions replace, and keep their notes as loose doc comments,
+// so the lints allowed on them here are expected to fire.
+#[cfg(feature = "std")]
+#[allow(clippy::empty_line_after_doc_comments)]
 mod i1_arrays_and_vectors;
+#[cfg(feature = "std")]
+#[allow(
+    clippy::empty_line_after_doc_comments,
+    clippy::doc_lazy_continuation,
+    clippy::manual_inspect,
+    clippy::manual_repeat_n,
+    clippy::map_flatten,
+    clippy::needless_range_loop,
+    clippy::unnecessary_fold,
+    clippy::useless_vec
+)]
 mod i2_std_iterators;
-mod i3_mini_project;
+#[cfg(feature = "std")]
+pub mod i3_mini_project;
+#[cfg(feature = "std")]
+#[allow(clippy::while_let_loop, clippy::while_let_on_iterator)]
 mod i4_iterators;
+#[cfg(feature = "std")]
+#[allow(clippy::empty_line_after_doc_comments)]
 mod i5_custom_iterators;
+#[cfg(feature = "std")]
+#[allow(clippy::empty_line_after_doc_comments, clippy::useless_vec)]
 mod i6_iterator_adapters;
+// The lessons' exercises; see there for the `solutions` feature.
+#[cfg(feature = "std")]
+mod exercises;
 
-#[macro_export]
-macro_rules! delim {
-    () => {
-        println!("{}", "-".repeat(50));
-    };
-    ($len:expr) => {
-        println!("{}, " - ".repeat($len)");
-    };
-}
+pub mod adapters;
+#[cfg(feature = "proptest")]
+pub mod arbitrary_iter;
+#[cfg(feature = "async")]
+pub mod async_adapters;
+#[cfg(feature = "std")]
+pub mod bench;
+pub mod collections;
+pub mod combine;
+pub mod consumers;
+pub mod either;
+pub mod fallible;
+pub mod internal;
+pub mod json;
+pub mod lending;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod pipeline;
+pub mod sources;
+pub mod testing;
+pub mod transducer;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,535 @@
+//! The crate's macros. They are all `#[macro_export]`ed, so they live at
+//! the crate root: `iterators::izip!` and so on. The module itself is
+//! public for what `delim!` expands to.
+
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+
+/// Prints a separator line, for output that comes in sections:
+///
+/// - `delim!()`: 50 dashes,
+/// - `delim!(80)`: 80 dashes,
+/// - `delim!('=', 30)`: 30 `=`s,
+/// - `delim!("section title")`: the title in the middle of 50 dashes, and
+///   `delim!('=', "section title")` the same with `=`s.
+#[macro_export]
+macro_rules! delim {
+    () => {
+        $crate::delim!('-', $crate::macros::DELIM_WIDTH)
+    };
+    ($arg:expr) => {
+        $crate::delim!('-', $arg)
+    };
+    ($fill:expr, $arg:expr) => {
+        ::std::println!("{}", $crate::macros::DelimArg::delim_line($arg, $fill))
+    };
+}
+
+/// How wide `delim!` lines are unless told otherwise.
+pub const DELIM_WIDTH: usize = 50;
+
+/// What `delim!` takes: a width, or a title.
+pub trait DelimArg {
+    /// The line to print, made of `fill`.
+    fn delim_line(self, fill: char) -> String;
+}
+
+macro_rules! impl_delim_width {
+    ($($t:ty),*) => {$(
+        /// A line `self` characters wide. Negative widths print an empty line.
+        impl DelimArg for $t {
+            fn delim_line(self, fill: char) -> String {
+                core::iter::repeat_n(fill, usize::try_from(self).unwrap_or(0)).collect()
+            }
+        }
+    )*};
+}
+
+impl_delim_width!(i32, u32, i64, u64, usize);
+
+/// The title with a space either side, centered in a line `DELIM_WIDTH`
+/// wide; if the two sides can't be even, the right one gets the extra
+/// character. A title too long for the line is printed with one `fill` on
+/// each side.
+impl DelimArg for &str {
+    fn delim_line(self, fill: char) -> String {
+        let used = self.chars().count() + 2;
+        let padding = DELIM_WIDTH.saturating_sub(used).max(2);
+        let (left, right) = (padding / 2, padding - padding / 2);
+        let mut line: String = core::iter::repeat_n(fill, left).collect();
+        line.push(' ');
+        line.push_str(self);
+        line.push(' ');
+        line.extend(core::iter::repeat_n(fill, right));
+        line
+    }
+}
+
+impl DelimArg for &String {
+    fn delim_line(self, fill: char) -> String {
+        self.as_str().delim_line(fill)
+    }
+}
+
+#[test]
+fn delim_lines() {
+    assert_eq!(5.delim_line('-'), "-----");
+    assert_eq!(3usize.delim_line('='), "===");
+    assert_eq!((-2).delim_line('-'), "");
+    assert_eq!(DELIM_WIDTH.delim_line('-').len(), 50);
+
+    let title = "section title".delim_line('-');
+    assert_eq!(title.len(), DELIM_WIDTH);
+    assert_eq!(
+        title,
+        format!("{} section title {}", "-".repeat(17), "-".repeat(18))
+    );
+    assert_eq!(
+        "é".delim_line('=').chars().count(),
+        DELIM_WIDTH,
+        "width counts characters, not bytes"
+    );
+    let long = "x".repeat(60);
+    assert_eq!((&long).delim_line('~'), format!("~ {long} ~"));
+}
+
+#[test]
+fn delim_forms_compile() {
+    let width = 10usize;
+    let title = String::from("owned title");
+    delim!();
+    delim!(80);
+    delim!(width);
+    delim!('=', 30);
+    delim!("section title");
+    delim!('*', "starred");
+    delim!(&title);
+}
+
+/// Asserts that two iterables yield equal items, comparing them one by one
+/// like `Iterator::eq`. On failure the panic message shows the index and
+/// both items where they first differ, or which side ran out first.
+/// Extra arguments are a message, as for `assert_eq!`.
+#[macro_export]
+macro_rules! assert_iter_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::testing::assert_iter_eq($left, $right)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let ::core::option::Option::Some(mismatch) = $crate::testing::first_mismatch($left, $right) {
+            ::core::panic!(
+                "assertion `left == right` failed: {}\n{}",
+                ::core::format_args!($($arg)+),
+                mismatch
+            );
+        }
+    };
+}
+
+#[test]
+#[should_panic(
+    expected = "assertion `left == right` failed: evens\nright iterator ended after 2 items\n  left[2]: 4"
+)]
+fn assert_iter_eq_with_a_message() {
+    assert_iter_eq!((0..3).map(|n| n * 2), [0, 2], "evens");
+}
+
+/// Zips any number of iterators into flat tuples: `izip!(a, b, c)` yields
+/// `(a, b, c)` where `a.zip(b).zip(c)` would yield `((a, b), c)`. Each
+/// argument can be anything `IntoIterator`, and the result stops with the
+/// shortest one. A single argument is just turned into an iterator.
+#[macro_export]
+macro_rules! izip {
+    // The closure that flattens the nested pairs: each extra iterator
+    // wraps the pattern in one more pair and adds its item to the tuple.
+    (@closure $p:pat => $tup:expr) => {
+        |$p| $tup
+    };
+    (@closure $p:pat => ($($tup:tt)*), $_iter:expr $(, $tail:expr)*) => {
+        $crate::izip!(@closure ($p, b) => ($($tup)*, b) $(, $tail)*)
+    };
+
+    ($first:expr $(,)?) => {
+        ::core::iter::IntoIterator::into_iter($first)
+    };
+    ($first:expr, $second:expr $(,)?) => {
+        ::core::iter::Iterator::zip(::core::iter::IntoIterator::into_iter($first), $second)
+    };
+    ($first:expr $(, $rest:expr)* $(,)?) => {
+        ::core::iter::IntoIterator::into_iter($first)
+            $(.zip($rest))*
+            .map($crate::izip!(@closure a => (a) $(, $rest)*))
+    };
+}
+
+#[test]
+fn izip_flattens_tuples() {
+    let names = ["a", "b", "c"];
+    let counts = vec![1, 2, 3];
+    let flags = [true, false, true];
+    let zipped: Vec<(&str, i32, bool)> = izip!(names, counts, flags).collect();
+    assert_eq!(zipped, [("a", 1, true), ("b", 2, false), ("c", 3, true)]);
+
+    // Two iterators are just `zip`, and one is itself.
+    assert_iter_eq!(izip!(0..3, 3..6), [(0, 3), (1, 4), (2, 5)]);
+    assert_iter_eq!(izip!(vec![1, 2],), [1, 2]);
+}
+
+#[test]
+fn izip_stops_at_the_shortest() {
+    let zipped: Vec<_> = izip!(0.., "xyz".chars(), [1.5, 2.5], 10..20).collect();
+    assert_eq!(zipped, [(0, 'x', 1.5, 10), (1, 'y', 2.5, 11)]);
+    assert_eq!(izip!(0..5, 0..0, 0..5).count(), 0);
+
+    let eight = izip!(0..4, 1..4, 2..4, 3..4, 4..9, 5..9, 6..9, 7..9).next();
+    assert_eq!(eight, Some((0, 1, 2, 3, 4, 5, 6, 7)));
+    assert_eq!(
+        izip!(0..4, 1..4, 2..4, 3..4, 4..9, 5..9, 6..9, 7..9).count(),
+        1
+    );
+}
+
+/// Chains any number of iterables end to end: `chain!(a, b, c)` is
+/// `a.into_iter().chain(b).chain(c)`. With no arguments it is an empty
+/// iterator, whose item type comes from the context.
+#[macro_export]
+macro_rules! chain {
+    () => {
+        ::core::iter::empty()
+    };
+    ($first:expr $(, $rest:expr)* $(,)?) => {
+        ::core::iter::IntoIterator::into_iter($first)
+            $(.chain($rest))*
+    };
+}
+
+#[test]
+fn chain_arrays_ranges_and_vectors() {
+    let chained: Vec<i32> = chain!([1, 2], 3..5, vec![5, 6], Some(7)).collect();
+    assert_eq!(chained, [1, 2, 3, 4, 5, 6, 7]);
+    assert_iter_eq!(chain!(0..3), 0..3);
+    assert_iter_eq!(chain!(vec![1], [], 2..2, [2],), [1, 2]);
+
+    // It's a plain `Chain`, so it runs backwards too.
+    assert_iter_eq!(chain!(0..2, [5, 6]).rev(), [6, 5, 1, 0]);
+}
+
+#[test]
+fn chain_nothing() {
+    let mut nothing = chain!();
+    assert_eq!(nothing.next(), None::<char>);
+    let empty: Vec<u8> = chain!().collect();
+    assert!(empty.is_empty());
+}
+
+/// A comprehension, as in Python: `iter![x * 2; for x in 0..10; if x % 3 == 0]`
+/// yields `0, 6, 12, 18`. After the expression come any number of `for`
+/// and `if` clauses, separated by `;`, starting with a `for`. Later clauses
+/// see the variables of earlier ones, and the last `for` varies fastest.
+///
+/// A single `for` is a `map`, or a `filter_map` with an `if`; each `for`
+/// with more clauses after it is a `flat_map`. The closures are `move`, so
+/// refer to outside data by reference.
+#[macro_export]
+macro_rules! iter {
+    ($e:expr; for $($clauses:tt)+) => {
+        $crate::iter!(@clauses ($e) for $($clauses)+)
+    };
+
+    (@clauses ($e:expr) for $p:pat in $it:expr) => {
+        ::core::iter::Iterator::map(::core::iter::IntoIterator::into_iter($it), move |$p| $e)
+    };
+    (@clauses ($e:expr) for $p:pat in $it:expr; if $c:expr) => {
+        ::core::iter::Iterator::filter_map(
+            ::core::iter::IntoIterator::into_iter($it),
+            move |$p| if $c { ::core::option::Option::Some($e) } else { ::core::option::Option::None },
+        )
+    };
+    (@clauses ($e:expr) for $p:pat in $it:expr; $($rest:tt)+) => {
+        ::core::iter::Iterator::flat_map(
+            ::core::iter::IntoIterator::into_iter($it),
+            move |$p| $crate::iter!(@clauses ($e) $($rest)+),
+        )
+    };
+    // An `if` that isn't right after the last `for` decides whether the
+    // clauses after it run at all.
+    (@clauses ($e:expr) if $c:expr) => {
+        ::core::option::Option::into_iter(
+            if $c { ::core::option::Option::Some($e) } else { ::core::option::Option::None },
+        )
+    };
+    (@clauses ($e:expr) if $c:expr; $($rest:tt)+) => {
+        ::core::iter::Iterator::flatten(::core::option::Option::into_iter(if $c {
+            ::core::option::Option::Some($crate::iter!(@clauses ($e) $($rest)+))
+        } else {
+            ::core::option::Option::None
+        }))
+    };
+}
+
+#[test]
+fn iter_comprehension_single_loop() {
+    assert_iter_eq!(iter![x * 2; for x in 0..10; if x % 3 == 0], [0, 6, 12, 18]);
+    assert_iter_eq!(
+        iter![c.to_ascii_uppercase(); for c in "abc".chars()],
+        "ABC".chars()
+    );
+
+    let words = ["apple", "fig", "banana"];
+    let long: Vec<usize> = iter![w.len(); for w in &words; if w.len() > 3].collect();
+    assert_eq!(long, [5, 6]);
+
+    // Any pattern will do.
+    let pairs = [(1, 'a'), (2, 'b')];
+    assert_iter_eq!(iter![format!("{n}{c}"); for (n, c) in pairs], ["1a", "2b"]);
+}
+
+#[test]
+fn iter_comprehension_nested_loops() {
+    // The last `for` varies fastest, as in nested `for` loops.
+    let grid: Vec<(i32, char)> = iter![(y, x); for y in 0..2; for x in ['a', 'b']].collect();
+    assert_eq!(grid, [(0, 'a'), (0, 'b'), (1, 'a'), (1, 'b')]);
+
+    // Later clauses can use earlier variables.
+    let triangle: Vec<(u32, u32)> = iter![(i, j); for i in 0..4; for j in 0..i].collect();
+    assert_eq!(triangle, [(1, 0), (2, 0), (2, 1), (3, 0), (3, 1), (3, 2)]);
+
+    // Pythagorean triples, filtering between and after the loops.
+    let triples: Vec<(u32, u32, u32)> = iter![
+        (a, b, c);
+        for c in 1..=20;
+        for b in 1..c;
+        if b % 2 == 0 || c % 2 == 1;
+        for a in 1..b;
+        if a * a + b * b == c * c
+    ]
+    .collect();
+    assert_eq!(
+        triples,
+        [
+            (3, 4, 5),
+            (6, 8, 10),
+            (5, 12, 13),
+            (9, 12, 15),
+            (8, 15, 17),
+            (12, 16, 20)
+        ]
+    );
+
+    // An `if` between loops skips the inner loop, and one after an `if`
+    // still only filters.
+    let odd_rows = iter![(y, x); for y in 0..4; if y % 2 == 1; for x in 0..2];
+    assert_iter_eq!(odd_rows, [(1, 0), (1, 1), (3, 0), (3, 1)]);
+    assert_iter_eq!(
+        iter![x; for x in 0..20; if x % 2 == 0; if x % 3 == 0],
+        [0, 6, 12, 18]
+    );
+}
+
+/// A query in the style of C#'s LINQ, turned into the adapter calls it
+/// stands for:
+///
+/// ```text
+/// query!(from p in people, where p.age >= 18, order_by p.name.as_str(), select p.name)
+/// ```
+///
+/// After `from x in xs` come any of these clauses, separated by commas and
+/// applied in the order they are written:
+///
+/// - `where pred`: `filter`, with `x` bound to a reference to each item;
+/// - `order_by key` and `order_by_desc key`: a stable sort by `key`, with
+///   `x` bound to a reference; the key can borrow from it;
+/// - `select expr`: `map`, with `x` bound to each item. It has to be last;
+/// - `group_by key`: `(key, items)` pairs, one for each key, in the order
+///   each key first appears; see `macros::group_by`. It has to be last too.
+///
+/// `x` can be any pattern. `order_by` and `group_by` collect the items, so
+/// a query with either goes over its input as soon as the first item is
+/// asked for; the other clauses are as lazy as the adapters.
+#[macro_export]
+macro_rules! query {
+    (from $x:pat in $xs:expr $(, $($clauses:tt)+)?) => {
+        $crate::query!(@clauses ($x) (::core::iter::IntoIterator::into_iter($xs)) $($($clauses)+)?)
+    };
+
+    (@clauses ($x:pat) ($it:expr)) => {
+        $it
+    };
+    (@clauses ($x:pat) ($it:expr) where $pred:expr $(, $($rest:tt)+)?) => {
+        $crate::query!(@clauses ($x) (::core::iter::Iterator::filter($it, |$x| $pred)) $($($rest)+)?)
+    };
+    (@clauses ($x:pat) ($it:expr) order_by $key:expr $(, $($rest:tt)+)?) => {
+        $crate::query!(@clauses ($x) ($crate::macros::sorted_by($it, |a, b| {
+            let $x = a;
+            let ka = $key;
+            let $x = b;
+            ::core::cmp::Ord::cmp(&ka, &$key)
+        })) $($($rest)+)?)
+    };
+    (@clauses ($x:pat) ($it:expr) order_by_desc $key:expr $(, $($rest:tt)+)?) => {
+        $crate::query!(@clauses ($x) ($crate::macros::sorted_by($it, |a, b| {
+            let $x = a;
+            let ka = $key;
+            let $x = b;
+            ::core::cmp::Ord::cmp(&$key, &ka)
+        })) $($($rest)+)?)
+    };
+    (@clauses ($x:pat) ($it:expr) select $e:expr $(,)?) => {
+        ::core::iter::Iterator::map($it, |$x| $e)
+    };
+    (@clauses ($x:pat) ($it:expr) group_by $key:expr $(,)?) => {
+        $crate::macros::group_by($it, |$x| $key)
+    };
+}
+
+/// The items sorted with `compare`, keeping equal ones in the order they
+/// came; what `query!`'s `order_by` expands to.
+pub fn sorted_by<I, F>(iter: I, compare: F) -> vec::IntoIter<I::Item>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> core::cmp::Ordering,
+{
+    let mut items: Vec<_> = iter.collect();
+    items.sort_by(compare);
+    items.into_iter()
+}
+
+/// The items grouped by `key`: a `(key, items)` pair for each key, in the
+/// order the keys first appear, each with its items in the order they
+/// came. What `query!`'s `group_by` expands to.
+///
+/// Only `PartialEq` is asked of the keys, so each item's key is compared
+/// with the keys of the groups so far: this is meant for a handful of
+/// groups, not thousands.
+pub fn group_by<I, K, F>(iter: I, mut key: F) -> vec::IntoIter<(K, Vec<I::Item>)>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    let mut groups: Vec<(K, Vec<I::Item>)> = Vec::new();
+    for item in iter {
+        let k = key(&item);
+        match groups.iter_mut().find(|(g, _)| *g == k) {
+            Some((_, items)) => items.push(item),
+            None => groups.push((k, alloc::vec![item])),
+        }
+    }
+    groups.into_iter()
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+struct Person {
+    name: &'static str,
+    age: u32,
+    city: &'static str,
+}
+
+#[cfg(test)]
+fn people() -> Vec<Person> {
+    let person = |name, age, city| Person { name, age, city };
+    alloc::vec![
+        person("dee", 31, "oslo"),
+        person("al", 17, "rome"),
+        person("cy", 45, "oslo"),
+        person("bo", 31, "lima"),
+        person("eve", 12, "rome"),
+    ]
+}
+
+#[test]
+fn query_clauses_are_the_adapters() {
+    let people = people();
+
+    // `from` alone is `into_iter`.
+    assert_iter_eq!(query!(from p in &people), &people);
+    // `where` is `filter`.
+    assert_iter_eq!(
+        query!(from p in &people, where p.age >= 18),
+        people.iter().filter(|p| p.age >= 18)
+    );
+    // `select` is `map`.
+    assert_iter_eq!(
+        query!(from p in &people, select p.name),
+        people.iter().map(|p| p.name)
+    );
+    // `order_by` is a stable sort.
+    let mut by_age = people.clone();
+    by_age.sort_by_key(|p| p.age);
+    assert_iter_eq!(
+        query!(from p in people.clone(), order_by p.age),
+        by_age.clone()
+    );
+    by_age.sort_by_key(|p| core::cmp::Reverse(p.age));
+    assert_iter_eq!(
+        query!(from p in people.clone(), order_by_desc p.age),
+        by_age
+    );
+}
+
+#[test]
+fn query_clauses_combine_in_order() {
+    let people = people();
+    let adults: Vec<_> = query!(
+        from p in &people,
+        where p.age >= 18,
+        order_by p.name,
+        select p.name
+    )
+    .collect();
+    assert_eq!(adults, ["bo", "cy", "dee"]);
+
+    // Ties keep their order, so a second sort key goes first.
+    let oldest_first: Vec<_> = query!(
+        from p in &people,
+        order_by p.name,
+        order_by_desc p.age,
+        select (p.age, p.name),
+    )
+    .collect();
+    assert_eq!(
+        oldest_first,
+        [(45, "cy"), (31, "bo"), (31, "dee"), (17, "al"), (12, "eve")]
+    );
+
+    // Any pattern, and keys that borrow from the items.
+    let pairs = [("b", 2), ("a", 1), ("c", 3)];
+    assert_iter_eq!(
+        query!(from (name, n) in pairs, where *n > 1, order_by *name, select n * 10),
+        [20, 30]
+    );
+    let words = ["pear".to_string(), "fig".to_string()];
+    assert_iter_eq!(
+        query!(from w in &words, order_by w.as_str()),
+        [&words[1], &words[0]]
+    );
+}
+
+#[test]
+fn query_group_by() {
+    let people = people();
+    let by_city: Vec<_> = query!(from p in &people, group_by p.city)
+        .map(|(city, ps)| (city, ps.iter().map(|p| p.name).collect::<Vec<_>>()))
+        .collect();
+    assert_eq!(
+        by_city,
+        [
+            ("oslo", alloc::vec!["dee", "cy"]),
+            ("rome", alloc::vec!["al", "eve"]),
+            ("lima", alloc::vec!["bo"]),
+        ]
+    );
+
+    // After `where` and `order_by`.
+    let teens_and_adults: Vec<_> = query!(
+        from p in &people,
+        where p.age > 12,
+        order_by p.age,
+        group_by p.age >= 18
+    )
+    .map(|(adult, ps)| (adult, ps.len()))
+    .collect();
+    assert_eq!(teens_and_adults, [(false, 1), (true, 3)]);
+    assert_eq!(query!(from n in 0..0, group_by n % 2).count(), 0);
+}
@@ -0,0 +1,126 @@
+//! Parallel versions of the consumers that touch every element and do real
+//! work per element: sorting, counting, and picking the `k` smallest. They
+//! are built on rayon, and only compiled with the `parallel` feature.
+//!
+//! Each one returns exactly what its sequential counterpart would, so the
+//! two can be swapped freely; the tests check that on inputs big enough for
+//! rayon to actually split the work.
+
+use rayon::iter::{IterBridge, ParallelBridge, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+pub trait ParallelExt: Iterator {
+    /// Hands the items out to rayon's thread pool as they come. This is
+    /// `ParallelBridge::par_bridge` under a name that shows up in the docs
+    /// next to the consumers below.
+    fn par_bridge_demo(self) -> IterBridge<Self>
+    where
+        Self: Sized + Send,
+        Self::Item: Send,
+    {
+        self.par_bridge()
+    }
+
+    /// All the items, sorted. Equal items keep their order.
+    fn par_sorted(self) -> Vec<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord + Send,
+    {
+        let mut items: Vec<Self::Item> = self.collect();
+        items.par_sort();
+        items
+    }
+
+    /// How many times each item occurs.
+    fn par_counts(self) -> HashMap<Self::Item, usize>
+    where
+        Self: Sized + Send,
+        Self::Item: Hash + Eq + Send,
+    {
+        // Each thread counts its share into a map of its own; the maps are
+        // then merged pairwise.
+        self.par_bridge()
+            .fold(HashMap::new, |mut counts, item| {
+                *counts.entry(item).or_insert(0) += 1;
+                counts
+            })
+            .reduce(HashMap::new, |mut a, b| {
+                for (item, n) in b {
+                    *a.entry(item).or_insert(0) += n;
+                }
+                a
+            })
+    }
+
+    /// The `k` smallest items in ascending order, or all of them if there
+    /// are fewer than `k`.
+    fn par_k_smallest(self, k: usize) -> Vec<Self::Item>
+    where
+        Self: Sized + Send,
+        Self::Item: Ord + Send,
+    {
+        // A max-heap of at most `k` items per thread: anything bigger than
+        // the top can't be among the `k` smallest.
+        let keep = move |mut heap: BinaryHeap<Self::Item>, item| {
+            heap.push(item);
+            if heap.len() > k {
+                heap.pop();
+            }
+            heap
+        };
+        self.par_bridge()
+            .fold(BinaryHeap::new, keep)
+            .reduce(BinaryHeap::new, |a, b| b.into_iter().fold(a, keep))
+            .into_sorted_vec()
+    }
+}
+
+impl<I: Iterator> ParallelExt for I {}
+
+#[cfg(test)]
+fn large_input() -> Vec<u32> {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    let mut rng = StdRng::seed_from_u64(1372);
+    (0..200_000).map(|_| rng.gen_range(0..5_000)).collect()
+}
+
+#[test]
+fn par_sorted_matches_sort() {
+    let input = large_input();
+    let mut expected = input.clone();
+    expected.sort();
+    assert_eq!(input.into_iter().par_sorted(), expected);
+}
+
+#[test]
+fn par_counts_matches_sequential_counts() {
+    let input = large_input();
+    let mut expected = HashMap::new();
+    for &x in &input {
+        *expected.entry(x).or_insert(0) += 1;
+    }
+    assert_eq!(input.into_iter().par_counts(), expected);
+}
+
+#[test]
+fn par_k_smallest_matches_sort_and_truncate() {
+    let input = large_input();
+    let mut sorted = input.clone();
+    sorted.sort();
+    for k in [0, 1, 10, 1000] {
+        let expected = &sorted[..k];
+        assert_eq!(input.iter().copied().par_k_smallest(k), expected);
+    }
+    assert_eq!((0..5).rev().par_k_smallest(10), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn par_bridge_demo_sees_every_item() {
+    let input = large_input();
+    let sum: u64 = input.iter().par_bridge_demo().map(|&x| x as u64).sum();
+    assert_eq!(sum, input.iter().map(|&x| x as u64).sum());
+}
@@ -0,0 +1,301 @@
+//! A `Pipeline<T>` is a chain of adapters kept as data instead of as a
+//! type. A chain like `xs.map(double).filter(is_small)` is a
+//! `Filter<Map<..>>`: it runs fast, but all that can be done with it is
+//! run it. A pipeline's stages have names, and can be listed before it
+//! runs and measured while it runs:
+//!
+//! ```text
+//! let mut pipeline = Pipeline::new()
+//!     .stage("double", |n| n * 2)
+//!     .filter("small", |n| *n < 10);
+//! let out: Vec<i32> = pipeline.run(0..10).collect();
+//! ```
+//!
+//! `run` takes any input iterator and is itself an iterator, pushing each
+//! item through the stages in order, so it is as lazy as the chain would
+//! be. Its `report()` says, for each stage, how many items went in and
+//! came out and how long the stage took over them.
+//!
+//! The price is a boxed closure call per stage per item, and every stage
+//! going from `T` to `T`.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::time::{Duration, Instant};
+
+enum Op<T> {
+    Map(Box<dyn FnMut(T) -> T>),
+    Filter(Box<dyn FnMut(&T) -> bool>),
+}
+
+struct Stage<T> {
+    name: String,
+    op: Op<T>,
+}
+
+pub struct Pipeline<T> {
+    stages: Vec<Stage<T>>,
+}
+
+impl<T> Pipeline<T> {
+    /// A pipeline with no stages, which passes the items through.
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Adds a stage that turns each item into `f(item)`.
+    pub fn stage<F>(mut self, name: impl Into<String>, f: F) -> Self
+    where
+        F: FnMut(T) -> T + 'static,
+    {
+        self.stages.push(Stage {
+            name: name.into(),
+            op: Op::Map(Box::new(f)),
+        });
+        self
+    }
+
+    /// Adds a stage that only lets through the items `pred` holds for.
+    pub fn filter<P>(mut self, name: impl Into<String>, pred: P) -> Self
+    where
+        P: FnMut(&T) -> bool + 'static,
+    {
+        self.stages.push(Stage {
+            name: name.into(),
+            op: Op::Filter(Box::new(pred)),
+        });
+        self
+    }
+
+    /// The stages' names, in the order the items go through them.
+    pub fn stage_names(&self) -> impl Iterator<Item = &str> {
+        self.stages.iter().map(|stage| stage.name.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// The items of `input` after going through every stage.
+    pub fn run<I>(&mut self, input: I) -> Run<'_, I::IntoIter, T>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let reports = self
+            .stages
+            .iter()
+            .map(|stage| StageReport {
+                name: stage.name.clone(),
+                items_in: 0,
+                items_out: 0,
+                time: Duration::ZERO,
+            })
+            .collect();
+        Run {
+            input: input.into_iter(),
+            stages: &mut self.stages,
+            report: Report { stages: reports },
+        }
+    }
+}
+
+impl<T> Default for Pipeline<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists the stages by name: `Pipeline(["double", "small"])`.
+impl<T> fmt::Debug for Pipeline<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pipeline")
+            .field(&self.stage_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// How one stage did over a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub name: String,
+    pub items_in: usize,
+    pub items_out: usize,
+    /// The time spent in the stage's closure, over all its items.
+    pub time: Duration,
+}
+
+/// How each stage did over a run, in the order of the stages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub stages: Vec<StageReport>,
+}
+
+/// A line per stage: `double   10 in   10 out   1.2µs`.
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.stages.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{:width$}  {:>6} in  {:>6} out  {:>9.1?}",
+                stage.name, stage.items_in, stage.items_out, stage.time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A pipeline running over an input iterator.
+pub struct Run<'a, I, T> {
+    input: I,
+    stages: &'a mut [Stage<T>],
+    report: Report,
+}
+
+impl<I, T> Run<'_, I, T> {
+    /// How each stage has done so far.
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+}
+
+impl<I: fmt::Debug, T> fmt::Debug for Run<'_, I, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Run")
+            .field("input", &self.input)
+            .field("report", &self.report)
+            .finish()
+    }
+}
+
+impl<I, T> Iterator for Run<'_, I, T>
+where
+    I: Iterator<Item = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        'items: loop {
+            let mut item = self.input.next()?;
+            for (stage, report) in self.stages.iter_mut().zip(&mut self.report.stages) {
+                report.items_in += 1;
+                let start = Instant::now();
+                let keep = match &mut stage.op {
+                    Op::Map(f) => {
+                        item = f(item);
+                        true
+                    }
+                    Op::Filter(pred) => pred(&item),
+                };
+                report.time += start.elapsed();
+                if !keep {
+                    continue 'items;
+                }
+                report.items_out += 1;
+            }
+            return Some(item);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let filters = self
+            .stages
+            .iter()
+            .any(|stage| matches!(stage.op, Op::Filter(_)));
+        match self.input.size_hint() {
+            (_, upper) if filters => (0, upper),
+            hint => hint,
+        }
+    }
+}
+
+impl<I, T> FusedIterator for Run<'_, I, T> where I: FusedIterator<Item = T> {}
+
+#[cfg(test)]
+fn counts(report: &Report) -> Vec<(&str, usize, usize)> {
+    report
+        .stages
+        .iter()
+        .map(|s| (s.name.as_str(), s.items_in, s.items_out))
+        .collect()
+}
+
+#[test]
+fn pipeline_lists_its_stages() {
+    let pipeline = Pipeline::new()
+        .stage("double", |n: i32| n * 2)
+        .filter("small", |n| *n < 10)
+        .stage("negate", |n| -n);
+    assert_eq!(pipeline.len(), 3);
+    assert_iter_eq!(pipeline.stage_names(), ["double", "small", "negate"]);
+    assert_eq!(
+        format!("{pipeline:?}"),
+        r#"Pipeline(["double", "small", "negate"])"#
+    );
+    assert!(Pipeline::<u8>::default().is_empty());
+}
+
+#[test]
+fn pipeline_runs_like_the_adapter_chain() {
+    let mut pipeline = Pipeline::new()
+        .stage("double", |n: i32| n * 2)
+        .filter("small", |n| *n < 10)
+        .stage("negate", |n| -n);
+    let chain: Vec<i32> = (0..10)
+        .map(|n| n * 2)
+        .filter(|n| *n < 10)
+        .map(|n| -n)
+        .collect();
+    assert_eq!(pipeline.run(0..10).collect::<Vec<_>>(), chain);
+
+    // The same pipeline runs again, over any input.
+    assert_iter_eq!(pipeline.run(vec![4, 5, 1]), [-8, -2]);
+    assert_iter_eq!(Pipeline::new().run("ab".chars()), "ab".chars());
+}
+
+#[test]
+fn pipeline_reports_on_each_stage() {
+    let mut pipeline = Pipeline::new()
+        .stage("double", |n: i32| n * 2)
+        .filter("small", |n| *n < 10)
+        .stage("negate", |n| -n);
+    let mut run = pipeline.run(0..10);
+    assert_eq!(run.size_hint(), (0, Some(10)));
+    run.by_ref().take(2).for_each(drop);
+    assert_eq!(
+        counts(run.report()),
+        [("double", 2, 2), ("small", 2, 2), ("negate", 2, 2)]
+    );
+    run.by_ref().for_each(drop);
+    let report = run.report().clone();
+    assert_eq!(
+        counts(&report),
+        [("double", 10, 10), ("small", 10, 5), ("negate", 5, 5)]
+    );
+
+    let text = report.to_string();
+    assert_eq!(text.lines().count(), 3);
+    assert!(text.starts_with("double      10 in      10 out"), "{text}");
+    assert!(text.contains("small       10 in       5 out"), "{text}");
+}
+
+#[test]
+fn pipeline_times_each_stage() {
+    let mut pipeline = Pipeline::new()
+        .stage("fast", |n: u64| n + 1)
+        .stage("slow", |n| {
+            std::thread::sleep(Duration::from_millis(5));
+            n
+        });
+    let mut run = pipeline.run(0..4);
+    run.by_ref().for_each(drop);
+    let [fast, slow] = &run.report().stages[..] else {
+        panic!("two stages");
+    };
+    assert!(slow.time >= Duration::from_millis(20));
+    assert!(fast.time < slow.time);
+}
@@ -0,0 +1,28 @@
+//! Custom iterator sources in the style of `i5_custom_iterators`: a struct
+//! holding the iteration state, and an `Iterator` impl that advances it.
+
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod channel;
+pub mod checkpoint;
+pub mod digits;
+pub mod iterate;
+pub mod linspace;
+#[cfg(feature = "std")]
+pub mod passwords;
+pub mod step_counter;
+pub mod successors_until;
+pub mod unfold;
+
+pub use bits::{from_bits, BitOrder, Bits, Unsigned};
+#[cfg(feature = "std")]
+pub use channel::{recv_batches, recv_until_idle, RecvBatches, RecvUntilIdle};
+pub use checkpoint::Checkpoint;
+pub use digits::{from_digits, Digits};
+pub use iterate::{iterate, Iterate};
+pub use linspace::Linspace;
+#[cfg(feature = "std")]
+pub use passwords::{generate_passwords, PasswordBuilder, PasswordConfigError, PasswordGenerator};
+pub use step_counter::{StepCounter, StepCounterState};
+pub use successors_until::{successors_until, SuccessorsUntil};
+pub use unfold::{unfold, Unfold};
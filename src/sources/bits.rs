@@ -0,0 +1,223 @@
+//! `Bits` yields the bits of an unsigned integer as `bool`s, and `from_bits`
+//! folds them back into an integer.
+
+use alloc::vec::Vec;
+
+/// The unsigned integer types `Bits` can walk over.
+pub trait Unsigned: Copy {
+    const BITS: u32;
+    const ZERO: Self;
+
+    /// Whether the bit at `index` (0 = least significant) is set.
+    fn bit(self, index: u32) -> bool;
+
+    /// `self` with the bit at `index` set.
+    fn with_bit(self, index: u32) -> Self;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Unsigned for $t {
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+
+                fn bit(self, index: u32) -> bool {
+                    (self >> index) & 1 == 1
+                }
+
+                fn with_bit(self, index: u32) -> Self {
+                    self | (1 << index)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    MsbFirst,
+    LsbFirst,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bits<T> {
+    value: T,
+    order: BitOrder,
+    // Positions still to be yielded, counted in `order` from the front.
+    front: u32,
+    back: u32,
+}
+
+impl<T: Unsigned> Bits<T> {
+    pub fn new(value: T, order: BitOrder) -> Self {
+        Bits {
+            value,
+            order,
+            front: 0,
+            back: T::BITS,
+        }
+    }
+
+    pub fn msb_first(value: T) -> Self {
+        Self::new(value, BitOrder::MsbFirst)
+    }
+
+    pub fn lsb_first(value: T) -> Self {
+        Self::new(value, BitOrder::LsbFirst)
+    }
+
+    fn bit_at(&self, position: u32) -> bool {
+        match self.order {
+            BitOrder::MsbFirst => self.value.bit(T::BITS - 1 - position),
+            BitOrder::LsbFirst => self.value.bit(position),
+        }
+    }
+}
+
+impl<T: Unsigned> Iterator for Bits<T> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.bit_at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<T: Unsigned> DoubleEndedIterator for Bits<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.bit_at(self.back))
+    }
+}
+
+impl<T: Unsigned> ExactSizeIterator for Bits<T> {}
+
+impl<T: Unsigned> core::iter::FusedIterator for Bits<T> {}
+
+/// Assembles an integer from bits given in `order`.
+///
+/// # Panics
+///
+/// Panics if `bits` yields more than `T::BITS` bits. Fewer bits are fine:
+/// they fill the low end of the result.
+pub fn from_bits<T, I>(bits: I, order: BitOrder) -> T
+where
+    T: Unsigned,
+    I: IntoIterator<Item = bool>,
+{
+    let bits: Vec<bool> = bits.into_iter().collect();
+    let len = bits.len() as u32;
+    assert!(len <= T::BITS, "{len} bits don't fit in {} bits", T::BITS);
+
+    bits.into_iter()
+        .enumerate()
+        .filter(|&(_, bit)| bit)
+        .fold(T::ZERO, |acc, (i, _)| match order {
+            BitOrder::MsbFirst => acc.with_bit(len - 1 - i as u32),
+            BitOrder::LsbFirst => acc.with_bit(i as u32),
+        })
+}
+
+#[test]
+fn bits_msb_and_lsb_first() {
+    let msb: Vec<_> = Bits::msb_first(0b1000_0110u8).collect();
+    assert_eq!(msb, [true, false, false, false, false, true, true, false]);
+
+    let lsb: Vec<_> = Bits::lsb_first(0b1000_0110u8).collect();
+    assert_eq!(lsb, [false, true, true, false, false, false, false, true]);
+}
+
+#[test]
+fn bits_is_exact_size_and_double_ended() {
+    let mut bits = Bits::msb_first(0b1010u16);
+    assert_eq!(bits.len(), 16);
+
+    assert_eq!(bits.next_back(), Some(false));
+    assert_eq!(bits.next_back(), Some(true));
+    assert_eq!(bits.next(), Some(false));
+    assert_eq!(bits.len(), 13);
+
+    // Reversing an MSB-first walk is an LSB-first walk.
+    let reversed: Vec<_> = Bits::msb_first(0xC3u8).rev().collect();
+    let lsb: Vec<_> = Bits::lsb_first(0xC3u8).collect();
+    assert_eq!(reversed, lsb);
+
+    let mut bits = Bits::lsb_first(1u8);
+    assert_eq!(bits.by_ref().count(), 8);
+    assert_eq!(bits.next(), None);
+    assert_eq!(bits.next_back(), None);
+}
+
+#[test]
+fn bits_round_trip_through_from_bits() {
+    for n in [0u32, 1, 42, 0xDEAD_BEEF, u32::MAX] {
+        assert_eq!(
+            from_bits::<u32, _>(Bits::msb_first(n), BitOrder::MsbFirst),
+            n
+        );
+        assert_eq!(
+            from_bits::<u32, _>(Bits::lsb_first(n), BitOrder::LsbFirst),
+            n
+        );
+    }
+    assert_eq!(
+        from_bits::<u128, _>(Bits::msb_first(u128::MAX - 7), BitOrder::MsbFirst),
+        u128::MAX - 7
+    );
+}
+
+#[test]
+fn bits_over_a_byte_stream() {
+    let bytes = [0xF0u8, 0x01];
+    let bits: Vec<_> = bytes.iter().flat_map(|&b| Bits::msb_first(b)).collect();
+    assert_eq!(bits.len(), 16);
+
+    let back: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| from_bits(chunk.iter().copied(), BitOrder::MsbFirst))
+        .collect();
+    assert_eq!(back, bytes);
+}
+
+#[test]
+fn from_bits_with_fewer_bits() {
+    assert_eq!(
+        from_bits::<u8, _>([true, false, true], BitOrder::MsbFirst),
+        0b101
+    );
+    assert_eq!(
+        from_bits::<u8, _>([true, true, false], BitOrder::LsbFirst),
+        0b011
+    );
+}
+
+#[test]
+#[should_panic(expected = "9 bits don't fit in 8 bits")]
+fn from_bits_too_many_bits() {
+    let _: u8 = from_bits([true; 9], BitOrder::MsbFirst);
+}
+
+#[test]
+fn bits_clone_independently() {
+    let mut bits = Bits::msb_first(0b1100_0000u8);
+    bits.next();
+
+    let copy = bits.clone();
+    assert_eq!(bits.next(), Some(true));
+    assert_eq!(copy.len(), 7);
+    assert_eq!(bits.len(), 6);
+}
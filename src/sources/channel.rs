@@ -0,0 +1,195 @@
+//! Iterators over the items coming in on a `mpsc::Receiver`. The receiver's
+//! own `iter()` waits for ever, as long as a sender is alive; these give up
+//! once the channel has been quiet for a while.
+//!
+//! `recv_until_idle(&rx, idle)` yields items one at a time until none has
+//! arrived for `idle`. `recv_batches(&rx, max, timeout)` yields them in
+//! `Vec`s of up to `max`, sending each batch off once it is full or once
+//! `timeout` has passed since its first item came in.
+
+use std::iter::FusedIterator;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct RecvUntilIdle<'a, T> {
+    rx: &'a Receiver<T>,
+    idle: Duration,
+    done: bool,
+}
+
+pub fn recv_until_idle<T>(rx: &Receiver<T>, idle: Duration) -> RecvUntilIdle<'_, T> {
+    RecvUntilIdle {
+        rx,
+        idle,
+        done: false,
+    }
+}
+
+impl<T> Iterator for RecvUntilIdle<'_, T> {
+    type Item = T;
+
+    /// Ends when no item arrives within the idle timeout, or when every
+    /// sender is gone and the channel is empty.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.rx.recv_timeout(self.idle).ok();
+        self.done = item.is_none();
+        item
+    }
+}
+
+// Without `done`, a later `next()` could find an item that arrived after
+// the iterator had already given up.
+impl<T> FusedIterator for RecvUntilIdle<'_, T> {}
+
+#[derive(Debug)]
+pub struct RecvBatches<'a, T> {
+    rx: &'a Receiver<T>,
+    max: usize,
+    timeout: Duration,
+    done: bool,
+}
+
+/// # Panics
+///
+/// Panics if `max` is 0.
+pub fn recv_batches<T>(rx: &Receiver<T>, max: usize, timeout: Duration) -> RecvBatches<'_, T> {
+    assert!(max > 0, "a batch holds at least one item");
+    RecvBatches {
+        rx,
+        max,
+        timeout,
+        done: false,
+    }
+}
+
+impl<T> Iterator for RecvBatches<'_, T> {
+    type Item = Vec<T>;
+
+    /// Waits up to the timeout for the first item of a batch; if none comes,
+    /// the iterator ends. Never yields an empty batch.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Ok(first) = self.rx.recv_timeout(self.timeout) else {
+            self.done = true;
+            return None;
+        };
+        let deadline = Instant::now() + self.timeout;
+        let mut batch = vec![first];
+        while batch.len() < self.max {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(left) {
+                Ok(item) => batch.push(item),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    // Yield what we have; the next call has nothing to wait
+                    // for.
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        Some(batch)
+    }
+}
+
+impl<T> FusedIterator for RecvBatches<'_, T> {}
+
+#[cfg(test)]
+use std::sync::mpsc;
+#[cfg(test)]
+use std::thread;
+
+#[cfg(test)]
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// Sends each `(delay, item)` after sleeping for `delay`, then hangs up.
+#[cfg(test)]
+fn delayed_sender<T: Send + 'static>(plan: Vec<(u64, T)>) -> Receiver<T> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (delay, item) in plan {
+            thread::sleep(ms(delay));
+            tx.send(item).unwrap();
+        }
+    });
+    rx
+}
+
+#[test]
+fn recv_until_idle_stops_at_a_quiet_spell() {
+    // The gap before 4 is longer than the idle timeout.
+    let rx = delayed_sender(vec![(0, 1), (10, 2), (10, 3), (300, 4)]);
+    let mut items = recv_until_idle(&rx, ms(100));
+    assert_eq!(items.by_ref().collect::<Vec<_>>(), [1, 2, 3]);
+
+    // Once it gives up, it stays given up, even after 4 arrives.
+    thread::sleep(ms(300));
+    assert_eq!(items.next(), None);
+    // The item is still in the channel for whoever asks next.
+    assert_eq!(rx.recv(), Ok(4));
+}
+
+#[test]
+fn recv_until_idle_stops_when_the_senders_are_gone() {
+    let rx = delayed_sender(vec![(5, 'a'), (5, 'b')]);
+    let start = Instant::now();
+    let items: String = recv_until_idle(&rx, Duration::from_secs(10)).collect();
+    assert_eq!(items, "ab");
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn recv_until_idle_with_several_senders() {
+    let (tx, rx) = mpsc::channel();
+    for id in 0..4 {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for n in 0..5 {
+                thread::sleep(ms(2));
+                tx.send(id * 10 + n).unwrap();
+            }
+        });
+    }
+    drop(tx);
+    let mut items: Vec<i32> = recv_until_idle(&rx, ms(500)).collect();
+    items.sort();
+    let expected: Vec<i32> = (0..4)
+        .flat_map(|id| (0..5).map(move |n| id * 10 + n))
+        .collect();
+    assert_eq!(items, expected);
+}
+
+#[test]
+fn recv_batches_by_size_and_time() {
+    // A burst of five, then two stragglers. The second batch is cut off by
+    // its timeout, and the stragglers come in before the wait for the third
+    // batch runs out.
+    let rx = delayed_sender(vec![
+        (0, 1),
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (0, 5),
+        (300, 6),
+        (10, 7),
+    ]);
+    let batches: Vec<Vec<i32>> = recv_batches(&rx, 3, ms(200)).collect();
+    assert_eq!(batches, [vec![1, 2, 3], vec![4, 5], vec![6, 7]]);
+}
+
+#[test]
+fn recv_batches_ends_when_idle() {
+    let (tx, rx) = mpsc::channel::<i32>();
+    let start = Instant::now();
+    assert_eq!(recv_batches(&rx, 4, ms(50)).next(), None);
+    assert!(start.elapsed() >= ms(50));
+    drop(tx);
+}
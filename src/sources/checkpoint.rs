@@ -0,0 +1,19 @@
+//! Saving where a source has got to, so that a long computation can stop
+//! and pick up again later, maybe in another process. `save_state()` takes
+//! a snapshot of the source's position, and `resume(state)` makes a source
+//! that yields exactly what the original would have yielded from there.
+//!
+//! Only sources whose items follow from a little state, with no outside
+//! input or randomness, can do this. Each has a `State` type of its own,
+//! which can be written and read with serde when the "serde" feature is on.
+
+pub trait Checkpoint: Sized {
+    /// Everything needed to carry on from where the source is.
+    type State;
+
+    /// A snapshot of where the source has got to.
+    fn save_state(&self) -> Self::State;
+
+    /// A source that carries on from `state`.
+    fn resume(state: Self::State) -> Self;
+}
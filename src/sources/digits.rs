@@ -0,0 +1,157 @@
+//! `Digits` yields the digits of a number in any base from 2 to 36, most
+//! significant first, and `from_digits` puts them back together.
+
+#[derive(Debug, Clone)]
+pub struct Digits {
+    rest: u64,
+    base: u64,
+    // Place value of the next digit; 0 once every digit has been yielded.
+    place: u64,
+    len: usize,
+}
+
+impl Digits {
+    /// # Panics
+    ///
+    /// Panics if `base` is not in `2..=36`.
+    pub fn new(n: u64, base: u32) -> Digits {
+        assert!((2..=36).contains(&base), "base {base} is not in 2..=36");
+        let base = base as u64;
+
+        // Zero still has one digit, so start from the ones place.
+        let mut place = 1;
+        let mut len = 1;
+        while place <= n / base {
+            place *= base;
+            len += 1;
+        }
+        Digits {
+            rest: n,
+            base,
+            place,
+            len,
+        }
+    }
+
+    /// The digits rendered as characters, using `a`-`z` above 9.
+    pub fn to_chars(self) -> impl Iterator<Item = char> {
+        let base = self.base as u32;
+        self.map(move |d| char::from_digit(d, base).unwrap())
+    }
+}
+
+impl Iterator for Digits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.place == 0 {
+            return None;
+        }
+        let digit = self.rest / self.place;
+        self.rest %= self.place;
+        self.place /= self.base;
+        self.len -= 1;
+        Some(digit as u32)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for Digits {}
+
+impl core::iter::FusedIterator for Digits {}
+
+/// Reassembles a number from its digits, most significant first.
+///
+/// Returns `None` if a digit is out of range for `base` or the number doesn't
+/// fit in a `u64`.
+///
+/// # Panics
+///
+/// Panics if `base` is not in `2..=36`.
+pub fn from_digits<I>(digits: I, base: u32) -> Option<u64>
+where
+    I: IntoIterator<Item = u32>,
+{
+    assert!((2..=36).contains(&base), "base {base} is not in 2..=36");
+
+    digits.into_iter().try_fold(0u64, |acc, digit| {
+        if digit >= base {
+            return None;
+        }
+        acc.checked_mul(base as u64)?.checked_add(digit as u64)
+    })
+}
+
+#[test]
+fn digits_in_base_10() {
+    assert_eq!(Digits::new(1234, 10).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert_eq!(Digits::new(1000, 10).collect::<Vec<_>>(), [1, 0, 0, 0]);
+    assert_eq!(Digits::new(7, 10).len(), 1);
+}
+
+#[test]
+fn digits_in_base_2() {
+    assert_eq!(Digits::new(6, 2).collect::<Vec<_>>(), [1, 1, 0]);
+    assert_eq!(Digits::new(u64::MAX, 2).len(), 64);
+}
+
+#[test]
+fn digits_in_base_16() {
+    let hex: String = Digits::new(0xBEEF, 16).to_chars().collect();
+    assert_eq!(hex, "beef");
+    assert_eq!(hex, format!("{:x}", 0xBEEF));
+
+    let max: String = Digits::new(u64::MAX, 16).to_chars().collect();
+    assert_eq!(max, "ffffffffffffffff");
+}
+
+#[test]
+fn digits_of_zero() {
+    for base in [2, 10, 16, 36] {
+        assert_eq!(Digits::new(0, base).collect::<Vec<_>>(), [0]);
+    }
+}
+
+#[test]
+fn digits_round_trip() {
+    for base in 2..=36 {
+        for n in [0, 1, 35, 36, 12345, u64::MAX] {
+            assert_eq!(from_digits(Digits::new(n, base), base), Some(n));
+        }
+    }
+}
+
+#[test]
+fn from_digits_rejects_bad_input() {
+    assert_eq!(from_digits([1, 2], 2), None);
+    assert_eq!(from_digits([1; 21], 10), None);
+    assert_eq!(from_digits([], 10), Some(0));
+}
+
+#[test]
+#[should_panic(expected = "base 37 is not in 2..=36")]
+fn digits_base_out_of_range() {
+    Digits::new(10, 37);
+}
+
+#[test]
+fn digits_stay_exhausted() {
+    let mut digits = Digits::new(5, 10);
+    assert_eq!(digits.next(), Some(5));
+    assert_eq!(digits.next(), None);
+    assert_eq!(digits.next(), None);
+}
+
+#[test]
+fn digits_clone_independently() {
+    let mut digits = Digits::new(987, 10);
+    digits.next();
+
+    let copy = digits.clone();
+    assert_eq!(digits.next(), Some(8));
+    assert_eq!(copy.collect::<Vec<_>>(), [8, 7]);
+    assert_eq!(digits.collect::<Vec<_>>(), [7]);
+}
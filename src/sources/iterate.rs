@@ -0,0 +1,108 @@
+//! `iterate(x0, f)` yields `x0, f(x0), f(f(x0)), ...` forever, like Haskell's
+//! `iterate`. Bound it with `take`, `take_while`, or `find`.
+
+#[derive(Clone)]
+pub struct Iterate<T, F> {
+    current: T,
+    f: F,
+    // `f` is only applied when the following item is asked for, so
+    // `take(n)` never computes an unneeded (possibly overflowing) step.
+    started: bool,
+}
+
+impl<T: core::fmt::Debug, F> core::fmt::Debug for Iterate<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Iterate")
+            .field("current", &self.current)
+            .field("started", &self.started)
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn iterate<T, F>(x0: T, f: F) -> Iterate<T, F>
+where
+    T: Clone,
+    F: FnMut(&T) -> T,
+{
+    Iterate {
+        current: x0,
+        f,
+        started: false,
+    }
+}
+
+impl<T, F> Iterator for Iterate<T, F>
+where
+    T: Clone,
+    F: FnMut(&T) -> T,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.started {
+            self.current = (self.f)(&self.current);
+        }
+        self.started = true;
+        Some(self.current.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+// Never returns `None` at all.
+impl<T, F> core::iter::FusedIterator for Iterate<T, F>
+where
+    T: Clone,
+    F: FnMut(&T) -> T,
+{
+}
+
+#[test]
+fn iterate_doubling() {
+    let powers: Vec<u32> = iterate(1, |&n| n * 2).take(6).collect();
+    assert_eq!(powers, [1, 2, 4, 8, 16, 32]);
+}
+
+#[test]
+fn iterate_is_lazy() {
+    // Doubling a `u8` overflows after 128, but nothing past it is computed.
+    let powers: Vec<u8> = iterate(1u8, |&n| n * 2).take(8).collect();
+    assert_eq!(powers.last(), Some(&128));
+}
+
+#[test]
+fn iterate_non_copy_items() {
+    let words: Vec<String> = iterate(String::from("a"), |s| format!("{s}b"))
+        .take(3)
+        .collect();
+    assert_eq!(words, ["a", "ab", "abb"]);
+}
+
+#[test]
+fn iterate_newton_sqrt_converges() {
+    // Newton's method for sqrt(2): x' = (x + 2 / x) / 2.
+    let newton = |x: &f64| (x + 2.0 / x) / 2.0;
+    let not_converged = |x: &f64| (x * x - 2.0).abs() > 1e-12;
+
+    let steps = iterate(1.0, newton).take_while(not_converged).count();
+    assert!(steps <= 6, "took {steps} steps");
+
+    let root = iterate(1.0, newton).nth(steps).unwrap();
+    assert!((root - 2f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn iterate_clone_independently() {
+    let mut powers = iterate(1, |&n| n * 3);
+    powers.nth(1);
+
+    let copy = powers.clone();
+    assert_eq!(powers.next(), Some(9));
+    assert_eq!(copy.take(2).collect::<Vec<_>>(), [9, 27]);
+    assert_eq!(
+        format!("{powers:?}"),
+        "Iterate { current: 9, started: true, .. }"
+    );
+}
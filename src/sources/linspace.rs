@@ -0,0 +1,123 @@
+//! `Linspace` yields `n` evenly spaced `f64`s from `start` to `end`
+//! inclusive, like NumPy's `linspace`.
+
+use core::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct Linspace {
+    start: f64,
+    end: f64,
+    n: usize,
+    // Indices `front..back` are still to be yielded.
+    front: usize,
+    back: usize,
+}
+
+impl Linspace {
+    pub fn new(start: f64, end: f64, n: usize) -> Linspace {
+        Linspace {
+            start,
+            end,
+            n,
+            front: 0,
+            back: n,
+        }
+    }
+
+    // Computing each point from its index, instead of adding up a step,
+    // keeps rounding errors from accumulating and hits `end` exactly.
+    fn at(&self, i: usize) -> f64 {
+        if i == 0 {
+            return self.start;
+        }
+        if i + 1 == self.n {
+            return self.end;
+        }
+        let t = i as f64 / (self.n - 1) as f64;
+        self.start + (self.end - self.start) * t
+    }
+}
+
+impl Iterator for Linspace {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.back - self.front {
+            self.front = self.back;
+            return None;
+        }
+        self.front += n + 1;
+        Some(self.at(self.front - 1))
+    }
+
+    fn count(self) -> usize {
+        self.back - self.front
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for Linspace {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.at(self.back))
+    }
+}
+
+impl ExactSizeIterator for Linspace {}
+
+impl FusedIterator for Linspace {}
+
+#[test]
+fn linspace_includes_both_ends() {
+    let points: Vec<_> = Linspace::new(0.0, 1.0, 5).collect();
+    assert_eq!(points, [0.0, 0.25, 0.5, 0.75, 1.0]);
+
+    assert_eq!(Linspace::new(3.0, 7.0, 1).collect::<Vec<_>>(), [3.0]);
+    assert_eq!(Linspace::new(3.0, 7.0, 0).count(), 0);
+}
+
+#[test]
+fn linspace_hits_end_exactly() {
+    assert_eq!(Linspace::new(0.0, 0.3, 4).last(), Some(0.3));
+    assert_eq!(Linspace::new(0.0, 0.3, 4).next_back(), Some(0.3));
+}
+
+#[test]
+fn linspace_fast_paths_match_next() {
+    fn slow(mut it: Linspace) -> impl Iterator<Item = f64> {
+        std::iter::from_fn(move || it.next())
+    }
+    let make = || Linspace::new(-1.0, 1.0, 9);
+
+    for n in 0..11 {
+        let (mut fast, mut linear) = (make(), slow(make()));
+        assert_eq!(fast.nth(n), linear.nth(n));
+        assert_eq!(fast.next(), linear.next());
+    }
+    assert_eq!(make().count(), slow(make()).count());
+    assert_eq!(make().last(), slow(make()).last());
+}
+
+#[test]
+fn linspace_fast_paths_do_no_per_item_work() {
+    let huge = || Linspace::new(0.0, 1.0, usize::MAX);
+
+    assert_eq!(huge().count(), usize::MAX);
+    assert_eq!(huge().last(), Some(1.0));
+    assert_eq!(huge().nth(usize::MAX - 1), Some(1.0));
+}
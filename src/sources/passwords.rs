@@ -0,0 +1,355 @@
+//! `PasswordGenerator` is the endless password source from
+//! `i5_custom_iterators`, grown up: a `PasswordBuilder` picks the length,
+//! which kinds of characters to use, and optionally a seed, and every
+//! password has at least one character of each kind.
+//!
+//! Without a seed the generator draws from the operating system's
+//! randomness (through `getrandom`), which is also what a browser build
+//! uses; see `crate::wasm`.
+
+use crate::json::{JsonError, JsonValue};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt;
+use std::iter::FusedIterator;
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+const SYMBOLS: &[u8] = b"!#$%&*+-=?@^_~";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PasswordConfigError {
+    /// No kind of character is switched on.
+    NoCharacters,
+    /// Too short to fit one character of each kind.
+    TooShort {
+        length: usize,
+        required: usize,
+    },
+    /// The JSON configuration isn't JSON, or isn't an object.
+    Json(JsonError),
+    UnknownOption(String),
+    /// An option with a value of the wrong type, e.g. `"length": "long"`.
+    InvalidValue {
+        option: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for PasswordConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordConfigError::NoCharacters => write!(f, "no characters to make passwords from"),
+            PasswordConfigError::TooShort { length, required } => write!(
+                f,
+                "a password of length {length} can't have all {required} kinds of character"
+            ),
+            PasswordConfigError::Json(err) => write!(f, "invalid configuration: {err}"),
+            PasswordConfigError::UnknownOption(option) => write!(f, "unknown option {option:?}"),
+            PasswordConfigError::InvalidValue { option, expected } => {
+                write!(f, "option {option:?} should be {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PasswordConfigError {}
+
+/// The settings for a `PasswordGenerator`. By default, passwords are 16
+/// characters of lowercase and uppercase letters and digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordBuilder {
+    length: usize,
+    lowercase: bool,
+    uppercase: bool,
+    digits: bool,
+    symbols: bool,
+    seed: Option<u64>,
+}
+
+impl Default for PasswordBuilder {
+    fn default() -> Self {
+        PasswordBuilder {
+            length: 16,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: false,
+            seed: None,
+        }
+    }
+}
+
+impl PasswordBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    pub fn lowercase(mut self, on: bool) -> Self {
+        self.lowercase = on;
+        self
+    }
+
+    pub fn uppercase(mut self, on: bool) -> Self {
+        self.uppercase = on;
+        self
+    }
+
+    pub fn digits(mut self, on: bool) -> Self {
+        self.digits = on;
+        self
+    }
+
+    /// Symbols are `!#$%&*+-=?@^_~`.
+    pub fn symbols(mut self, on: bool) -> Self {
+        self.symbols = on;
+        self
+    }
+
+    /// Makes the passwords reproducible: the same settings and seed always
+    /// give the same passwords. Only for tests and demos.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Settings from a JSON object such as
+    /// `{"length": 20, "symbols": true, "seed": 7}`. The keys are the names
+    /// of the builder's methods; any left out keep their defaults.
+    pub fn from_json(config: &str) -> Result<Self, PasswordConfigError> {
+        let config: JsonValue = config.parse().map_err(PasswordConfigError::Json)?;
+        let JsonValue::Object(options) = config else {
+            return Err(PasswordConfigError::Json(JsonError::Custom(
+                "expected an object".into(),
+            )));
+        };
+
+        let mut builder = PasswordBuilder::new();
+        for (option, value) in options {
+            let invalid = |expected| PasswordConfigError::InvalidValue {
+                option: option.clone(),
+                expected,
+            };
+            let flag = match value {
+                JsonValue::Bool(on) => Some(on),
+                _ => None,
+            };
+            // Seeds and lengths both have to be whole and not negative.
+            let whole = match value {
+                JsonValue::Int(n) => u64::try_from(n).ok(),
+                JsonValue::Number(n)
+                    if n >= 0.0 && n <= u64::MAX as f64 && n as u64 as f64 == n =>
+                {
+                    Some(n as u64)
+                }
+                _ => None,
+            };
+            builder = match option.as_str() {
+                "length" => {
+                    builder.length(whole.ok_or_else(|| invalid("a whole number"))? as usize)
+                }
+                "seed" => builder.seed(whole.ok_or_else(|| invalid("a whole number"))?),
+                "lowercase" => builder.lowercase(flag.ok_or_else(|| invalid("true or false"))?),
+                "uppercase" => builder.uppercase(flag.ok_or_else(|| invalid("true or false"))?),
+                "digits" => builder.digits(flag.ok_or_else(|| invalid("true or false"))?),
+                "symbols" => builder.symbols(flag.ok_or_else(|| invalid("true or false"))?),
+                _ => return Err(PasswordConfigError::UnknownOption(option)),
+            };
+        }
+        Ok(builder)
+    }
+
+    pub fn build(self) -> Result<PasswordGenerator, PasswordConfigError> {
+        let classes: Vec<&'static [u8]> = [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter_map(|(on, class)| on.then_some(class))
+        .collect();
+
+        if classes.is_empty() {
+            return Err(PasswordConfigError::NoCharacters);
+        }
+        if self.length < classes.len() {
+            return Err(PasswordConfigError::TooShort {
+                length: self.length,
+                required: classes.len(),
+            });
+        }
+        let rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(PasswordGenerator {
+            length: self.length,
+            alphabet: classes.concat(),
+            classes,
+            rng,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PasswordGenerator {
+    length: usize,
+    // Each password takes one character from each class, then fills up
+    // from the whole alphabet.
+    classes: Vec<&'static [u8]>,
+    alphabet: Vec<u8>,
+    rng: StdRng,
+}
+
+impl PasswordGenerator {
+    pub fn builder() -> PasswordBuilder {
+        PasswordBuilder::new()
+    }
+}
+
+impl Iterator for PasswordGenerator {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rng = &mut self.rng;
+        let mut password: Vec<u8> = self
+            .classes
+            .iter()
+            .map(|class| *class.choose(rng).unwrap())
+            .collect();
+        password.extend((password.len()..self.length).map(|_| *self.alphabet.choose(rng).unwrap()));
+        // Otherwise the first characters would always be a lowercase
+        // letter, then an uppercase one, and so on.
+        password.shuffle(rng);
+        Some(password.into_iter().map(char::from).collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for PasswordGenerator {}
+
+/// `count` passwords made to `config_json`; see
+/// `PasswordBuilder::from_json` for the settings.
+pub fn generate_passwords(
+    count: usize,
+    config_json: &str,
+) -> Result<Vec<String>, PasswordConfigError> {
+    let generator = PasswordBuilder::from_json(config_json)?.build()?;
+    Ok(generator.take(count).collect())
+}
+
+#[test]
+fn passwords_have_every_kind_of_character() {
+    let passwords: Vec<String> = PasswordGenerator::builder()
+        .length(4)
+        .symbols(true)
+        .seed(1383)
+        .build()
+        .unwrap()
+        .take(200)
+        .collect();
+    for password in &passwords {
+        assert_eq!(password.len(), 4);
+        let has = |class: &[u8]| password.bytes().any(|b| class.contains(&b));
+        assert!(has(LOWERCASE) && has(UPPERCASE) && has(DIGITS) && has(SYMBOLS));
+    }
+    // The kinds don't always come in the same order.
+    assert!(passwords
+        .iter()
+        .any(|p| !p.as_bytes()[0].is_ascii_lowercase()));
+
+    let digits_only = PasswordBuilder::new()
+        .lowercase(false)
+        .uppercase(false)
+        .length(6)
+        .build()
+        .unwrap();
+    assert!(digits_only
+        .take(20)
+        .all(|p| p.len() == 6 && p.bytes().all(|b| b.is_ascii_digit())));
+}
+
+#[test]
+fn seeded_passwords_are_reproducible() {
+    let make = |seed| {
+        let builder = PasswordBuilder::new().seed(seed);
+        builder.build().unwrap().take(5).collect::<Vec<_>>()
+    };
+    assert_eq!(make(1), make(1));
+    assert_ne!(make(1), make(2));
+}
+
+#[test]
+fn password_builder_rejects_impossible_settings() {
+    let none = PasswordBuilder::new()
+        .lowercase(false)
+        .uppercase(false)
+        .digits(false)
+        .build();
+    assert_eq!(none.unwrap_err(), PasswordConfigError::NoCharacters);
+
+    let short = PasswordBuilder::new().length(2).build().unwrap_err();
+    assert_eq!(
+        short,
+        PasswordConfigError::TooShort {
+            length: 2,
+            required: 3
+        }
+    );
+    assert_eq!(
+        short.to_string(),
+        "a password of length 2 can't have all 3 kinds of character"
+    );
+}
+
+#[test]
+fn password_builder_from_json() {
+    assert_eq!(PasswordBuilder::from_json("{}"), Ok(PasswordBuilder::new()));
+    assert_eq!(
+        PasswordBuilder::from_json(
+            r#"{"length": 20, "symbols": true, "digits": false, "seed": 9}"#
+        ),
+        Ok(PasswordBuilder::new()
+            .length(20)
+            .symbols(true)
+            .digits(false)
+            .seed(9))
+    );
+
+    let err = |json| PasswordBuilder::from_json(json).unwrap_err().to_string();
+    assert_eq!(err(r#"{"colour": true}"#), r#"unknown option "colour""#);
+    assert_eq!(
+        err(r#"{"length": 2.5}"#),
+        r#"option "length" should be a whole number"#
+    );
+    assert_eq!(
+        err(r#"{"symbols": 1}"#),
+        r#"option "symbols" should be true or false"#
+    );
+    assert_eq!(err("[]"), "invalid configuration: expected an object");
+    assert_eq!(err("{"), "invalid configuration: unexpected end of input");
+}
+
+#[test]
+fn generate_passwords_from_json() {
+    let passwords = generate_passwords(3, r#"{"length": 8, "seed": 5}"#).unwrap();
+    assert_eq!(passwords.len(), 3);
+    assert!(passwords.iter().all(|p| p.len() == 8));
+    assert_eq!(
+        passwords,
+        generate_passwords(3, r#"{"seed": 5, "length": 8}"#).unwrap()
+    );
+    assert_eq!(generate_passwords(0, "{}"), Ok(vec![]));
+    assert!(generate_passwords(3, r#"{"length": 1}"#).is_err());
+}
@@ -0,0 +1,247 @@
+//! `StepCounter` counts from `start` towards `end` (exclusive) in steps of
+//! `step`, like `(start..end).step_by(step)` but also for negative steps.
+
+use super::Checkpoint;
+use core::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct StepCounter {
+    next: i64,
+    step: i64,
+    // Tracking how many items are left, rather than comparing against `end`,
+    // makes `nth`, `count` and `last` simple arithmetic.
+    remaining: usize,
+}
+
+impl StepCounter {
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn new(start: i64, end: i64, step: i64) -> StepCounter {
+        assert!(step != 0, "step must not be zero");
+
+        let (start, end, step) = (start as i128, end as i128, step as i128);
+        let span = if step > 0 { end - start } else { start - end };
+        let remaining = if span > 0 {
+            (span - 1) / step.abs() + 1
+        } else {
+            0
+        };
+        StepCounter {
+            next: start as i64,
+            step: step as i64,
+            remaining: remaining as usize,
+        }
+    }
+
+    fn value_at(&self, n: usize) -> i64 {
+        (self.next as i128 + n as i128 * self.step as i128) as i64
+    }
+}
+
+impl Iterator for StepCounter {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let item = self.value_at(n);
+        self.remaining -= n + 1;
+        // Only step past `item` if there is something left to step to;
+        // otherwise the position might not fit in an `i64`.
+        if self.remaining > 0 {
+            self.next = item + self.step;
+        }
+        Some(item)
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        (self.remaining > 0).then(|| self.value_at(self.remaining - 1))
+    }
+}
+
+impl ExactSizeIterator for StepCounter {}
+
+impl FusedIterator for StepCounter {}
+
+/// Where a `StepCounter` has got to: the next value, the step, and how
+/// many values are left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepCounterState {
+    pub next: i64,
+    pub step: i64,
+    pub remaining: u64,
+}
+
+impl Checkpoint for StepCounter {
+    type State = StepCounterState;
+
+    fn save_state(&self) -> StepCounterState {
+        StepCounterState {
+            next: self.next,
+            step: self.step,
+            remaining: self.remaining as u64,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `step` is zero, or if `remaining` doesn't fit in a
+    /// `usize`.
+    fn resume(state: StepCounterState) -> StepCounter {
+        assert!(state.step != 0, "step must not be zero");
+        StepCounter {
+            next: state.next,
+            step: state.step,
+            remaining: usize::try_from(state.remaining).expect("too many values left for a usize"),
+        }
+    }
+}
+
+/// A state is written as `[next, step, remaining]`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for StepCounterState {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&(self.next, self.step, self.remaining), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StepCounterState {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        let (next, step, remaining) = serde::Deserialize::deserialize(deserializer)?;
+        if step == 0 {
+            return Err(D::Error::custom("step must not be zero"));
+        }
+        if usize::try_from(remaining).is_err() {
+            return Err(D::Error::custom("too many values left for a usize"));
+        }
+        Ok(StepCounterState {
+            next,
+            step,
+            remaining,
+        })
+    }
+}
+
+#[test]
+fn step_counter_matches_step_by() {
+    for (start, end, step) in [(0, 10, 3), (0, 9, 3), (5, 5, 1), (7, 3, 2), (-4, 4, 4)] {
+        let expected: Vec<i64> = (start..end).step_by(step as usize).collect();
+        let result: Vec<i64> = StepCounter::new(start, end, step).collect();
+        assert_eq!(result, expected, "{start}..{end} by {step}");
+    }
+}
+
+#[test]
+fn step_counter_counts_down() {
+    let result: Vec<_> = StepCounter::new(10, 0, -4).collect();
+    assert_eq!(result, [10, 6, 2]);
+
+    assert_eq!(StepCounter::new(0, 10, -1).count(), 0);
+}
+
+#[test]
+fn step_counter_fast_paths_match_next() {
+    fn slow(mut it: StepCounter) -> impl Iterator<Item = i64> {
+        std::iter::from_fn(move || it.next())
+    }
+
+    for (start, end, step) in [(0, 10, 3), (10, -10, -7), (0, 0, 1)] {
+        let make = || StepCounter::new(start, end, step);
+        for n in 0..5 {
+            let (mut fast, mut linear) = (make(), slow(make()));
+            assert_eq!(fast.nth(n), linear.nth(n));
+            assert_eq!(fast.next(), linear.next());
+        }
+        assert_eq!(make().count(), slow(make()).count());
+        assert_eq!(make().last(), slow(make()).last());
+    }
+}
+
+#[test]
+fn step_counter_fast_paths_do_no_per_item_work() {
+    // With 2^64 - 1 items, a linear scan would never finish.
+    let all = || StepCounter::new(i64::MIN, i64::MAX, 1);
+
+    assert_eq!(all().count(), usize::MAX);
+    assert_eq!(all().last(), Some(i64::MAX - 1));
+    assert_eq!(all().nth(usize::MAX - 1), Some(i64::MAX - 1));
+
+    let mut it = all();
+    assert_eq!(it.nth(1 << 63), Some(0));
+    assert_eq!(it.len(), (1 << 63) - 2);
+}
+
+#[test]
+fn step_counter_ends_at_i64_max() {
+    let mut it = StepCounter::new(i64::MAX - 1, i64::MAX, 1);
+    assert_eq!(it.next(), Some(i64::MAX - 1));
+    assert_eq!(it.next(), None);
+
+    let mut it = StepCounter::new(i64::MAX - 2, i64::MAX, 1);
+    assert_eq!(it.nth(1), Some(i64::MAX - 1));
+    assert_eq!(it.next(), None);
+}
+
+#[test]
+fn step_counter_resumes_where_it_left_off() {
+    for (start, end, step) in [(0, 20, 3), (10, -10, -7), (5, 5, 1)] {
+        let whole: Vec<i64> = StepCounter::new(start, end, step).collect();
+        for stop in 0..=whole.len() {
+            let mut it = StepCounter::new(start, end, step);
+            let mut resumed: Vec<i64> = it.by_ref().take(stop).collect();
+            resumed.extend(StepCounter::resume(it.save_state()));
+            assert_eq!(
+                resumed, whole,
+                "{start}..{end} by {step}, stopped at {stop}"
+            );
+        }
+    }
+
+    // At the very end, where `next` isn't stepped past `i64::MAX - 1`.
+    let mut it = StepCounter::new(i64::MAX - 2, i64::MAX, 1);
+    it.next();
+    let state = it.save_state();
+    assert_eq!(state.remaining, 1);
+    assert_iter_eq!(StepCounter::resume(state), [i64::MAX - 1]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn step_counter_state_serde_round_trip() {
+    let mut it = StepCounter::new(100, 0, -9);
+    it.nth(3);
+    let json = crate::json::to_string(&it.save_state()).unwrap();
+    assert_eq!(json, "[64,-9,8]");
+
+    let state: StepCounterState = crate::json::from_str(&json).unwrap();
+    assert_iter_eq!(StepCounter::resume(state), it);
+    assert!(crate::json::from_str::<StepCounterState>("[1,0,3]").is_err());
+
+    // Near the ends of `i64`, where an `f64` would round the state off.
+    for (start, end) in [(i64::MAX - 5, i64::MAX), ((1 << 53) + 1, (1 << 53) + 7)] {
+        let mut it = StepCounter::new(start, end, 1);
+        it.next();
+        let json = crate::json::to_string(&it.save_state()).unwrap();
+        assert_eq!(json, format!("[{},1,{}]", start + 1, end - start - 1));
+        let state: StepCounterState = crate::json::from_str(&json).unwrap();
+        assert_iter_eq!(StepCounter::resume(state), it);
+    }
+}
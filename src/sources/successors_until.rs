@@ -0,0 +1,103 @@
+//! `successors_until` is `std::iter::successors` without the `Option`
+//! plumbing: the step function always produces a value, and a separate
+//! predicate decides when to stop.
+
+#[derive(Clone)]
+pub struct SuccessorsUntil<T, F, P> {
+    next: Option<T>,
+    step: F,
+    stop: P,
+}
+
+impl<T: core::fmt::Debug, F, P> core::fmt::Debug for SuccessorsUntil<T, F, P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SuccessorsUntil")
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Yields `init`, `step(&init)`, `step(&step(&init))`, ... up to, but not
+/// including, the first value for which `stop` returns `true`.
+pub fn successors_until<T, F, P>(init: T, step: F, stop: P) -> SuccessorsUntil<T, F, P>
+where
+    F: FnMut(&T) -> T,
+    P: FnMut(&T) -> bool,
+{
+    SuccessorsUntil {
+        next: Some(init),
+        step,
+        stop,
+    }
+}
+
+impl<T, F, P> Iterator for SuccessorsUntil<T, F, P>
+where
+    F: FnMut(&T) -> T,
+    P: FnMut(&T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.next.take()?;
+        if (self.stop)(&item) {
+            return None;
+        }
+        self.next = Some((self.step)(&item));
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T, F, P> core::iter::FusedIterator for SuccessorsUntil<T, F, P>
+where
+    F: FnMut(&T) -> T,
+    P: FnMut(&T) -> bool,
+{
+}
+
+#[test]
+fn successors_until_powers_of_two() {
+    let powers: Vec<u32> = successors_until(1, |&n| n * 2, |&n| n > 100).collect();
+    assert_eq!(powers, [1, 2, 4, 8, 16, 32, 64]);
+}
+
+#[test]
+fn successors_until_immediate_stop() {
+    let steps = std::cell::Cell::new(0);
+    let step = |&n: &i32| {
+        steps.set(steps.get() + 1);
+        n + 1
+    };
+
+    let mut it = successors_until(10, step, |_| true);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next(), None);
+    assert_eq!(steps.get(), 0);
+}
+
+#[test]
+fn successors_until_never_stops() {
+    let odds: Vec<u64> = successors_until(1, |&n| n + 2, |_| false).take(5).collect();
+    assert_eq!(odds, [1, 3, 5, 7, 9]);
+}
+
+#[test]
+fn successors_until_clone_independently() {
+    let mut tens = successors_until(10, |&n| n + 10, |&n| n > 40);
+    tens.next();
+
+    let copy = tens.clone();
+    assert_eq!(tens.next(), Some(20));
+    assert_eq!(copy.collect::<Vec<_>>(), [20, 30, 40]);
+    assert_eq!(
+        format!("{tens:?}"),
+        "SuccessorsUntil { next: Some(30), .. }"
+    );
+}
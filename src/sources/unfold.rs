@@ -0,0 +1,111 @@
+//! `unfold` builds an iterator from a seed state and a step function. Unlike
+//! `std::iter::from_fn`, the state is passed into the closure and handed back
+//! with each item, so the threading of state is visible in the signature.
+
+#[derive(Clone)]
+pub struct Unfold<St, F> {
+    // `None` once the step function has returned `None`.
+    state: Option<St>,
+    f: F,
+}
+
+impl<St: core::fmt::Debug, F> core::fmt::Debug for Unfold<St, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Unfold")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Calls `f(state)` for every item: `Some((item, new_state))` yields `item`
+/// and continues from `new_state`, `None` ends the iteration.
+pub fn unfold<St, T, F>(state: St, f: F) -> Unfold<St, F>
+where
+    F: FnMut(St) -> Option<(T, St)>,
+{
+    Unfold {
+        state: Some(state),
+        f,
+    }
+}
+
+impl<St, T, F> Iterator for Unfold<St, F>
+where
+    F: FnMut(St) -> Option<(T, St)>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (item, state) = (self.f)(self.state.take()?)?;
+        self.state = Some(state);
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.state {
+            Some(_) => (0, None),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+// Once `f` returns `None` the state is gone, so `f` is never called again.
+impl<St, T, F> core::iter::FusedIterator for Unfold<St, F> where F: FnMut(St) -> Option<(T, St)> {}
+
+#[test]
+fn unfold_fibonacci() {
+    let fibo = unfold((0u64, 1u64), |(a, b)| Some((a, (b, a + b))));
+
+    assert_eq!(
+        fibo.take(10).collect::<Vec<_>>(),
+        [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+    );
+}
+
+#[test]
+fn unfold_collatz() {
+    fn collatz(n: u64) -> impl Iterator<Item = u64> {
+        // The state is the next number to yield, or `None` after reaching 1.
+        unfold(Some(n), |n: Option<u64>| {
+            let n = n?;
+            let next = match n {
+                1 => None,
+                n if n % 2 == 0 => Some(n / 2),
+                n => Some(3 * n + 1),
+            };
+            Some((n, next))
+        })
+    }
+
+    assert_eq!(
+        collatz(6).collect::<Vec<_>>(),
+        [6, 3, 10, 5, 16, 8, 4, 2, 1]
+    );
+    assert_eq!(collatz(1).collect::<Vec<_>>(), [1]);
+    assert_eq!(collatz(27).count(), 112);
+}
+
+#[test]
+fn unfold_stays_done() {
+    let calls = std::cell::Cell::new(0);
+    let mut countdown = unfold(2, |n| {
+        calls.set(calls.get() + 1);
+        (n > 0).then(|| (n, n - 1))
+    });
+
+    assert_eq!(countdown.by_ref().collect::<Vec<_>>(), [2, 1]);
+    assert_eq!(countdown.next(), None);
+    assert_eq!(countdown.next(), None);
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn unfold_clone_independently() {
+    let mut naturals = unfold(0, |n| Some((n, n + 1)));
+    naturals.next();
+
+    let copy = naturals.clone();
+    assert_eq!(naturals.next(), Some(1));
+    assert_eq!(copy.take(2).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(format!("{naturals:?}"), "Unfold { state: Some(2), .. }");
+}
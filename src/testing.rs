@@ -0,0 +1,132 @@
+//! Comparing iterators in tests. `assert_iter_eq!(a, b)` checks `a` and `b`
+//! item by item, without collecting them, and on failure says where they
+//! part ways: the index and both items, or which one ran out first.
+
+use core::fmt;
+
+/// The first place where two iterators differ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch<L, R> {
+    /// Both have an item at `index`, but not equal ones.
+    Item { index: usize, left: L, right: R },
+    /// The right one ended after `index` items; the left one goes on with
+    /// `left`.
+    LeftLonger { index: usize, left: L },
+    /// The left one ended after `index` items; the right one goes on with
+    /// `right`.
+    RightLonger { index: usize, right: R },
+}
+
+impl<L: fmt::Debug, R: fmt::Debug> fmt::Display for Mismatch<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Item { index, left, right } => write!(
+                f,
+                "iterators differ at index {index}\n  left: {left:?}\n right: {right:?}"
+            ),
+            Mismatch::LeftLonger { index, left } => write!(
+                f,
+                "right iterator ended after {index} items\n  left[{index}]: {left:?}"
+            ),
+            Mismatch::RightLonger { index, right } => write!(
+                f,
+                "left iterator ended after {index} items\n right[{index}]: {right:?}"
+            ),
+        }
+    }
+}
+
+/// Where `left` and `right` first differ, or `None` if they yield equal
+/// items and end together. Stops at the first difference, so it works on
+/// endless iterators that differ somewhere.
+pub fn first_mismatch<L, R>(left: L, right: R) -> Option<Mismatch<L::Item, R::Item>>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: PartialEq<R::Item>,
+{
+    let (mut left, mut right) = (left.into_iter(), right.into_iter());
+    let mut index = 0;
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => return None,
+            (Some(l), Some(r)) if l == r => index += 1,
+            (Some(left), Some(right)) => return Some(Mismatch::Item { index, left, right }),
+            (Some(left), None) => return Some(Mismatch::LeftLonger { index, left }),
+            (None, Some(right)) => return Some(Mismatch::RightLonger { index, right }),
+        }
+    }
+}
+
+/// Panics with the first mismatch between `left` and `right`, if any. See
+/// `assert_iter_eq!`.
+#[track_caller]
+pub fn assert_iter_eq<L, R>(left: L, right: R)
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: PartialEq<R::Item> + fmt::Debug,
+    R::Item: fmt::Debug,
+{
+    if let Some(mismatch) = first_mismatch(left, right) {
+        panic!("assertion `left == right` failed: {mismatch}");
+    }
+}
+
+#[test]
+fn first_mismatch_finds_the_difference() {
+    assert_eq!(first_mismatch(0..3, [0, 1, 2]), None);
+    assert!(first_mismatch("".chars(), "".chars()).is_none());
+    assert_eq!(
+        first_mismatch([1, 2, 3], [1, 5, 3]),
+        Some(Mismatch::Item {
+            index: 1,
+            left: 2,
+            right: 5
+        })
+    );
+    assert_eq!(
+        first_mismatch(0..4, 0..2),
+        Some(Mismatch::LeftLonger { index: 2, left: 2 })
+    );
+    assert_eq!(
+        first_mismatch(0..0, 7..),
+        Some(Mismatch::RightLonger { index: 0, right: 7 })
+    );
+    // Endless iterators are fine as long as they differ somewhere.
+    assert_eq!(
+        first_mismatch(0.., (0..).map(|n| n.min(5))).unwrap(),
+        Mismatch::Item {
+            index: 6,
+            left: 6,
+            right: 5
+        }
+    );
+}
+
+#[test]
+fn mismatch_messages() {
+    let item = first_mismatch(["a", "b"], ["a", "c"]).unwrap();
+    assert_eq!(
+        item.to_string(),
+        "iterators differ at index 1\n  left: \"b\"\n right: \"c\""
+    );
+    let longer = first_mismatch(0..3, 0..1).unwrap();
+    assert_eq!(
+        longer.to_string(),
+        "right iterator ended after 1 items\n  left[1]: 1"
+    );
+    let shorter = first_mismatch(0..1, 0..3).unwrap();
+    assert_eq!(
+        shorter.to_string(),
+        "left iterator ended after 1 items\n right[1]: 1"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "assertion `left == right` failed: iterators differ at index 2\n  left: 2\n right: 9"
+)]
+fn assert_iter_eq_panics_at_the_first_difference() {
+    assert_iter_eq(0..5, [0, 1, 9, 3, 4]);
+}
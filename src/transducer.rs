@@ -0,0 +1,365 @@
+//! Transducers, after Clojure's: a transformation such as "double each
+//! item, keep the small ones, stop after three" described once, as a
+//! value, apart from any input it will run over.
+//!
+//! An adapter chain like `xs.map(f).filter(p).take(3)` has its input built
+//! in: to run the same steps over other input, the chain has to be written
+//! out again. A transducer is the steps alone. Each one is a push-based
+//! stage: it is handed items one at a time, and passes on whatever comes
+//! out to the stage after it, saying with a `ControlFlow` whether it wants
+//! any more (`take` doesn't, once it has had enough).
+//!
+//! ```text
+//! let t = map(|n| n * 2).then(filter(|n: &i32| *n < 10)).then(take(3));
+//! t.apply(0..100)                        // an Iterator, pulled item by item
+//! t.fold(other_input, 0, |a, b| a + b)   // pushed, through InternalIterator
+//! ```
+//!
+//! `apply` and `fold` each start from a clone of the transducer, so the
+//! state of one run (how many items `take` has let through) never leaks
+//! into the next.
+
+use crate::internal::InternalIterator;
+use alloc::collections::VecDeque;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::ControlFlow;
+
+pub trait Transducer<In>: Clone {
+    type Out;
+
+    /// Feeds `item` through, calling `down` with whatever comes out. A
+    /// `Break`, from here or from `down`, means no more items are wanted.
+    fn step<F>(&mut self, item: In, down: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(Self::Out) -> ControlFlow<()>;
+
+    /// This transducer, followed by `next`.
+    fn then<T>(self, next: T) -> Compose<Self, T>
+    where
+        T: Transducer<Self::Out>,
+    {
+        Compose {
+            first: self,
+            second: next,
+        }
+    }
+
+    /// An iterator of what comes out when the items of `iter` are fed
+    /// through.
+    fn apply<I>(&self, iter: I) -> Transduced<I::IntoIter, Self>
+    where
+        I: IntoIterator<Item = In>,
+    {
+        Transduced {
+            orig: iter.into_iter(),
+            transducer: self.clone(),
+            out: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Pushes the items of `iter` through, folding what comes out with
+    /// `g`. Any `Iterator` will do, as every one is an `InternalIterator`.
+    fn fold<I, Acc, G>(&self, iter: I, init: Acc, mut g: G) -> Acc
+    where
+        I: InternalIterator<Item = In>,
+        G: FnMut(Acc, Self::Out) -> Acc,
+    {
+        let mut transducer = self.clone();
+        let mut acc = Some(init);
+        let mut down = |out| {
+            acc = acc.take().map(|a| g(a, out));
+            ControlFlow::Continue(())
+        };
+        let _ = iter.try_for_each_internal(|item| transducer.step(item, &mut down));
+        acc.expect("the accumulator is always put back")
+    }
+}
+
+/// Passes each item on unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Identity;
+
+pub fn identity() -> Identity {
+    Identity
+}
+
+impl<In> Transducer<In> for Identity {
+    type Out = In;
+
+    fn step<F>(&mut self, item: In, down: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(In) -> ControlFlow<()>,
+    {
+        down(item)
+    }
+}
+
+#[derive(Clone)]
+pub struct Map<F> {
+    f: F,
+}
+
+/// Passes on `f(item)` for each item.
+pub fn map<F>(f: F) -> Map<F> {
+    Map { f }
+}
+
+impl<In, B, F> Transducer<In> for Map<F>
+where
+    F: FnMut(In) -> B + Clone,
+{
+    type Out = B;
+
+    fn step<G>(&mut self, item: In, down: &mut G) -> ControlFlow<()>
+    where
+        G: FnMut(B) -> ControlFlow<()>,
+    {
+        down((self.f)(item))
+    }
+}
+
+#[derive(Clone)]
+pub struct Filter<P> {
+    pred: P,
+}
+
+/// Passes on the items `pred` holds for.
+pub fn filter<P>(pred: P) -> Filter<P> {
+    Filter { pred }
+}
+
+impl<In, P> Transducer<In> for Filter<P>
+where
+    P: FnMut(&In) -> bool + Clone,
+{
+    type Out = In;
+
+    fn step<F>(&mut self, item: In, down: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(In) -> ControlFlow<()>,
+    {
+        if (self.pred)(&item) {
+            down(item)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Take {
+    left: usize,
+}
+
+/// Passes on the first `n` items, then asks for no more.
+pub fn take(n: usize) -> Take {
+    Take { left: n }
+}
+
+impl<In> Transducer<In> for Take {
+    type Out = In;
+
+    fn step<F>(&mut self, item: In, down: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(In) -> ControlFlow<()>,
+    {
+        if self.left == 0 {
+            return ControlFlow::Break(());
+        }
+        self.left -= 1;
+        down(item)?;
+        // Saying so now saves pulling an item only to drop it.
+        if self.left == 0 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Two transducers, one after the other.
+#[derive(Debug, Clone)]
+pub struct Compose<A, B> {
+    first: A,
+    second: B,
+}
+
+/// `first`, followed by `second`; the same as `first.then(second)`.
+pub fn compose<In, A, B>(first: A, second: B) -> Compose<A, B>
+where
+    A: Transducer<In>,
+    B: Transducer<A::Out>,
+{
+    first.then(second)
+}
+
+impl<In, A, B> Transducer<In> for Compose<A, B>
+where
+    A: Transducer<In>,
+    B: Transducer<A::Out>,
+{
+    type Out = B::Out;
+
+    fn step<F>(&mut self, item: In, down: &mut F) -> ControlFlow<()>
+    where
+        F: FnMut(B::Out) -> ControlFlow<()>,
+    {
+        let second = &mut self.second;
+        self.first.step(item, &mut |mid| second.step(mid, down))
+    }
+}
+
+impl<F> fmt::Debug for Map<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map").finish_non_exhaustive()
+    }
+}
+
+impl<P> fmt::Debug for Filter<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Filter").finish_non_exhaustive()
+    }
+}
+
+/// A transducer pulling its items from an iterator.
+pub struct Transduced<I: Iterator, T: Transducer<I::Item>> {
+    orig: I,
+    transducer: T,
+    // What came out of the last item fed through, not yet yielded: a step
+    // can pass on any number of items.
+    out: VecDeque<T::Out>,
+    // Whether the transducer has asked for no more.
+    done: bool,
+}
+
+impl<I, T> Iterator for Transduced<I, T>
+where
+    I: Iterator,
+    T: Transducer<I::Item>,
+{
+    type Item = T::Out;
+
+    fn next(&mut self) -> Option<T::Out> {
+        loop {
+            if let Some(item) = self.out.pop_front() {
+                return Some(item);
+            }
+            if self.done {
+                return None;
+            }
+            let item = self.orig.next()?;
+            let out = &mut self.out;
+            let flow = self.transducer.step(item, &mut |item| {
+                out.push_back(item);
+                ControlFlow::Continue(())
+            });
+            self.done = flow.is_break();
+        }
+    }
+}
+
+impl<I, T> FusedIterator for Transduced<I, T>
+where
+    I: FusedIterator,
+    T: Transducer<I::Item>,
+{
+}
+
+impl<I, T> fmt::Debug for Transduced<I, T>
+where
+    I: Iterator + fmt::Debug,
+    T: Transducer<I::Item> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transduced")
+            .field("orig", &self.orig)
+            .field("transducer", &self.transducer)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+use alloc::vec::Vec;
+
+#[test]
+fn transducer_matches_the_adapter_chain() {
+    let t = map(|n: i32| n * 3)
+        .then(filter(|n: &i32| n % 2 == 0))
+        .then(take(4));
+    assert_iter_eq!(
+        t.apply(0..100),
+        (0..100).map(|n| n * 3).filter(|n| n % 2 == 0).take(4)
+    );
+    // The same description, reused over other inputs.
+    assert_iter_eq!(t.apply([5, 4, 2]), [12, 6]);
+    assert_iter_eq!(t.apply(Vec::new()), []);
+    assert_eq!(t.fold(0..100, 0, |a, b| a + b), 6 + 12 + 18);
+}
+
+#[test]
+fn transducer_stops_pulling_after_take() {
+    let t = compose(filter(|n: &u32| n.is_multiple_of(5)), take(3));
+    let pulled = core::cell::Cell::new(0);
+    let counted = (0..).inspect(|_| pulled.set(pulled.get() + 1));
+    assert_iter_eq!(t.apply(counted), [0, 5, 10]);
+    assert_eq!(pulled.get(), 11);
+
+    // Pushing stops too, so an endless input is fine.
+    assert_eq!(t.fold(0.., 0, |a, b| a + b), 15);
+    assert_eq!(Transducer::<u8>::apply(&take(0), 0..).next(), None);
+}
+
+#[test]
+fn transducer_laws() {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    let f = |n: i64| n * 7 - 3;
+    let p = |n: &i64| n % 3 != 0;
+    let mut rng = StdRng::seed_from_u64(1420);
+    for _ in 0..50 {
+        let len = rng.gen_range(0..40);
+        let xs: Vec<i64> = (0..len).map(|_| rng.gen_range(-100..100)).collect();
+        let n = rng.gen_range(0..10);
+
+        // Each piece is its adapter, and identity is no adapter at all.
+        assert_iter_eq!(map(f).apply(xs.clone()), xs.iter().copied().map(f));
+        assert_iter_eq!(filter(p).apply(xs.clone()), xs.iter().copied().filter(p));
+        assert_iter_eq!(take(n).apply(xs.clone()), xs.iter().copied().take(n));
+        assert_iter_eq!(identity().apply(xs.clone()), xs.clone());
+        assert_iter_eq!(
+            map(f).then(identity()).apply(xs.clone()),
+            map(f).apply(xs.clone())
+        );
+
+        // Composing is associative.
+        let left = map(f).then(filter(p)).then(take(n));
+        let right = map(f).then(filter(p).then(take(n)));
+        let direct: Vec<i64> = xs.iter().copied().map(f).filter(p).take(n).collect();
+        assert_iter_eq!(left.apply(xs.clone()), direct.clone());
+        assert_iter_eq!(right.apply(xs.clone()), direct.clone());
+
+        // Pulled or pushed, the same items come out.
+        let pushed = left.fold(xs.clone().into_iter(), Vec::new(), |mut v, x| {
+            v.push(x);
+            v
+        });
+        assert_eq!(pushed, direct);
+    }
+}
+
+#[test]
+fn transducer_runs_over_internal_iterators() {
+    use crate::internal::InternalIterator;
+
+    let t = map(|(i, j): (u32, u32)| i * 10 + j).then(take(4));
+    let pairs = (1..4).flat_map_internal(|i| (0..i).map_internal(move |j| (i, j)));
+    let firsts = t.fold(pairs, Vec::new(), |mut v, x| {
+        v.push(x);
+        v
+    });
+    assert_eq!(firsts, [10, 20, 21, 30]);
+}
@@ -0,0 +1,19 @@
+//! The password generator for the browser, through `wasm-bindgen`:
+//!
+//! ```text
+//! wasm-pack build --target web -- --features wasm
+//! ```
+//!
+//! Without a `seed`, the passwords' randomness comes from
+//! `crypto.getRandomValues`, by way of `getrandom`'s "js" feature.
+
+use crate::sources::passwords;
+use wasm_bindgen::prelude::*;
+
+/// `count` passwords made to `config_json`, e.g.
+/// `{"length": 20, "symbols": true}`; see `PasswordBuilder::from_json`.
+/// An invalid configuration throws an `Error` in JavaScript.
+#[wasm_bindgen(js_name = generatePasswords)]
+pub fn generate_passwords(count: usize, config_json: &str) -> Result<Vec<String>, JsError> {
+    passwords::generate_passwords(count, config_json).map_err(|err| JsError::new(&err.to_string()))
+}
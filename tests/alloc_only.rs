@@ -0,0 +1,70 @@
+//! The part of the crate that works without `std`. Built against the
+//! library as a user would see it, so
+//!
+//! ```text
+//! cargo test --no-default-features --test alloc_only
+//! ```
+//!
+//! checks the `no_std` build itself (unit tests always get `std`), and
+//! `cargo test --test alloc_only` checks the same calls with `std`.
+
+use iterators::adapters::{Delimiter, SplitWhenExt, UniqueExt};
+use iterators::collections::{BitSet, Bst, List, MyVec, RingBuffer, SortedVec, Trie};
+use iterators::fallible::{convert, TryIterator};
+use iterators::internal::InternalIterator;
+use iterators::lending::{windows_mut, LendingIterator};
+use iterators::sources::{from_digits, iterate, unfold, Bits, Digits, Linspace, StepCounter};
+
+#[test]
+fn collections_without_std() {
+    let list: List<i32> = (1..=4).collect();
+    assert_eq!(list.iter().sum::<i32>(), 10);
+
+    let tree: Bst<i32> = [5, 2, 8, 1].into_iter().collect();
+    assert!(tree.in_order().copied().eq([1, 2, 5, 8]));
+
+    let sorted: SortedVec<i32> = [3, 1, 2].into_iter().collect();
+    assert!(sorted.iter().copied().eq(1..=3));
+
+    let bits: BitSet = [1, 64, 3].into_iter().collect();
+    assert!(bits.ones().eq([1, 3, 64]));
+
+    let trie: Trie = ["car", "cat", "dog"].into_iter().collect();
+    assert_eq!(trie.iter_prefix("ca").count(), 2);
+
+    let v: MyVec<i32> = (0..3).collect();
+    assert_eq!(v.iter().count(), 3);
+
+    let mut ring = RingBuffer::<i32, 2>::new();
+    ring.extend(0..5);
+    assert!(ring.iter().copied().eq([3, 4]));
+}
+
+#[test]
+fn sources_and_adapters_without_std() {
+    assert!(Digits::new(1234, 10).eq([1, 2, 3, 4]));
+    assert_eq!(from_digits([1, 2, 3, 4], 10), Some(1234));
+    assert_eq!(Bits::lsb_first(5u8).filter(|&b| b).count(), 2);
+    assert_eq!(StepCounter::new(0, 10, 5).count(), 2);
+    assert_eq!(Linspace::new(0.0, 1.0, 3).last(), Some(1.0));
+    assert_eq!(iterate(1, |x| x * 3).nth(3), Some(27));
+    assert_eq!(unfold(0, |n| (n < 3).then_some((n, n + 1))).sum::<i32>(), 3);
+
+    let parts: Vec<Vec<i32>> = [1, 0, 2, 3]
+        .into_iter()
+        .split_when_with(|&x| x == 0, Delimiter::Drop)
+        .collect();
+    assert_eq!(parts, [vec![1], vec![2, 3]]);
+    assert!([2, 1, 2].into_iter().unique_ord().eq([2, 1]));
+}
+
+#[test]
+fn iteration_models_without_std() {
+    let total: Result<Vec<i32>, ()> = convert([Ok(1), Ok(2)]).collect();
+    assert_eq!(total, Ok(vec![1, 2]));
+
+    assert_eq!((1..=4).map_internal(|x| x * x).sum_internal::<i32>(), 30);
+
+    let mut v = [1, 1, 1];
+    assert_eq!(windows_mut(&mut v, 2).count(), 2);
+}
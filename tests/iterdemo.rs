@@ -0,0 +1,92 @@
+//! The `iterdemo` subcommands, run through the binary's own `run` with
+//! the input and output in memory.
+
+#![cfg(feature = "std")]
+
+// Only `run` is called from here; the rest is the binary's.
+#[allow(dead_code)]
+#[path = "../src/bin/iterdemo.rs"]
+mod iterdemo;
+
+fn run(args: &str, input: &str) -> Result<String, String> {
+    let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+    let mut out = Vec::new();
+    iterdemo::run(&args, input.as_bytes(), &mut out)?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn passwords_from_the_builder() {
+    let out = run("passwords --count 3 --length 12 --seed 7", "").unwrap();
+    assert_eq!(out.lines().count(), 3);
+    assert!(out.lines().all(|p| p.len() == 12));
+    assert_eq!(
+        out,
+        run("passwords --seed 7 --length 12 --count 3", "").unwrap()
+    );
+
+    let symbols = run("passwords --count 1 --symbols --seed 1", "").unwrap();
+    assert!(symbols
+        .trim_end()
+        .chars()
+        .any(|c| !c.is_ascii_alphanumeric()));
+    assert_eq!(
+        run("passwords --length 2 --symbols", ""),
+        Err("a password of length 2 can't have all 4 kinds of character".into())
+    );
+}
+
+#[test]
+fn primes_below() {
+    let out = run("primes --below 30", "").unwrap();
+    let primes: Vec<u64> = out.lines().map(|l| l.parse().unwrap()).collect();
+    assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert_eq!(run("primes --below 2", "").unwrap(), "");
+    assert_eq!(run("primes", ""), Err("--below is required".into()));
+    assert_eq!(
+        run("primes --below many", ""),
+        Err("--below: `many` is not a valid value".into())
+    );
+}
+
+#[test]
+fn stats_of_standard_input() {
+    let out = run("stats", "4 1\n3 2.5\n\n-0.5").unwrap();
+    assert_eq!(
+        out,
+        "count  5\nsum    10\nmean   2\nmedian 2.5\nmin    -0.5\nmax    4\n"
+    );
+    assert!(run("stats", "1 2 3 4").unwrap().contains("median 2.5\n"));
+    assert_eq!(
+        run("stats", " \n"),
+        Err("no numbers on standard input".into())
+    );
+    assert_eq!(run("stats", "1 two"), Err("`two` is not a number".into()));
+}
+
+#[test]
+fn worm_frames() {
+    let out = run("worm --steps 3 --seed 5", "").unwrap();
+    let frames: Vec<&str> = out.split("\n\n").filter(|f| !f.is_empty()).collect();
+    assert_eq!(frames.len(), 3);
+    for (i, frame) in frames.iter().enumerate() {
+        let mut lines = frame.lines();
+        assert_eq!(lines.next(), Some(format!("turn {}", i + 1).as_str()));
+        assert_eq!(lines.count(), 12);
+        assert_eq!(frame.matches('@').count(), 3);
+    }
+    assert_eq!(out, run("worm --seed 5 --steps 3", "").unwrap());
+}
+
+#[test]
+fn bad_arguments() {
+    assert_eq!(run("", ""), Err("no subcommand".into()));
+    assert_eq!(run("fly", ""), Err("unknown subcommand `fly`".into()));
+    assert_eq!(
+        run("worm --steps 2 --speed 9", ""),
+        Err("unknown option `--speed`".into())
+    );
+    assert_eq!(run("worm --steps", ""), Err("--steps needs a value".into()));
+    assert_eq!(run("primes 10", ""), Err("unexpected argument `10`".into()));
+    assert!(run("help", "").unwrap().starts_with("usage:"));
+}
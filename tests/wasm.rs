@@ -0,0 +1,25 @@
+//! `generatePasswords` in a browser. Run with
+//! `wasm-pack test --headless --firefox -- --features wasm`.
+
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use iterators::wasm::generate_passwords;
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn generate_passwords_in_the_browser() {
+    let passwords = generate_passwords(5, r#"{"length": 12, "symbols": true}"#).unwrap();
+    assert_eq!(passwords.len(), 5);
+    assert!(passwords.iter().all(|p| p.len() == 12));
+    // Unseeded, so two passwords in a row being equal means `getrandom`
+    // isn't wired to the browser.
+    assert_ne!(passwords[0], passwords[1]);
+}
+
+#[wasm_bindgen_test]
+fn generate_passwords_rejects_bad_configs() {
+    assert!(generate_passwords(1, r#"{"length": "long"}"#).is_err());
+    assert!(generate_passwords(1, "not json").is_err());
+}